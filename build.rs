@@ -1,117 +1,606 @@
+use std::collections::{HashMap, HashSet};
 use std::fs;
 
-const JSON_URL: &str =
-    "https://raw.githubusercontent.com/dzcode-io/leblad/master/data/WilayaList.json";
+#[path = "src/overlay/schema.rs"]
+mod schema;
 
-#[derive(serde::Deserialize)]
-pub struct Baladyia {
-    pub code: u16,
-    pub name: String,
-    pub name_en: String,
-    pub name_ar: String,
-}
+use schema::{Baladyia, Daira, Override, Patch, Wilaya};
 
-impl ToString for Baladyia {
-    fn to_string(&self) -> String {
+const JSON_URL: &str =
+    "https://raw.githubusercontent.com/dzcode-io/leblad/{rev}/data/WilayaList.json";
+const COMMIT_API_URL: &str = "https://api.github.com/repos/dzcode-io/leblad/commits/{rev}";
+/// Environment variable pinning the upstream revision (branch, tag or commit) to fetch.
+const DATA_REV_ENV: &str = "LEBLAD_DATA_REV";
+const DEFAULT_DATA_REV: &str = "master";
+/// Environment variable holding the absolute path of a JSON or TOML patch to apply.
+const PATCH_ENV: &str = "LEBLAD_PATCH";
+const COORDINATES_PATH: &str = "./data/coordinates.csv";
+const STATS_PATH: &str = "./data/stats.csv";
+const BOUNDARIES_PATH: &str = "./data/boundaries.geojson";
+/// Tolerance used to simplify boundaries, in degrees (about 100 meters).
+const SIMPLIFY_TOLERANCE: f64 = 0.001;
+
+impl Baladyia {
+    fn format(&self, layers: &Layers) -> String {
         format!(
             r#"Baladyia {{
     code: {},
-    name: "{}",
-    name_en: "{}",
-    name_ar: "{}",
+    name: {:?},
+    name_en: {:?},
+    name_ar: {:?},{}
 }}"#,
-            self.code, self.name, self.name_en, self.name_ar
+            self.code,
+            self.name,
+            self.name_en,
+            self.name_ar,
+            layers.format("baladyia", self.code)
         )
     }
 }
 
-#[derive(serde::Deserialize)]
-pub struct Daira {
+/// Rows of a data file by level and code, with the remaining fields of each row.
+type Rows = HashMap<(String, u16), Vec<String>>;
+
+/// Reads a CSV data file whose rows start with a level and a code.
+fn read_rows(path: &str, columns: usize) -> Result<Rows, Box<dyn std::error::Error>> {
+    let mut rows = HashMap::new();
+    for line in fs::read_to_string(path)?.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("level,") {
+            continue;
+        }
+        let fields = line.split(',').map(str::trim).collect::<Vec<&str>>();
+        if fields.len() != columns {
+            return Err(format!("invalid line in {}: {}", path, line).into());
+        }
+        rows.insert(
+            (fields[0].to_string(), fields[1].parse()?),
+            fields[2..].iter().map(|field| field.to_string()).collect(),
+        );
+    }
+    Ok(rows)
+}
+
+/// Optional data layers, each one adding a field to places behind its feature.
+pub struct Layers {
+    coordinates: Coordinates,
+    stats: Stats,
+}
+
+impl Layers {
+    fn read() -> Result<Layers, Box<dyn std::error::Error>> {
+        Ok(Layers {
+            coordinates: Coordinates::read(COORDINATES_PATH)?,
+            stats: Stats::read(STATS_PATH)?,
+        })
+    }
+
+    /// Formats the fields of a place for every layer.
+    fn format(&self, level: &str, code: u16) -> String {
+        format!(
+            "{}{}",
+            self.coordinates.format(level, code),
+            self.stats.format(level, code)
+        )
+    }
+}
+
+/// Coordinates of places by level and code, read from `data/coordinates.csv`.
+pub struct Coordinates(HashMap<(String, u16), (f64, f64)>);
+
+impl Coordinates {
+    fn read(path: &str) -> Result<Coordinates, Box<dyn std::error::Error>> {
+        let mut coordinates = HashMap::new();
+        for (key, fields) in read_rows(path, 4)? {
+            coordinates.insert(key, (fields[0].parse()?, fields[1].parse()?));
+        }
+        Ok(Coordinates(coordinates))
+    }
+
+    /// Formats the `coordinates` field of a place, which only exists with the `geo` feature.
+    fn format(&self, level: &str, code: u16) -> String {
+        let value = match self.0.get(&(level.to_string(), code)) {
+            Some((latitude, longitude)) => format!(
+                "Some(crate::geo::Coordinates {{ latitude: {:?}, longitude: {:?} }})",
+                latitude, longitude
+            ),
+            None => "None".to_string(),
+        };
+        format!(
+            "\n    #[cfg(feature = \"geo\")]\n    coordinates: {},",
+            value
+        )
+    }
+}
+
+/// Statistics of places by level and code, read from `data/stats.csv`.
+pub struct Stats(HashMap<(String, u16), String>);
+
+impl Stats {
+    fn read(path: &str) -> Result<Stats, Box<dyn std::error::Error>> {
+        /// Formats an optional value, parsed to check it.
+        fn optional<T: std::str::FromStr + std::fmt::Debug>(
+            field: &str,
+        ) -> Result<String, Box<dyn std::error::Error>>
+        where
+            T::Err: std::error::Error + 'static,
+        {
+            Ok(match field {
+                "" => "None".to_string(),
+                value => format!("Some({:?})", value.parse::<T>()?),
+            })
+        }
+        let mut stats = HashMap::new();
+        for (key, fields) in read_rows(path, 6)? {
+            stats.insert(
+                key,
+                format!(
+                    "crate::stats::Stats {{ population_2008: {}, population_estimate: {}, estimate_year: {}, area_km2: {} }}",
+                    optional::<u32>(&fields[0])?,
+                    optional::<u32>(&fields[1])?,
+                    optional::<u16>(&fields[2])?,
+                    optional::<f64>(&fields[3])?,
+                ),
+            );
+        }
+        Ok(Stats(stats))
+    }
+
+    /// Formats the `stats` field of a place, which only exists with the `stats` feature.
+    fn format(&self, level: &str, code: u16) -> String {
+        let value = match self.0.get(&(level.to_string(), code)) {
+            Some(stats) => format!("Some({})", stats),
+            None => "None".to_string(),
+        };
+        format!("\n    #[cfg(feature = \"stats\")]\n    stats: {},", value)
+    }
+}
+
+/// Boundary of a place, read from `data/boundaries.geojson`.
+/// Points are `(longitude, latitude)` pairs as in GeoJSON.
+pub struct Boundary {
+    pub level: String,
+    pub code: u16,
+    pub polygons: Vec<Vec<Vec<(f64, f64)>>>,
+}
+
+impl Boundary {
+    fn read_all(path: &str) -> Result<Vec<Boundary>, Box<dyn std::error::Error>> {
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let mut boundaries = vec![];
+        for feature in json["features"].as_array().into_iter().flatten() {
+            let properties = &feature["properties"];
+            let (Some(level), Some(code)) =
+                (properties["level"].as_str(), properties["code"].as_u64())
+            else {
+                return Err(format!("missing level or code in {}", path).into());
+            };
+            let geometry = &feature["geometry"];
+            let polygons = match geometry["type"].as_str() {
+                Some("Polygon") => vec![serde_json::from_value(geometry["coordinates"].clone())?],
+                Some("MultiPolygon") => serde_json::from_value(geometry["coordinates"].clone())?,
+                _ => return Err(format!("unsupported geometry for {} {}", level, code).into()),
+            };
+            boundaries.push(Boundary {
+                level: level.to_string(),
+                code: u16::try_from(code)?,
+                polygons,
+            });
+        }
+        Ok(boundaries)
+    }
+
+    fn format(&self) -> Option<String> {
+        let level = match self.level.as_str() {
+            "wilaya" => "Wilaya",
+            "daira" => "Daira",
+            "baladyia" => "Baladyia",
+            _ => return None,
+        };
+        let polygons = self
+            .polygons
+            .iter()
+            .map(|rings| {
+                let rings = rings
+                    .iter()
+                    .map(|ring| {
+                        let points = simplify(ring, SIMPLIFY_TOLERANCE)
+                            .iter()
+                            .map(|(longitude, latitude)| {
+                                format!(
+                                    "crate::geo::Coordinates::new({:?}, {:?})",
+                                    latitude, longitude
+                                )
+                            })
+                            .collect::<Vec<String>>();
+                        format!("&[{}]", points.join(", "))
+                    })
+                    .collect::<Vec<String>>();
+                format!("&[{}]", rings.join(", "))
+            })
+            .collect::<Vec<String>>();
+        Some(format!(
+            "crate::geo::Boundary {{ level: crate::Level::{}, code: {}, polygons: &[{}] }}",
+            level,
+            self.code,
+            polygons.join(", ")
+        ))
+    }
+}
+
+/// Simplifies a ring with the Ramer-Douglas-Peucker algorithm.
+fn simplify(points: &[(f64, f64)], tolerance: f64) -> Vec<(f64, f64)> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let (index, distance) = points[1..points.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(i, point)| (i + 1, segment_distance(*point, first, last)))
+        .fold(
+            (0, 0.0),
+            |max, current| if current.1 > max.1 { current } else { max },
+        );
+    if distance <= tolerance {
+        return vec![first, last];
+    }
+    let mut simplified = simplify(&points[..=index], tolerance);
+    simplified.pop();
+    simplified.extend(simplify(&points[index..], tolerance));
+    simplified
+}
+
+/// Distance from a point to the segment between `a` and `b`.
+fn segment_distance(point: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length = dx * dx + dy * dy;
+    let t = if length == 0.0 {
+        0.0
+    } else {
+        (((point.0 - a.0) * dx + (point.1 - a.1) * dy) / length).clamp(0.0, 1.0)
+    };
+    let (x, y) = (a.0 + t * dx, a.1 + t * dy);
+    ((point.0 - x).powi(2) + (point.1 - y).powi(2)).sqrt()
+}
+
+pub struct Const {
     pub code: u16,
     pub name: String,
-    pub name_ar: String,
-    pub name_en: String,
-    pub baladyiats: Option<Vec<Baladyia>>,
+    pub format: String,
+}
+
+/// Joins a list of items with `, `.
+fn join<T: ToString>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
 }
 
-impl ToString for Daira {
-    fn to_string(&self) -> String {
-        let mut s = format!(
-            r#"Daira {{
+/// Formats a daira whose baladyiats are referenced by their constant names.
+fn format_daira(daira: &Daira, baladyiats: Option<&[String]>, layers: &Layers) -> String {
+    let baladyiats = match baladyiats {
+        Some(names) => format!("Some(&[{}])", names.join(", ")),
+        None => "None".to_string(),
+    };
+    format!(
+        r#"Daira {{
     code: {},
-    name: "{}",
-    name_ar: "{}",
-    name_en: "{}","#,
-            self.code, self.name, self.name_ar, self.name_en
-        );
-        if self.baladyiats.is_none() {
-            s.push_str(
-                r#"
-    baladyiats: None,
-}"#,
-            );
-        } else {
-            let baladyiats = self.baladyiats.as_ref().unwrap();
-            s.push_str(
-                format!(
-                    r#"
-    baladyiats: Some(&[{}]),
+    name: {:?},
+    name_ar: {:?},
+    name_en: {:?},
+    baladyiats: {},{}
 }}"#,
-                    baladyiats
-                        .iter()
-                        .map(|e| e.to_string())
-                        .collect::<Vec<String>>()
-                        .join(", ")
-                )
-                .as_str(),
+        daira.code,
+        daira.name,
+        daira.name_ar,
+        daira.name_en,
+        baladyiats,
+        layers.format("daira", daira.code)
+    )
+}
+
+/// Generates a table of constants sorted by code, so const code can binary search it.
+/// The sort is stable, so places sharing a code keep their dataset order.
+fn format_index(name: &str, ty: &str, consts: &[Const]) -> String {
+    let mut sorted = consts.iter().collect::<Vec<&Const>>();
+    sorted.sort_by_key(|c| c.code);
+    format!(
+        "\npub(crate) const {}: &[{}] = &[{}];\n",
+        name,
+        ty,
+        sorted
+            .iter()
+            .map(|c| c.name.clone())
+            .collect::<Vec<String>>()
+            .join(", ")
+    )
+}
+
+/// Hashes the fetched data with 64 bits FNV-1a, which is enough to tell snapshots apart.
+fn content_hash(data: &[u8]) -> String {
+    let hash = data.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Seconds since the UNIX epoch, honoring `SOURCE_DATE_EPOCH` for reproducible builds.
+fn fetch_time() -> u64 {
+    std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse().ok())
+        .unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |time| time.as_secs())
+        })
+}
+
+/// Applies the patch pointed to by `LEBLAD_PATCH`, if any.
+fn apply_patch(wilayas: &mut [Wilaya]) -> Result<Vec<Override>, Box<dyn std::error::Error>> {
+    let Ok(path) = std::env::var(PATCH_ENV) else {
+        return Ok(vec![]);
+    };
+    println!("cargo:rerun-if-changed={}", path);
+    let source = fs::read_to_string(&path)?;
+    let patch = if path.ends_with(".toml") {
+        Patch::from_toml(&source)
+    } else {
+        Patch::from_json(&source)
+    };
+    let overrides = patch
+        .and_then(|patch| patch.apply(wilayas))
+        .unwrap_or_else(|e| panic!("invalid patch {}: {}", path, e));
+    Ok(overrides)
+}
+
+fn format_dataset_info(
+    url: &str,
+    rev: &str,
+    data: &str,
+    wilayas: usize,
+    dairats: usize,
+    baladyiats: usize,
+    overrides: &[Override],
+) -> String {
+    let overrides = overrides
+        .iter()
+        .map(|(level, code, field)| {
+            let level = match *level {
+                "wilaya" => "Wilaya",
+                "daira" => "Daira",
+                "baladyia" => "Baladyia",
+                level => panic!("unknown level `{}` in the patch overrides", level),
+            };
+            format!(
+                "crate::Override {{ level: crate::Level::{}, code: {}, field: {:?} }}",
+                level, code, field
             )
+        })
+        .collect::<Vec<String>>();
+    format!(
+        r#"
+pub(crate) const DATASET_INFO: crate::DatasetInfo = crate::DatasetInfo {{
+    source_url: {:?},
+    revision: {:?},
+    content_hash: "{}",
+    fetched_at: {},
+    wilayas: {},
+    dairats: {},
+    baladyiats: {},
+    overrides: &[{}],
+}};
+"#,
+        url,
+        rev,
+        content_hash(data.as_bytes()),
+        fetch_time(),
+        wilayas,
+        dairats,
+        baladyiats,
+        overrides.join(", ")
+    )
+}
+
+/// Generates the hidden macros behind `wilaya!` and `baladyia!`.
+/// Every known code or name gets its own arm, anything else is a compile error.
+fn format_macros(wilayas: &[Wilaya], wilaya_consts: &[Const], baladyia_consts: &[Const]) -> String {
+    let mut wilaya_arms = vec![];
+    let mut seen_names = HashSet::new();
+    for (wilaya, c) in wilayas.iter().zip(wilaya_consts) {
+        wilaya_arms.push(format!(
+            "    ({}) => {{ &$crate::__private::{} }};",
+            wilaya.mattricule, c.name
+        ));
+        // Macro arms match tokens, so the zero-padded form `01` needs its own arm.
+        if wilaya.mattricule < 10 {
+            wilaya_arms.push(format!(
+                "    (0{}) => {{ &$crate::__private::{} }};",
+                wilaya.mattricule, c.name
+            ));
+        }
+        if seen_names.insert(wilaya.name.as_str()) {
+            wilaya_arms.push(format!(
+                "    ({:?}) => {{ &$crate::__private::{} }};",
+                wilaya.name, c.name
+            ));
+        }
+    }
+
+    let mut baladyia_arms = vec![];
+    let mut seen_codes = HashSet::new();
+    let baladyias = wilayas
+        .iter()
+        .flat_map(|wilaya| wilaya.dairats.iter())
+        .flat_map(|daira| daira.baladyiats.iter().flatten());
+    for (baladyia, c) in baladyias.zip(baladyia_consts) {
+        // The first baladyia with a code wins, as with `get_baladyia_by_code`.
+        if seen_codes.insert(baladyia.code) {
+            baladyia_arms.push(format!(
+                "    ({}) => {{ &$crate::__private::{} }};",
+                baladyia.code, c.name
+            ));
+            if baladyia.code < 1000 {
+                baladyia_arms.push(format!(
+                    "    (0{}) => {{ &$crate::__private::{} }};",
+                    baladyia.code, c.name
+                ));
+            }
         }
-        s
     }
+
+    format!(
+        r#"
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __wilaya {{
+{}
+    ($other:tt) => {{ compile_error!(concat!("unknown wilaya: ", stringify!($other))) }};
+}}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __baladyia {{
+{}
+    ($other:tt) => {{ compile_error!(concat!("unknown baladyia: ", stringify!($other))) }};
+}}
+"#,
+        wilaya_arms.join("\n"),
+        baladyia_arms.join("\n")
+    )
 }
 
-#[derive(serde::Deserialize)]
-pub struct Wilaya {
-    pub mattricule: u16,
-    pub name_ar: String,
-    pub name_ber: String,
-    pub name_en: String,
-    pub name: String,
-    #[serde(rename = "phoneCodes")]
-    pub phone_codes: Vec<u16>,
-    #[serde(rename = "postalCodes")]
-    pub postal_codes: Vec<u16>,
-    pub dairats: Vec<Daira>,
-    #[serde(rename = "adjacentWilayas")]
-    pub adjacent_wilayas: Vec<u16>,
+/// Generates the gRPC messages and service from `proto/leblad.proto`, using the vendored
+/// `protoc` unless `PROTOC` points to another one.
+#[cfg(feature = "grpc")]
+fn compile_protos() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=proto");
+    println!("cargo:rerun-if-env-changed=PROTOC");
+    if std::env::var_os("PROTOC").is_none() {
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    }
+    tonic_prost_build::compile_protos("proto/leblad.proto")?;
+    Ok(())
 }
 
-pub struct Const {
-    pub name: String,
-    pub format: String,
+/// Generates the C header of the `ffi` module into `OUT_DIR`, configured by `cbindgen.toml`.
+/// `tests/ffi.rs` checks that `include/leblad.h` is up to date with it.
+#[cfg(feature = "ffi")]
+fn generate_header() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    let config = cbindgen::Config::from_file("cbindgen.toml")?;
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src("src/ffi.rs")
+        .generate()?
+        .write_to_file(std::path::Path::new(&std::env::var("OUT_DIR")?).join("leblad.h"));
+    Ok(())
+}
+
+/// Resolves a branch, tag or commit of the upstream repository to the full SHA of its commit.
+async fn resolve_revision(rev: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let is_sha = |rev: &str| rev.len() == 40 && rev.bytes().all(|b| b.is_ascii_hexdigit());
+    if is_sha(rev) {
+        return Ok(rev.to_ascii_lowercase());
+    }
+    let sha = reqwest::Client::new()
+        .get(COMMIT_API_URL.replace("{rev}", rev))
+        .header("Accept", "application/vnd.github.sha")
+        .header("User-Agent", "leblad-build")
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    if !is_sha(sha.trim()) {
+        return Err(format!("cannot resolve revision `{}`: got `{}`", rev, sha).into());
+    }
+    Ok(sha.trim().to_string())
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    match reqwest::get(JSON_URL).await {
+    #[cfg(feature = "grpc")]
+    compile_protos()?;
+    #[cfg(feature = "ffi")]
+    generate_header()?;
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=data");
+    println!("cargo:rerun-if-env-changed={}", DATA_REV_ENV);
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    println!("cargo:rerun-if-env-changed={}", PATCH_ENV);
+    let rev = std::env::var(DATA_REV_ENV).unwrap_or_else(|_| DEFAULT_DATA_REV.to_string());
+    // Fetch the data at the resolved commit, so the recorded revision is what was fetched. The
+    // GitHub API is rate limited, so the ref itself is fetched and recorded when it fails.
+    let rev = match resolve_revision(&rev).await {
+        Ok(sha) => sha,
+        Err(error) => {
+            println!("cargo:warning=recording `{}` as the data revision: {}", rev, error);
+            rev
+        }
+    };
+    let url = JSON_URL.replace("{rev}", &rev);
+    match reqwest::get(&url).await {
         Ok(res) => {
-            let json: serde_json::Value = res.json().await?;
-            let wilayas = serde_json::from_value::<Vec<Wilaya>>(json).unwrap();
+            let data = res.text().await?;
+            let mut wilayas = serde_json::from_str::<Vec<Wilaya>>(&data).unwrap();
+            let overrides = apply_patch(&mut wilayas)?;
+            let layers = Layers::read()?;
+            let boundaries = Boundary::read_all(BOUNDARIES_PATH)?;
             std::fs::create_dir_all("./src/_auto_generated")?;
             let mut consts: Vec<Const> = vec![];
+            let mut daira_consts: Vec<Const> = vec![];
+            let mut baladyia_consts: Vec<Const> = vec![];
             for (i, wilaya) in wilayas.iter().enumerate() {
                 let name = format!("W{}", i + 1);
+                let mut dairats = vec![];
+                for daira in wilaya.dairats.iter() {
+                    let baladyiats = daira.baladyiats.as_ref().map(|baladyiats| {
+                        baladyiats
+                            .iter()
+                            .map(|baladyia| {
+                                let name = format!("B{}", baladyia_consts.len() + 1);
+                                baladyia_consts.push(Const {
+                                    code: baladyia.code,
+                                    name: name.clone(),
+                                    format: format!(
+                                        "#[doc(hidden)]\npub const {}: Baladyia = {};\n",
+                                        name,
+                                        baladyia.format(&layers)
+                                    ),
+                                });
+                                name
+                            })
+                            .collect::<Vec<String>>()
+                    });
+                    let name = format!("D{}", daira_consts.len() + 1);
+                    daira_consts.push(Const {
+                        code: daira.code,
+                        name: name.clone(),
+                        format: format!(
+                            "#[doc(hidden)]\npub const {}: Daira = {};\n",
+                            name,
+                            format_daira(daira, baladyiats.as_deref(), &layers)
+                        ),
+                    });
+                    dairats.push(name);
+                }
                 let format = format!(
-                    r#"const W{}: Wilaya = Wilaya {{
+                    r#"#[doc(hidden)]
+pub const W{}: Wilaya = Wilaya {{
     mattricule: {},
-    name_ar: "{}",
-    name_ber: "{}",
-    name_en: "{}",
-    name: "{}",
+    name_ar: {:?},
+    name_ber: {:?},
+    name_en: {:?},
+    name: {:?},
     phone_codes: &[{}],
     postal_codes: &[{}],
     dairats: &[{}],
-    adjacent_wilayas: &[{}],
+    adjacent_wilayas: &[{}],{}
 }};
     "#,
                     i + 1,
@@ -120,32 +609,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     wilaya.name_ber,
                     wilaya.name_en,
                     wilaya.name,
-                    wilaya
-                        .phone_codes
-                        .iter()
-                        .map(|e| e.to_string())
-                        .collect::<Vec<String>>()
-                        .join(", "),
-                    wilaya
-                        .postal_codes
-                        .iter()
-                        .map(|e| e.to_string())
-                        .collect::<Vec<String>>()
-                        .join(", "),
-                    wilaya
-                        .dairats
-                        .iter()
-                        .map(|e| e.to_string())
-                        .collect::<Vec<String>>()
-                        .join(", "),
-                    wilaya
-                        .adjacent_wilayas
-                        .iter()
-                        .map(|e| e.to_string())
-                        .collect::<Vec<String>>()
-                        .join(", "),
+                    join(&wilaya.phone_codes),
+                    join(&wilaya.postal_codes),
+                    dairats.join(", "),
+                    join(&wilaya.adjacent_wilayas),
+                    layers.format("wilaya", wilaya.mattricule),
                 );
-                consts.push(Const { name, format });
+                consts.push(Const {
+                    code: wilaya.mattricule,
+                    name,
+                    format,
+                });
             }
             let mut s = r#"// This is auto-generated. Do not edit manually.
 
@@ -172,6 +646,12 @@ pub struct Wilaya {
     pub dairats: &'static[Daira],
     /// Wilaya's adjacent wilayas.
     pub adjacent_wilayas: &'static[u16],
+    /// Wilaya's reference point, which is the location of its seat.
+    #[cfg(feature = "geo")]
+    pub coordinates: Option<crate::geo::Coordinates>,
+    /// Wilaya's own statistics, see `leblad::stats::wilaya_stats` for aggregated ones.
+    #[cfg(feature = "stats")]
+    pub stats: Option<crate::stats::Stats>,
 }
 
 /// Daira struct.
@@ -189,6 +669,12 @@ pub struct Daira {
     pub name_en: &'static str,
     /// Daira's baladyiats.
     pub baladyiats: Option<&'static[Baladyia]>,
+    /// Daira's reference point, which is the location of its seat.
+    #[cfg(feature = "geo")]
+    pub coordinates: Option<crate::geo::Coordinates>,
+    /// Daira's own statistics, see `leblad::stats::daira_stats` for aggregated ones.
+    #[cfg(feature = "stats")]
+    pub stats: Option<crate::stats::Stats>,
 }
 
 /// Baladyia struct.
@@ -204,9 +690,33 @@ pub struct Baladyia {
     pub name_en: &'static str,
     /// Baladyia's name in Arabic.
     pub name_ar: &'static str,
+    /// Baladyia's reference point.
+    #[cfg(feature = "geo")]
+    pub coordinates: Option<crate::geo::Coordinates>,
+    /// Baladyia's statistics.
+    #[cfg(feature = "stats")]
+    pub stats: Option<crate::stats::Stats>,
 }
 "#
             .to_string();
+            s.push_str(
+                baladyia_consts
+                    .iter()
+                    .map(|c| c.format.clone())
+                    .collect::<Vec<String>>()
+                    .join("\n")
+                    .as_str(),
+            );
+            s.push('\n');
+            s.push_str(
+                daira_consts
+                    .iter()
+                    .map(|c| c.format.clone())
+                    .collect::<Vec<String>>()
+                    .join("\n")
+                    .as_str(),
+            );
+            s.push('\n');
             s.push_str(
                 consts
                     .iter()
@@ -226,6 +736,33 @@ pub struct Baladyia {
                 )
                 .as_str(),
             );
+            s.push_str(
+                format!(
+                    "\n#[cfg(feature = \"geo\")]\npub(crate) const BOUNDARIES: &[crate::geo::Boundary] = &[{}];\n",
+                    boundaries
+                        .iter()
+                        .filter_map(|boundary| boundary.format())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )
+                .as_str(),
+            );
+            s.push_str(&format_index("ALL_DAIRATS", "Daira", &daira_consts));
+            s.push_str(&format_index(
+                "ALL_BALADYIATS",
+                "Baladyia",
+                &baladyia_consts,
+            ));
+            s.push_str(&format_macros(&wilayas, &consts, &baladyia_consts));
+            s.push_str(&format_dataset_info(
+                &url,
+                &rev,
+                &data,
+                consts.len(),
+                daira_consts.len(),
+                baladyia_consts.len(),
+                &overrides,
+            ));
             fs::write("./src/_auto_generated/mod.rs", s)?;
         }
         Err(_) => panic!("Data was not received"),
@@ -0,0 +1,278 @@
+#ifndef LEBLAD_H
+#define LEBLAD_H
+
+/* Generated by cbindgen from src/ffi.rs, do not edit. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+/* Places of the embedded dataset, only handled through pointers. */
+typedef struct LebladWilaya LebladWilaya;
+typedef struct LebladDaira LebladDaira;
+typedef struct LebladBaladyia LebladBaladyia;
+
+// Level of a place.
+typedef enum LebladLevel {
+  // A wilaya.
+  LEBLAD_LEVEL_WILAYA = 0,
+  // A daira.
+  LEBLAD_LEVEL_DAIRA = 1,
+  // A baladyia.
+  LEBLAD_LEVEL_BALADYIA = 2,
+} LebladLevel;
+
+// UTF-8 string of the dataset, not NUL-terminated, e.g. printed with `printf("%.*s", (int)s.len, s.ptr)`.
+typedef struct LebladStr {
+  // First byte of the string, never NULL.
+  const uint8_t *ptr;
+  // Length of the string, in bytes.
+  size_t len;
+} LebladStr;
+
+// List of codes of the dataset.
+typedef struct LebladCodes {
+  // First code of the list, never NULL.
+  const uint16_t *ptr;
+  // Number of codes.
+  size_t len;
+} LebladCodes;
+
+// A place found by a search.
+typedef struct LebladPlace {
+  // Level of the place.
+  enum LebladLevel level;
+  // Code of the place, which is the mattricule for wilayas.
+  uint16_t code;
+} LebladPlace;
+
+// Places found by a search, to free with `leblad_places_free`.
+typedef struct LebladPlaces {
+  // First place, NULL when there are none.
+  struct LebladPlace *ptr;
+  // Number of places.
+  size_t len;
+} LebladPlaces;
+
+// Get the number of wilayas.
+size_t leblad_wilaya_count(void);
+
+// Get a wilaya by index, from 0 to `leblad_wilaya_count() - 1`, or NULL.
+const LebladWilaya *leblad_wilaya_at(size_t index);
+
+// Get a wilaya by mattricule, or NULL.
+const LebladWilaya *leblad_wilaya_by_code(uint16_t mattricule);
+
+// Get a wilaya by postal code, or NULL.
+const LebladWilaya *leblad_wilaya_by_zip_code(uint16_t zip_code);
+
+// Get a wilaya by phone code, or NULL.
+const LebladWilaya *leblad_wilaya_by_phone_code(uint16_t phone_code);
+
+// Get a wilaya by name in any language, ignoring case and accents, or NULL.
+//
+// # Safety
+//
+// `name` must be NULL or a NUL-terminated string.
+const LebladWilaya *leblad_wilaya_by_name(const char *name);
+
+// Get the mattricule of a wilaya, or 0.
+//
+// # Safety
+//
+// `wilaya` must be NULL or a wilaya returned by this library.
+uint16_t leblad_wilaya_code(const LebladWilaya *wilaya);
+
+// Get the name of a wilaya.
+//
+// # Safety
+//
+// `wilaya` must be NULL or a wilaya returned by this library.
+struct LebladStr leblad_wilaya_name(const LebladWilaya *wilaya);
+
+// Get the Arabic name of a wilaya.
+//
+// # Safety
+//
+// `wilaya` must be NULL or a wilaya returned by this library.
+struct LebladStr leblad_wilaya_name_ar(const LebladWilaya *wilaya);
+
+// Get the Berber name of a wilaya, in Tifinagh.
+//
+// # Safety
+//
+// `wilaya` must be NULL or a wilaya returned by this library.
+struct LebladStr leblad_wilaya_name_ber(const LebladWilaya *wilaya);
+
+// Get the English name of a wilaya.
+//
+// # Safety
+//
+// `wilaya` must be NULL or a wilaya returned by this library.
+struct LebladStr leblad_wilaya_name_en(const LebladWilaya *wilaya);
+
+// Get the phone codes of a wilaya.
+//
+// # Safety
+//
+// `wilaya` must be NULL or a wilaya returned by this library.
+struct LebladCodes leblad_wilaya_phone_codes(const LebladWilaya *wilaya);
+
+// Get the postal codes of a wilaya.
+//
+// # Safety
+//
+// `wilaya` must be NULL or a wilaya returned by this library.
+struct LebladCodes leblad_wilaya_postal_codes(const LebladWilaya *wilaya);
+
+// Get the mattricules of the wilayas adjacent to a wilaya.
+//
+// # Safety
+//
+// `wilaya` must be NULL or a wilaya returned by this library.
+struct LebladCodes leblad_wilaya_adjacent_wilayas(const LebladWilaya *wilaya);
+
+// Get the number of dairats of a wilaya.
+//
+// # Safety
+//
+// `wilaya` must be NULL or a wilaya returned by this library.
+size_t leblad_wilaya_daira_count(const LebladWilaya *wilaya);
+
+// Get a daira of a wilaya by index, from 0 to `leblad_wilaya_daira_count(wilaya) - 1`, or
+// NULL.
+//
+// # Safety
+//
+// `wilaya` must be NULL or a wilaya returned by this library.
+const LebladDaira *leblad_wilaya_daira_at(const LebladWilaya *wilaya, size_t index);
+
+// Get a daira by code, or NULL.
+const LebladDaira *leblad_daira_by_code(uint16_t code);
+
+// Get a daira by name in any language, ignoring case and accents, or NULL.
+//
+// # Safety
+//
+// `name` must be NULL or a NUL-terminated string.
+const LebladDaira *leblad_daira_by_name(const char *name);
+
+// Get the code of a daira, or 0.
+//
+// # Safety
+//
+// `daira` must be NULL or a daira returned by this library.
+uint16_t leblad_daira_code(const LebladDaira *daira);
+
+// Get the wilaya of a daira, or NULL.
+//
+// # Safety
+//
+// `daira` must be NULL or a daira returned by this library.
+const LebladWilaya *leblad_daira_wilaya(const LebladDaira *daira);
+
+// Get the name of a daira.
+//
+// # Safety
+//
+// `daira` must be NULL or a daira returned by this library.
+struct LebladStr leblad_daira_name(const LebladDaira *daira);
+
+// Get the Arabic name of a daira.
+//
+// # Safety
+//
+// `daira` must be NULL or a daira returned by this library.
+struct LebladStr leblad_daira_name_ar(const LebladDaira *daira);
+
+// Get the English name of a daira.
+//
+// # Safety
+//
+// `daira` must be NULL or a daira returned by this library.
+struct LebladStr leblad_daira_name_en(const LebladDaira *daira);
+
+// Get the number of baladyiats of a daira, which is 0 when they are unknown.
+//
+// # Safety
+//
+// `daira` must be NULL or a daira returned by this library.
+size_t leblad_daira_baladyia_count(const LebladDaira *daira);
+
+// Get a baladyia of a daira by index, from 0 to `leblad_daira_baladyia_count(daira) - 1`,
+// or NULL.
+//
+// # Safety
+//
+// `daira` must be NULL or a daira returned by this library.
+const LebladBaladyia *leblad_daira_baladyia_at(const LebladDaira *daira, size_t index);
+
+// Get a baladyia by code, or NULL.
+const LebladBaladyia *leblad_baladyia_by_code(uint16_t code);
+
+// Get a baladyia by name in any language, ignoring case and accents, or NULL.
+//
+// # Safety
+//
+// `name` must be NULL or a NUL-terminated string.
+const LebladBaladyia *leblad_baladyia_by_name(const char *name);
+
+// Get the code of a baladyia, or 0.
+//
+// # Safety
+//
+// `baladyia` must be NULL or a baladyia returned by this library.
+uint16_t leblad_baladyia_code(const LebladBaladyia *baladyia);
+
+// Get the daira of a baladyia, or NULL.
+//
+// # Safety
+//
+// `baladyia` must be NULL or a baladyia returned by this library.
+const LebladDaira *leblad_baladyia_daira(const LebladBaladyia *baladyia);
+
+// Get the name of a baladyia.
+//
+// # Safety
+//
+// `baladyia` must be NULL or a baladyia returned by this library.
+struct LebladStr leblad_baladyia_name(const LebladBaladyia *baladyia);
+
+// Get the Arabic name of a baladyia.
+//
+// # Safety
+//
+// `baladyia` must be NULL or a baladyia returned by this library.
+struct LebladStr leblad_baladyia_name_ar(const LebladBaladyia *baladyia);
+
+// Get the English name of a baladyia.
+//
+// # Safety
+//
+// `baladyia` must be NULL or a baladyia returned by this library.
+struct LebladStr leblad_baladyia_name_en(const LebladBaladyia *baladyia);
+
+// Search places of every level by name or code, best matches first, keeping at most `limit`
+// results. The results must be freed with `leblad_places_free`.
+//
+// # Safety
+//
+// `query` must be NULL or a NUL-terminated string.
+struct LebladPlaces leblad_search(const char *query, size_t limit);
+
+// Search places of one level by name or code, see `leblad_search`. `level` is a
+// `LebladLevel`, and other values find no places.
+//
+// # Safety
+//
+// `query` must be NULL or a NUL-terminated string.
+struct LebladPlaces leblad_search_level(uint32_t level, const char *query, size_t limit);
+
+// Free the results of a search.
+//
+// # Safety
+//
+// `places` must be returned by `leblad_search` or `leblad_search_level`, and not be freed
+// already.
+void leblad_places_free(struct LebladPlaces places);
+
+#endif  /* LEBLAD_H */
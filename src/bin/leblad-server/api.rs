@@ -0,0 +1,288 @@
+//! Routes of the REST API, mirroring the upstream leblad API.
+
+use serde_json::{json, Value};
+
+use leblad::{
+    get_baladyiats_for_daira_code, get_daira_by_code, get_wilaya_by_code, get_wilaya_by_phone_code,
+    get_wilaya_by_zip_code, get_wilaya_list, search, search_level, Baladyia, Daira, Level, Place,
+    Wilaya,
+};
+
+/// Maximum number of search results when the `limit` parameter is missing.
+const SEARCH_LIMIT: usize = 20;
+
+/// A JSON response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    fn ok(value: Value) -> Response {
+        Response {
+            status: 200,
+            body: value.to_string(),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Response {
+        Response {
+            status,
+            body: json!({ "error": message }).to_string(),
+        }
+    }
+
+    fn found(value: Option<Value>, what: &str) -> Response {
+        match value {
+            Some(value) => Response::ok(value),
+            None => Response::error(404, &format!("{} not found", what)),
+        }
+    }
+
+    /// Entity tag of the body, a quoted FNV-1a hash.
+    pub fn etag(&self) -> String {
+        let hash = self
+            .body
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            });
+        format!("\"{:016x}\"", hash)
+    }
+}
+
+/// Answers a `GET` request for a URL, made of a path and an optional query string.
+pub fn route(url: &str) -> Response {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<&str>>();
+    let code = |segment: &str| segment.parse::<u16>().ok();
+    match segments.as_slice() {
+        ["wilayas"] => Response::ok(get_wilaya_list().iter().map(wilaya).collect()),
+        ["wilayas", mattricule] => {
+            let wilaya = code(mattricule).and_then(get_wilaya_by_code);
+            Response::found(wilaya.map(wilaya_with_dairats), "wilaya")
+        }
+        ["wilayas", mattricule, "dairats"] => {
+            let wilaya = code(mattricule).and_then(get_wilaya_by_code);
+            let dairats = wilaya.map(|w| w.dairats.iter().map(daira).collect());
+            Response::found(dairats, "wilaya")
+        }
+        ["dairats", daira_code, "baladyiats"] => {
+            let daira_code = code(daira_code).filter(|c| get_daira_by_code(*c).is_some());
+            let baladyiats = daira_code.map(|c| {
+                let baladyiats = get_baladyiats_for_daira_code(c).unwrap_or_default();
+                baladyiats.iter().map(baladyia).collect()
+            });
+            Response::found(baladyiats, "daira")
+        }
+        ["zip", zip_code] => {
+            let wilaya = code(zip_code).and_then(get_wilaya_by_zip_code);
+            Response::found(wilaya.map(wilaya_with_dairats), "postal code")
+        }
+        ["phone", phone_code] => {
+            let wilaya = code(phone_code).and_then(get_wilaya_by_phone_code);
+            Response::found(wilaya.map(wilaya_with_dairats), "phone code")
+        }
+        ["search"] => search_places(query),
+        _ => Response::error(404, "no such route"),
+    }
+}
+
+/// Searches places with the `q`, `level` and `limit` parameters of a query string.
+fn search_places(query: &str) -> Response {
+    let mut q = None;
+    let mut level = None;
+    let mut limit = SEARCH_LIMIT;
+    for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        let Some(value) = decode(value) else {
+            return Response::error(400, &format!("invalid encoding of `{}`", key));
+        };
+        match key {
+            "q" => q = Some(value),
+            "level" => match Level::parse(&value) {
+                Some(parsed) => level = Some(parsed),
+                None => return Response::error(400, &format!("unknown level `{}`", value)),
+            },
+            "limit" => match value.parse() {
+                Ok(parsed) => limit = parsed,
+                Err(_) => return Response::error(400, &format!("invalid limit `{}`", value)),
+            },
+            _ => {}
+        }
+    }
+    let Some(q) = q.filter(|q| !q.trim().is_empty()) else {
+        return Response::error(400, "missing `q` parameter");
+    };
+    let places = match level {
+        Some(level) => search_level(level, &q),
+        None => search(&q),
+    };
+    Response::ok(places.iter().take(limit).map(place).collect())
+}
+
+/// Decodes a percent-encoded query string value, where `+` stands for a space.
+fn decode(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+                rest = &tail[2..];
+                continue;
+            }
+            byte => bytes.push(byte),
+        }
+        rest = tail;
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn wilaya(wilaya: &Wilaya) -> Value {
+    json!({
+        "mattricule": wilaya.mattricule,
+        "name": wilaya.name,
+        "name_ar": wilaya.name_ar,
+        "name_ber": wilaya.name_ber,
+        "name_en": wilaya.name_en,
+        "phone_codes": wilaya.phone_codes,
+        "postal_codes": wilaya.postal_codes,
+        "adjacent_wilayas": wilaya.adjacent_wilayas,
+    })
+}
+
+fn wilaya_with_dairats(w: &Wilaya) -> Value {
+    let mut value = wilaya(w);
+    value["dairats"] = w.dairats.iter().map(daira).collect();
+    value
+}
+
+fn daira(daira: &Daira) -> Value {
+    json!({
+        "code": daira.code,
+        "name": daira.name,
+        "name_ar": daira.name_ar,
+        "name_en": daira.name_en,
+    })
+}
+
+fn baladyia(baladyia: &Baladyia) -> Value {
+    json!({
+        "code": baladyia.code,
+        "name": baladyia.name,
+        "name_ar": baladyia.name_ar,
+        "name_en": baladyia.name_en,
+    })
+}
+
+fn place(place: &Place) -> Value {
+    json!({
+        "level": place.level().as_str(),
+        "code": place.code(),
+        "name": place.name(),
+        "name_ar": place.name_ar(),
+        "name_en": place.name_en(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get(url: &str) -> (u16, Value) {
+        let response = route(url);
+        (
+            response.status,
+            serde_json::from_str(&response.body).unwrap(),
+        )
+    }
+
+    #[test]
+    fn wilayas() {
+        let (status, wilayas) = get("/wilayas");
+        assert_eq!(status, 200);
+        assert_eq!(wilayas.as_array().unwrap().len(), get_wilaya_list().len());
+        assert!(wilayas[0].get("dairats").is_none());
+
+        let (status, adrar) = get("/wilayas/01");
+        assert_eq!(status, 200);
+        assert_eq!(adrar["name"], "Adrar");
+        assert_eq!(adrar["dairats"][0]["code"], 101);
+
+        let (_, dairats) = get("/wilayas/1/dairats/");
+        assert_eq!(dairats, adrar["dairats"]);
+        assert_eq!(get("/wilayas/99").0, 404);
+        assert_eq!(get("/wilayas/x/dairats").0, 404);
+    }
+
+    #[test]
+    fn baladyiats() {
+        let (status, baladyiats) = get("/dairats/101/baladyiats");
+        assert_eq!(status, 200);
+        assert_eq!(baladyiats[0]["code"], 101);
+        assert_eq!(baladyiats[0]["name"], "ADRAR");
+        assert!(baladyiats
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|baladyia| baladyia["name"] == "OULED AHMED TIMMI"));
+        assert_eq!(get("/dairats/9999/baladyiats").0, 404);
+    }
+
+    #[test]
+    fn zip_and_phone_codes() {
+        assert_eq!(get("/zip/1000").1["mattricule"], 1);
+        assert_eq!(get("/zip/1").0, 404);
+        let phone_code = get_wilaya_by_code(1).unwrap().phone_codes[0];
+        assert_eq!(get(&format!("/phone/{}", phone_code)).1["mattricule"], 1);
+        assert_eq!(get("/phone/0").0, 404);
+    }
+
+    #[test]
+    fn search_parameters() {
+        let (status, results) = get("/search?q=ouled+ahmed+timmi");
+        assert_eq!(status, 200);
+        assert_eq!(results[0]["name"], "OULED AHMED TIMMI");
+        assert_eq!(results[0]["level"], "baladyia");
+
+        let (_, results) = get("/search?q=%D8%A3%D8%AF%D8%B1%D8%A7%D8%B1&level=wilaya");
+        assert_eq!(
+            results,
+            json!([place(&Place::Wilaya(get_wilaya_by_code(1).unwrap()))])
+        );
+        assert_eq!(
+            get("/search?q=adrar&limit=1").1.as_array().unwrap().len(),
+            1
+        );
+        assert_eq!(get("/search?q=zzzz"), (200, json!([])));
+
+        for url in [
+            "/search",
+            "/search?q=",
+            "/search?q=%ZZ",
+            "/search?q=a&level=x",
+        ] {
+            assert_eq!(get(url).0, 400, "{}", url);
+        }
+    }
+
+    #[test]
+    fn unknown_routes() {
+        assert_eq!(get("/").0, 404);
+        assert_eq!(get("/communes").1["error"], "no such route");
+    }
+
+    #[test]
+    fn etags_depend_on_the_body() {
+        let etag = route("/wilayas/1").etag();
+        assert_eq!(etag.len(), 18);
+        assert_eq!(etag, route("/wilayas/01").etag());
+        assert_ne!(etag, route("/wilayas/16").etag());
+    }
+}
@@ -0,0 +1,183 @@
+//! HTTP server exposing the leblad dataset as a JSON REST API.
+//!
+//! Usage: `leblad-server [ADDRESS]`, listening on `127.0.0.1:8080` by default.
+//!
+//! | Route                           | Response                       |
+//! |---------------------------------|--------------------------------|
+//! | `/wilayas`                      | every wilaya                   |
+//! | `/wilayas/{mattricule}`         | a wilaya with its dairats      |
+//! | `/wilayas/{mattricule}/dairats` | the dairats of a wilaya        |
+//! | `/dairats/{code}/baladyiats`    | the baladyiats of a daira      |
+//! | `/zip/{code}`                   | the wilaya of a postal code    |
+//! | `/phone/{code}`                 | the wilaya of a phone code     |
+//! | `/search?q=&level=&limit=`      | places matching a name or code |
+//!
+//! Responses carry an `ETag`, and requests with a matching `If-None-Match` get a
+//! `304 Not Modified`.
+
+use std::process::ExitCode;
+
+use tiny_http::{Header, Method, Request, Server};
+
+mod api;
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+fn main() -> ExitCode {
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+    let server = match Server::http(&address) {
+        Ok(server) => server,
+        Err(error) => {
+            eprintln!("leblad-server: cannot listen on {}: {}", address, error);
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!("leblad-server: listening on http://{}", address);
+    serve(&server);
+    ExitCode::SUCCESS
+}
+
+/// Answers the requests of a server until it is unblocked.
+fn serve(server: &Server) {
+    for request in server.incoming_requests() {
+        if let Err(error) = respond(request) {
+            eprintln!("leblad-server: {}", error);
+        }
+    }
+}
+
+fn respond(request: Request) -> std::io::Result<()> {
+    let response = match request.method() {
+        Method::Get | Method::Head => api::route(request.url()),
+        _ => {
+            let response = tiny_http::Response::from_string(r#"{"error":"method not allowed"}"#)
+                .with_status_code(405)
+                .with_header(header("Allow", "GET, HEAD"))
+                .with_header(header("Content-Type", "application/json"));
+            return request.respond(response);
+        }
+    };
+    let etag = response.etag();
+    let not_modified = request.headers().iter().any(|h| {
+        h.field.equiv("If-None-Match")
+            && h.value
+                .as_str()
+                .split(',')
+                .map(str::trim)
+                .any(|tag| tag == "*" || tag == etag)
+    });
+    let (status, body) = if not_modified {
+        (304, String::new())
+    } else {
+        (response.status, response.body)
+    };
+    let mut response = tiny_http::Response::from_string(body)
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json; charset=utf-8"))
+        .with_header(header("ETag", &etag));
+    // Errors are not cached, and a 304 keeps the caching of the response it revalidates.
+    if status == 200 {
+        response.add_header(header("Cache-Control", "public, max-age=3600"));
+    }
+    request.respond(response)
+}
+
+fn header(field: &str, value: &str) -> Header {
+    // Field names and values are ASCII, so the header is always valid.
+    Header::from_bytes(field, value).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::sync::Arc;
+    use std::thread;
+
+    /// Sends a raw request to a server on a local socket, returning the response head and body.
+    fn exchange(request: &str) -> (String, String) {
+        let server = Arc::new(Server::http("127.0.0.1:0").unwrap());
+        let address = server.server_addr().to_ip().unwrap();
+        let handle = {
+            let server = Arc::clone(&server);
+            thread::spawn(move || serve(&server))
+        };
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        server.unblock();
+        handle.join().unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        (head.to_string(), body.to_string())
+    }
+
+    fn get(path: &str, headers: &str) -> (String, String) {
+        exchange(&format!(
+            "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n{}\r\n",
+            path, headers
+        ))
+    }
+
+    fn etag(head: &str) -> String {
+        let line = head.lines().find(|l| l.to_lowercase().starts_with("etag:"));
+        line.unwrap()[5..].trim().to_string()
+    }
+
+    #[test]
+    fn serves_json() {
+        let (head, body) = get("/wilayas/1", "");
+        assert!(head.starts_with("HTTP/1.1 200"), "{}", head);
+        assert!(head.contains("application/json"));
+        let wilaya = serde_json::from_str::<serde_json::Value>(&body).unwrap();
+        assert_eq!(wilaya["name"], "Adrar");
+
+        let (head, body) = get("/wilayas/99", "");
+        assert!(head.starts_with("HTTP/1.1 404"), "{}", head);
+        assert!(body.contains("wilaya not found"));
+    }
+
+    #[test]
+    fn caches_only_successful_responses() {
+        let cache_control = |head: &str| {
+            head.lines()
+                .any(|l| l.to_lowercase().starts_with("cache-control:"))
+        };
+        let (head, _) = get("/wilayas/1", "");
+        assert!(
+            head.contains("Cache-Control: public, max-age=3600"),
+            "{}",
+            head
+        );
+        let (head, _) = get("/wilayas/99", "");
+        assert!(!cache_control(&head), "{}", head);
+        let (head, _) = get("/search", "");
+        assert!(head.starts_with("HTTP/1.1 400"), "{}", head);
+        assert!(!cache_control(&head), "{}", head);
+    }
+
+    #[test]
+    fn revalidates_with_etags() {
+        let (head, _) = get("/search?q=adrar", "");
+        let tag = etag(&head);
+        assert_eq!(tag, api::route("/search?q=adrar").etag());
+
+        let (head, body) = get("/search?q=adrar", &format!("If-None-Match: {}\r\n", tag));
+        assert!(head.starts_with("HTTP/1.1 304"), "{}", head);
+        assert!(body.is_empty());
+        let (head, _) = get("/search?q=adrar", "If-None-Match: \"0\"\r\n");
+        assert!(head.starts_with("HTTP/1.1 200"), "{}", head);
+    }
+
+    #[test]
+    fn rejects_other_methods() {
+        let (head, _) = exchange(
+            "POST /wilayas HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: 0\r\n\r\n",
+        );
+        assert!(head.starts_with("HTTP/1.1 405"), "{}", head);
+        assert!(head.contains("GET, HEAD"));
+    }
+}
@@ -0,0 +1,272 @@
+//! Command-line tool to query the leblad dataset.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+
+use leblad::diff::{diff, Snapshot};
+use leblad::{
+    get_wilaya_by_code, get_wilaya_by_phone_code, get_wilaya_by_zip_code, lookup, search,
+    search_level, Language, Level, Place,
+};
+use output::{Format, Table};
+
+mod output;
+mod repl;
+
+/// Query Algerian administrative areas: wilayas, dairats and baladyiats.
+#[derive(Debug, Parser)]
+#[command(name = "leblad", version)]
+struct Cli {
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Table, global = true)]
+    format: Format,
+    /// Language of names: fr, en, ar or ber.
+    #[arg(short, long, default_value = "fr", value_parser = parse_language, global = true)]
+    lang: Language,
+    /// Query to run, or none to explore the data interactively.
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Look up a wilaya by mattricule or name.
+    Wilaya { query: String },
+    /// Look up a daira by code or name.
+    Daira { query: String },
+    /// Look up a baladyia by code or name.
+    Baladyia { query: String },
+    /// Find the wilaya of a postal code.
+    Zip { code: u16 },
+    /// Find the wilaya of a phone code.
+    Phone { code: u16 },
+    /// List the wilayas adjacent to a wilaya, given by mattricule or name.
+    Adjacent { query: String },
+    /// Search places by name or code.
+    Search {
+        query: String,
+        /// Only search places of a level: wilaya, daira or baladyia.
+        #[arg(long, value_parser = parse_level)]
+        level: Option<Level>,
+        /// Maximum number of results.
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Compare a `WilayaList.json` snapshot with the built-in data, e.g. before updating it.
+    Diff {
+        /// Snapshot to compare to.
+        new: PathBuf,
+        /// Snapshot to compare from, instead of the built-in data.
+        #[arg(long)]
+        from: Option<PathBuf>,
+    },
+}
+
+fn parse_language(language: &str) -> Result<Language, String> {
+    Language::parse(language).ok_or_else(|| format!("unknown language `{}`", language))
+}
+
+fn parse_level(level: &str) -> Result<Level, String> {
+    Level::parse(level).ok_or_else(|| format!("unknown level `{}`", level))
+}
+
+/// Runs a command, returning the places to print or a message explaining why there are none.
+fn run(command: &Command) -> Result<Vec<Place<'static>>, String> {
+    let places = match command {
+        Command::Wilaya { query } => find(Level::Wilaya, query)?,
+        Command::Daira { query } => find(Level::Daira, query)?,
+        Command::Baladyia { query } => find(Level::Baladyia, query)?,
+        Command::Zip { code } => get_wilaya_by_zip_code(*code)
+            .map(Place::Wilaya)
+            .into_iter()
+            .collect(),
+        Command::Phone { code } => get_wilaya_by_phone_code(*code)
+            .map(Place::Wilaya)
+            .into_iter()
+            .collect(),
+        Command::Adjacent { query } => find(Level::Wilaya, query)?
+            .iter()
+            .flat_map(|wilaya| match wilaya {
+                Place::Wilaya(wilaya) => wilaya.adjacent_wilayas,
+                _ => &[],
+            })
+            .filter_map(|code| get_wilaya_by_code(*code).map(Place::Wilaya))
+            .collect(),
+        Command::Search {
+            query,
+            level,
+            limit,
+        } => {
+            let mut places = match level {
+                Some(level) => search_level(*level, query),
+                None => search(query),
+            };
+            places.truncate(*limit);
+            places
+        }
+        Command::Diff { .. } => unreachable!("diffs are run by `run_diff`"),
+    };
+    if places.is_empty() {
+        return Err("no results".to_string());
+    }
+    Ok(places)
+}
+
+/// Compares two snapshots, returning the changes in a format: JSON, or one change per line.
+fn run_diff(new: &Path, from: Option<&Path>, format: Format) -> Result<String, String> {
+    let load = |path: &Path| {
+        let json =
+            std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Snapshot::from_json(&json).map_err(|e| format!("{}: {}", path.display(), e))
+    };
+    let old = match from {
+        Some(from) => load(from)?,
+        None => Snapshot::compiled().map_err(|e| format!("built-in data: {}", e))?,
+    };
+    let changeset = diff(&old, &load(new)?);
+    Ok(match format {
+        Format::Json => {
+            let json = serde_json::to_string_pretty(&changeset.to_json()).unwrap_or_default();
+            format!("{}\n", json)
+        }
+        Format::Table | Format::Csv => changeset.to_string(),
+    })
+}
+
+/// Looks up places by exact code or name, suggesting close names when there are none.
+fn find(level: Level, query: &str) -> Result<Vec<Place<'static>>, String> {
+    let places = lookup(level, query);
+    if !places.is_empty() {
+        return Ok(places);
+    }
+    let suggestions = search_level(level, query)
+        .iter()
+        .take(5)
+        .map(|place| format!("{} ({})", place.name(), place.code()))
+        .collect::<Vec<String>>();
+    if suggestions.is_empty() {
+        Err(format!("no {} matches `{}`", level.as_str(), query))
+    } else {
+        Err(format!(
+            "no {} matches `{}`, did you mean: {}?",
+            level.as_str(),
+            query,
+            suggestions.join(", ")
+        ))
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match &cli.command {
+        Some(Command::Diff { new, from }) => {
+            run_diff(new, from.as_deref(), cli.format).map(|changes| print!("{}", changes))
+        }
+        Some(command) => run(command).map(|places| {
+            print!("{}", Table::of_places(&places, cli.lang).render(cli.format));
+        }),
+        None => repl::run(cli.format, cli.lang),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("leblad: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    fn run_args(args: &[&str]) -> Result<Vec<Place<'static>>, String> {
+        let cli = Cli::try_parse_from([&["leblad"], args].concat()).map_err(|e| e.to_string())?;
+        run(&cli.command.unwrap())
+    }
+
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn lookups_by_code_and_name() {
+        assert_eq!(run_args(&["wilaya", "1"]).unwrap()[0].name(), "Adrar");
+        assert_eq!(run_args(&["wilaya", "adrar"]).unwrap()[0].code(), 1);
+        assert_eq!(run_args(&["baladyia", "101"]).unwrap()[0].code(), 101);
+        assert_eq!(run_args(&["zip", "1000"]).unwrap()[0].code(), 1);
+        let error = run_args(&["wilaya", "adr"]).unwrap_err();
+        assert!(error.contains("did you mean: Adrar (1)"), "{}", error);
+        assert!(run_args(&["phone", "0"]).is_err());
+    }
+
+    #[test]
+    fn adjacent_wilayas() {
+        let wilaya = get_wilaya_by_code(1).unwrap();
+        let codes = run_args(&["adjacent", "adrar"])
+            .unwrap_or_default()
+            .iter()
+            .map(Place::code)
+            .collect::<Vec<u16>>();
+        let expected = wilaya
+            .adjacent_wilayas
+            .iter()
+            .copied()
+            .filter(|code| get_wilaya_by_code(*code).is_some())
+            .collect::<Vec<u16>>();
+        assert_eq!(codes, expected);
+        assert!(run_args(&["adjacent", "99"]).is_err());
+    }
+
+    #[test]
+    fn search_with_options() {
+        let results = run_args(&["search", "adrar", "--level", "daira", "-n", "1"]).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].level(), Level::Daira);
+        assert!(run_args(&["search", "adrar", "--level", "commune"]).is_err());
+    }
+
+    #[test]
+    fn diff_snapshots() {
+        let dir = std::env::temp_dir().join(format!("leblad-diff-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let wilaya = |mattricule: u16, name: &str| {
+            serde_json::json!({
+                "mattricule": mattricule, "name": name, "name_ar": "", "name_ber": "",
+                "name_en": name, "phoneCodes": [], "postalCodes": [],
+                "adjacentWilayas": [], "dairats": [],
+            })
+        };
+        let old = dir.join("old.json");
+        let new = dir.join("new.json");
+        std::fs::write(&old, serde_json::json!([wilaya(1, "Adrar")]).to_string()).unwrap();
+        let snapshot = serde_json::json!([wilaya(1, "Adrar"), wilaya(49, "Timimoun")]);
+        std::fs::write(&new, snapshot.to_string()).unwrap();
+
+        let cli = Cli::try_parse_from(["leblad", "diff", "new.json", "--from", "old.json"]);
+        assert!(matches!(cli.unwrap().command, Some(Command::Diff { .. })));
+        let changes = run_diff(&new, Some(&old), Format::Table).unwrap();
+        assert_eq!(changes, "+ wilaya 49 Timimoun\n");
+        let json = run_diff(&new, Some(&old), Format::Json).unwrap();
+        let json = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+        assert_eq!(json[0]["change"], "added");
+        let changes = run_diff(&old, None, Format::Table).unwrap();
+        assert!(changes.contains("- wilaya 16 "), "{}", changes);
+        let error = run_diff(&dir.join("missing.json"), None, Format::Table).unwrap_err();
+        assert!(error.starts_with(&dir.join("missing.json").display().to_string()));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn global_options() {
+        let cli =
+            Cli::try_parse_from(["leblad", "wilaya", "1", "--lang", "ar", "-f", "csv"]).unwrap();
+        assert_eq!(cli.lang, Language::Ar);
+        assert_eq!(cli.format, Format::Csv);
+        assert!(Cli::try_parse_from(["leblad", "--lang", "de", "wilaya", "1"]).is_err());
+    }
+}
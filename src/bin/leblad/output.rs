@@ -0,0 +1,219 @@
+//! Rendering of query results as tables, JSON or CSV.
+
+use clap::ValueEnum;
+use serde_json::{json, Map, Value};
+
+use leblad::{Language, Level, Place};
+
+/// Output format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Aligned columns.
+    Table,
+    /// An array of objects.
+    Json,
+    /// Comma-separated values with a header.
+    Csv,
+}
+
+/// Rows of values under named columns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub columns: Vec<&'static str>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    /// Builds a table of places, with columns depending on their level.
+    pub fn of_places(places: &[Place], language: Language) -> Table {
+        let level = places.first().map(Place::level);
+        let level = level.filter(|level| places.iter().all(|p| p.level() == *level));
+        let columns = match level {
+            Some(Level::Wilaya) => vec![
+                "code",
+                "name",
+                "iso_code",
+                "phone_codes",
+                "postal_codes",
+                "adjacent_wilayas",
+            ],
+            Some(Level::Daira) => vec!["code", "name", "wilaya"],
+            Some(Level::Baladyia) => vec!["code", "name", "daira", "wilaya"],
+            None => vec!["level", "code", "name", "wilaya"],
+        };
+        let rows = places
+            .iter()
+            .map(|place| {
+                let name = json!(place.name_in(language));
+                let parent = place.parent();
+                let wilaya = parent
+                    .and_then(|parent| parent.parent().or(Some(parent)))
+                    .map_or(Value::Null, |wilaya| json!(wilaya.name_in(language)));
+                match (level, place) {
+                    (Some(_), Place::Wilaya(w)) => vec![
+                        json!(w.mattricule),
+                        name,
+                        json!(w.iso_code()),
+                        json!(w.phone_codes),
+                        json!(w.postal_codes),
+                        json!(w.adjacent_wilayas),
+                    ],
+                    (Some(_), Place::Daira(d)) => vec![json!(d.code), name, wilaya],
+                    (Some(_), Place::Baladyia(b)) => vec![
+                        json!(b.code),
+                        name,
+                        parent.map_or(Value::Null, |daira| json!(daira.name_in(language))),
+                        wilaya,
+                    ],
+                    (None, _) => vec![
+                        json!(place.level().as_str()),
+                        json!(place.code()),
+                        name,
+                        wilaya,
+                    ],
+                }
+            })
+            .collect();
+        Table { columns, rows }
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Table => self.to_text(),
+            Format::Json => {
+                let objects = self
+                    .rows
+                    .iter()
+                    .map(|row| {
+                        let object = self
+                            .columns
+                            .iter()
+                            .map(|column| column.to_string())
+                            .zip(row.iter().cloned())
+                            .collect::<Map<String, Value>>();
+                        Value::Object(object)
+                    })
+                    .collect::<Vec<Value>>();
+                let mut json = serde_json::to_string_pretty(&objects).unwrap_or_default();
+                json.push('\n');
+                json
+            }
+            Format::Csv => {
+                let mut csv = self.columns.join(",");
+                csv.push('\n');
+                for row in &self.rows {
+                    let cells = row.iter().map(|value| csv_cell(&cell(value)));
+                    csv.push_str(&cells.collect::<Vec<String>>().join(","));
+                    csv.push('\n');
+                }
+                csv
+            }
+        }
+    }
+
+    fn to_text(&self) -> String {
+        let cells = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(cell).collect::<Vec<String>>())
+            .collect::<Vec<Vec<String>>>();
+        let widths = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                cells
+                    .iter()
+                    .map(|row| row[i].chars().count())
+                    .chain([column.len()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<usize>>();
+        let line = |values: Vec<&str>| {
+            let padded = values
+                .iter()
+                .zip(&widths)
+                .map(|(value, width)| {
+                    format!("{}{}", value, " ".repeat(width - value.chars().count()))
+                })
+                .collect::<Vec<String>>();
+            format!("{}\n", padded.join("  ").trim_end())
+        };
+        let mut text = line(self.columns.clone());
+        for row in &cells {
+            text.push_str(&line(row.iter().map(String::as_str).collect()));
+        }
+        text
+    }
+}
+
+/// Formats a value for text outputs, joining arrays with spaces.
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(values) => values.iter().map(cell).collect::<Vec<String>>().join(" "),
+        value => value.to_string(),
+    }
+}
+
+fn csv_cell(cell: &str) -> String {
+    if cell.contains([',', '"', '\n']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Table {
+        Table {
+            columns: vec!["code", "name", "codes"],
+            rows: vec![
+                vec![json!(1), json!("Adrar"), json!([49, 29])],
+                vec![json!(16), json!("Alger, centre"), json!([])],
+            ],
+        }
+    }
+
+    #[test]
+    fn render_text() {
+        assert_eq!(
+            table().render(Format::Table),
+            "code  name           codes\n1     Adrar          49 29\n16    Alger, centre\n"
+        );
+    }
+
+    #[test]
+    fn render_csv() {
+        assert_eq!(
+            table().render(Format::Csv),
+            "code,name,codes\n1,Adrar,49 29\n16,\"Alger, centre\",\n"
+        );
+    }
+
+    #[test]
+    fn render_json() {
+        let json = serde_json::from_str::<Value>(&table().render(Format::Json)).unwrap();
+        assert_eq!(json[0]["codes"], json!([49, 29]));
+        assert_eq!(json[1]["name"], "Alger, centre");
+    }
+
+    #[test]
+    fn places_columns_depend_on_level() {
+        let wilaya = leblad::lookup(Level::Wilaya, "1");
+        let table = Table::of_places(&wilaya, Language::En);
+        assert_eq!(table.columns[2], "iso_code");
+        assert_eq!(table.rows[0][2], "DZ-01");
+
+        let mut mixed = wilaya.clone();
+        mixed.extend(leblad::lookup(Level::Baladyia, "101"));
+        let table = Table::of_places(&mixed, Language::Fr);
+        assert_eq!(table.columns, vec!["level", "code", "name", "wilaya"]);
+        assert_eq!(table.rows[1][3], "Adrar");
+    }
+}
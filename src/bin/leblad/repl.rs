@@ -0,0 +1,376 @@
+//! Interactive explorer of the administrative tree.
+//!
+//! The explorer keeps a current path from the root, which lists the wilayas, down to a
+//! wilaya, a daira and a baladyia. Paths are made of codes or names separated by `/`, e.g.
+//! `/1/101` or `adrar/adrar`, and names can be given in any language.
+
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use serde_json::json;
+
+use leblad::{dataset_version, get_wilaya_list, lookup, search, Language, Level, Place};
+
+use crate::output::{Format, Table};
+
+const COMMANDS: [&str; 9] = [
+    "cd", "exit", "help", "lang", "ls", "pwd", "quit", "search", "show",
+];
+
+const HELP: &str = "\
+cd [PATH]       go to a place, or to the root without a path
+ls [PATH]       list the places in the current place or in PATH
+show [PATH]     show the current place or the place at PATH
+pwd             print the current path
+search QUERY    search places by name or code, printing their paths
+lang LANGUAGE   print names in fr, en, ar or ber
+help            print this help
+exit, quit      leave the explorer
+
+PATH is made of codes or names separated by `/`: `/` is the root, `..` the parent place.
+";
+
+/// What to do after a line was executed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// Print a text, possibly empty, and read the next line.
+    Print(String),
+    /// Leave the explorer.
+    Exit,
+}
+
+/// State of an interactive session.
+#[derive(Debug, Clone)]
+pub struct Explorer {
+    path: Vec<Place<'static>>,
+    format: Format,
+    language: Language,
+}
+
+impl Explorer {
+    pub fn new(format: Format, language: Language) -> Explorer {
+        Explorer {
+            path: vec![],
+            format,
+            language,
+        }
+    }
+
+    /// The prompt, showing the current path by codes.
+    pub fn prompt(&self) -> String {
+        format!("leblad:{}> ", code_path(&self.path))
+    }
+
+    /// Executes a line, returning an error message when it is invalid.
+    pub fn execute(&mut self, line: &str) -> Result<Outcome, String> {
+        let line = line.trim();
+        let (command, argument) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let argument = argument.trim();
+        let text = match command {
+            "" => String::new(),
+            "cd" => {
+                self.path = self.resolve(if argument.is_empty() { "/" } else { argument })?;
+                String::new()
+            }
+            "ls" => {
+                let path = self.resolve(argument)?;
+                let children = match path.last() {
+                    None => get_wilaya_list().iter().map(Place::Wilaya).collect(),
+                    Some(Place::Baladyia(_)) => {
+                        return Err(format!("{} has no subdivisions", name_path(&path)))
+                    }
+                    Some(place) => place.children(),
+                };
+                if children.is_empty() {
+                    "no places\n".to_string()
+                } else {
+                    self.render(&children)
+                }
+            }
+            "show" => match self.resolve(argument)?.last() {
+                None => format!("{}\n", dataset_version()),
+                Some(place) => self.render(&[*place]),
+            },
+            "pwd" => format!("{}\n", name_path(&self.path)),
+            "search" if argument.is_empty() => return Err("usage: search QUERY".to_string()),
+            "search" => {
+                let places = search(argument);
+                if places.is_empty() {
+                    return Err(format!("no places match `{}`", argument));
+                }
+                let rows = places
+                    .iter()
+                    .map(|place| {
+                        vec![
+                            json!(code_path(&path_of(*place))),
+                            json!(place.level().as_str()),
+                            json!(place.name_in(self.language)),
+                        ]
+                    })
+                    .collect();
+                let table = Table {
+                    columns: vec!["path", "level", "name"],
+                    rows,
+                };
+                table.render(self.format)
+            }
+            "lang" => {
+                self.language = Language::parse(argument)
+                    .ok_or_else(|| format!("unknown language `{}`", argument))?;
+                String::new()
+            }
+            "help" => HELP.to_string(),
+            "exit" | "quit" => return Ok(Outcome::Exit),
+            _ => return Err(format!("unknown command `{}`, try `help`", command)),
+        };
+        Ok(Outcome::Print(text))
+    }
+
+    fn render(&self, places: &[Place]) -> String {
+        Table::of_places(places, self.language).render(self.format)
+    }
+
+    /// Resolves a path, absolute when it starts with `/`, or relative to the current one.
+    fn resolve(&self, target: &str) -> Result<Vec<Place<'static>>, String> {
+        let mut path = if target.starts_with('/') {
+            vec![]
+        } else {
+            self.path.clone()
+        };
+        for segment in target.split('/').map(str::trim) {
+            match segment {
+                "" | "." => {}
+                ".." => {
+                    path.pop();
+                }
+                segment => {
+                    let child = child(path.last(), segment)
+                        .ok_or_else(|| format!("no place `{}` in {}", segment, name_path(&path)))?;
+                    path.push(child);
+                }
+            }
+        }
+        Ok(path)
+    }
+
+    /// Completes the command at the start of a line, or the last segment of a path.
+    fn completions(&self, line: &str) -> (usize, Vec<String>) {
+        let Some((command, argument)) = line.split_once(' ') else {
+            let commands = COMMANDS.iter().filter(|c| c.starts_with(line));
+            return (0, commands.map(|c| format!("{} ", c)).collect());
+        };
+        if !matches!(command, "cd" | "ls" | "show") {
+            return (line.len(), vec![]);
+        }
+        let argument = argument.trim_start();
+        let (parent, prefix) = argument.rsplit_once('/').unwrap_or(("", argument));
+        let start = line.len() - prefix.len();
+        let parent = if argument.starts_with('/') && parent.is_empty() {
+            "/"
+        } else {
+            parent
+        };
+        let Ok(path) = self.resolve(parent) else {
+            return (start, vec![]);
+        };
+        let children = match path.last() {
+            None => get_wilaya_list().iter().map(Place::Wilaya).collect(),
+            Some(place) => place.children(),
+        };
+        let prefix = prefix.to_lowercase();
+        let code_prefix = prefix.trim_start_matches('0');
+        let mut candidates = children
+            .iter()
+            .flat_map(|place| {
+                let code = place.code().to_string();
+                let code = code.starts_with(code_prefix).then_some(code);
+                let names = Language::ALL
+                    .iter()
+                    .map(|language| place.name_in(*language))
+                    .filter(|name| name.to_lowercase().starts_with(&prefix))
+                    .map(str::to_string);
+                code.into_iter().chain(names)
+            })
+            .collect::<Vec<String>>();
+        candidates.sort();
+        candidates.dedup();
+        (start, candidates)
+    }
+}
+
+impl Completer for Explorer {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.completions(&line[..pos]))
+    }
+}
+
+impl Hinter for Explorer {
+    type Hint = String;
+}
+
+impl Highlighter for Explorer {}
+
+impl Validator for Explorer {}
+
+impl Helper for Explorer {}
+
+/// Runs the explorer on the terminal until `exit`, Ctrl-C or Ctrl-D.
+pub fn run(format: Format, language: Language) -> Result<(), String> {
+    let mut editor =
+        Editor::<Explorer, DefaultHistory>::new().map_err(|error| error.to_string())?;
+    editor.set_helper(Some(Explorer::new(format, language)));
+    loop {
+        let prompt = editor.helper().map(Explorer::prompt).unwrap_or_default();
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => return Ok(()),
+            Err(error) => return Err(error.to_string()),
+        };
+        if !line.trim().is_empty() {
+            let _ = editor.add_history_entry(line.as_str());
+        }
+        let Some(explorer) = editor.helper_mut() else {
+            return Ok(());
+        };
+        match explorer.execute(&line) {
+            Ok(Outcome::Print(text)) => print!("{}", text),
+            Ok(Outcome::Exit) => return Ok(()),
+            Err(message) => eprintln!("{}", message),
+        }
+    }
+}
+
+/// Finds a place directly in another one, or a wilaya at the root, by code or name.
+fn child(parent: Option<&Place>, segment: &str) -> Option<Place<'static>> {
+    let level = match parent {
+        None => Level::Wilaya,
+        Some(Place::Wilaya(_)) => Level::Daira,
+        Some(Place::Daira(_)) => Level::Baladyia,
+        Some(Place::Baladyia(_)) => return None,
+    };
+    lookup(level, segment)
+        .into_iter()
+        .find(|place| place.parent().as_ref() == parent)
+}
+
+/// Gets the path from the root to a place.
+fn path_of(place: Place<'static>) -> Vec<Place<'static>> {
+    let mut path = vec![place];
+    while let Some(parent) = path[0].parent() {
+        path.insert(0, parent);
+    }
+    path
+}
+
+fn code_path(path: &[Place]) -> String {
+    let codes = path.iter().map(|place| place.code().to_string());
+    format!("/{}", codes.collect::<Vec<String>>().join("/"))
+}
+
+fn name_path(path: &[Place]) -> String {
+    let names = path.iter().map(Place::name);
+    format!("/{}", names.collect::<Vec<&str>>().join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn explorer() -> Explorer {
+        Explorer::new(Format::Csv, Language::Fr)
+    }
+
+    fn print(explorer: &mut Explorer, line: &str) -> String {
+        match explorer.execute(line) {
+            Ok(Outcome::Print(text)) => text,
+            outcome => panic!("`{}` gave {:?}", line, outcome),
+        }
+    }
+
+    #[test]
+    fn navigate_by_code_and_name() {
+        let mut explorer = explorer();
+        assert_eq!(explorer.prompt(), "leblad:/> ");
+        print(&mut explorer, "cd 01");
+        print(&mut explorer, "cd adrar");
+        assert_eq!(explorer.prompt(), "leblad:/1/101> ");
+        assert_eq!(print(&mut explorer, "pwd"), "/Adrar/ADRAR\n");
+        print(&mut explorer, "cd ../101/101");
+        assert_eq!(explorer.prompt(), "leblad:/1/101/101> ");
+        print(&mut explorer, "cd /1/101/OULED AHMED TIMMI/..");
+        assert_eq!(explorer.prompt(), "leblad:/1/101> ");
+        print(&mut explorer, "cd");
+        assert_eq!(explorer.prompt(), "leblad:/> ");
+        assert!(explorer.execute("cd 101").is_err());
+        assert!(explorer.execute("cd 1/4901").is_err());
+    }
+
+    #[test]
+    fn list_and_show() {
+        let mut explorer = explorer();
+        let wilayas = print(&mut explorer, "ls");
+        assert_eq!(wilayas.lines().count(), get_wilaya_list().len() + 1);
+        assert!(print(&mut explorer, "ls 1/101").contains("\n101,ADRAR"));
+        assert!(print(&mut explorer, "show /1").starts_with("code,name,iso_code"));
+        assert!(explorer.execute("ls 1/101/101").is_err());
+        print(&mut explorer, "lang en");
+        assert!(print(&mut explorer, "show 16").contains(",Algiers,"));
+        assert!(explorer.execute("lang de").is_err());
+    }
+
+    #[test]
+    fn search_prints_paths() {
+        let mut explorer = explorer();
+        let results = print(&mut explorer, "search ouled ahmed timmi");
+        assert!(results.starts_with("path,level,name\n"));
+        assert!(results.lines().any(|line| {
+            line.starts_with("/1/101/") && line.ends_with(",baladyia,OULED AHMED TIMMI")
+        }));
+        assert!(explorer.execute("search").is_err());
+        assert!(explorer.execute("search zzzz").is_err());
+    }
+
+    #[test]
+    fn other_commands() {
+        let mut explorer = explorer();
+        assert_eq!(explorer.execute("exit"), Ok(Outcome::Exit));
+        assert_eq!(print(&mut explorer, "  "), "");
+        assert!(print(&mut explorer, "help").contains("search QUERY"));
+        assert!(explorer.execute("rm 1").is_err());
+    }
+
+    #[test]
+    fn complete_commands_and_paths() {
+        let explorer = explorer();
+        assert_eq!(explorer.completions("se"), (0, vec!["search ".to_string()]));
+        let codes = [
+            "4", "40", "41", "42", "43", "44", "45", "46", "47", "48", "49",
+        ];
+        assert_eq!(
+            explorer.completions("cd 4"),
+            (3, codes.map(String::from).to_vec())
+        );
+        let (start, candidates) = explorer.completions("cd /01/AD");
+        assert_eq!(start, 7);
+        assert!(candidates.contains(&"ADRAR".to_string()));
+        let (_, candidates) = explorer.completions("ls 1/101/");
+        assert!(candidates.contains(&"101".to_string()));
+        assert!(candidates.contains(&"OULED AHMED TIMMI".to_string()));
+        let wilaya = &get_wilaya_list()[0];
+        let prefix = wilaya.name_ar.chars().next().unwrap();
+        let (_, candidates) = explorer.completions(&format!("cd {}", prefix));
+        assert!(candidates.contains(&wilaya.name_ar.to_string()));
+        assert_eq!(explorer.completions("pwd x"), (5, vec![]));
+    }
+}
@@ -0,0 +1,183 @@
+//! Codes of places, checked against the dataset.
+//!
+//! [`WilayaCode`], [`DairaCode`] and [`BaladyiaCode`] can only hold codes of existing places,
+//! so they give back the place itself without any further check. They are meant for storing
+//! references to places, e.g. in `SMALLINT` database columns: with the `diesel` and `sqlx`
+//! features, they are read from and written to such columns directly, and reading an unknown
+//! code fails with an [`InvalidCodeError`].
+
+use std::fmt;
+
+use crate::{get_baladyia_by_code, get_daira_by_code, get_wilaya_by_code};
+use crate::{Baladyia, Daira, Level, Wilaya};
+
+#[cfg(feature = "diesel")]
+mod with_diesel;
+#[cfg(feature = "sqlx")]
+mod with_sqlx;
+
+/// Error returned when a code does not belong to any place of the dataset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidCodeError {
+    /// Level of the expected place.
+    pub level: Level,
+    /// The invalid code, which may be negative when read from a database.
+    pub code: i32,
+}
+
+impl fmt::Display for InvalidCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown {} code {}", self.level.as_str(), self.code)
+    }
+}
+
+impl std::error::Error for InvalidCodeError {}
+
+macro_rules! code_type {
+    (
+        $(#[$doc:meta])*
+        $name:ident, $level:expr, $place:ident, $method:ident, $get:ident, $example:literal
+    ) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[cfg_attr(
+            feature = "diesel",
+            derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow),
+            diesel(sql_type = diesel::sql_types::SmallInt)
+        )]
+        // Stored as the type of `SMALLINT` columns, which every code fits in.
+        pub struct $name(i16);
+
+        impl $name {
+            #[doc = concat!("Check that `code` belongs to a ", $example, ".")]
+            pub fn new(code: u16) -> Result<$name, InvalidCodeError> {
+                match $get(code) {
+                    Some(_) => Ok($name(code as i16)),
+                    None => Err(InvalidCodeError {
+                        level: $level,
+                        code: code.into(),
+                    }),
+                }
+            }
+
+            /// Get the code.
+            pub const fn get(self) -> u16 {
+                self.0 as u16
+            }
+
+            #[doc = concat!("Get the ", $example, " with this code.")]
+            pub fn $method(self) -> &'static $place {
+                match $get(self.get()) {
+                    Some(place) => place,
+                    None => unreachable!("codes are checked when created"),
+                }
+            }
+        }
+
+        impl TryFrom<u16> for $name {
+            type Error = InvalidCodeError;
+
+            fn try_from(code: u16) -> Result<$name, InvalidCodeError> {
+                $name::new(code)
+            }
+        }
+
+        impl TryFrom<i16> for $name {
+            type Error = InvalidCodeError;
+
+            /// Check a code read from a `SMALLINT` column.
+            fn try_from(code: i16) -> Result<$name, InvalidCodeError> {
+                let invalid = InvalidCodeError {
+                    level: $level,
+                    code: code.into(),
+                };
+                u16::try_from(code)
+                    .map_err(|_| invalid)
+                    .and_then($name::new)
+            }
+        }
+
+        impl From<$name> for u16 {
+            fn from(code: $name) -> u16 {
+                code.get()
+            }
+        }
+
+        impl From<$name> for &'static $place {
+            fn from(code: $name) -> &'static $place {
+                code.$method()
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.get())
+            }
+        }
+    };
+}
+
+code_type!(
+    /// Mattricule of a wilaya of the dataset.
+    /// # Example
+    /// ```
+    /// use leblad::WilayaCode;
+    /// let code = WilayaCode::new(1).unwrap();
+    /// assert_eq!(code.wilaya().name, "Adrar");
+    /// assert!(WilayaCode::new(100).is_err());
+    /// ```
+    WilayaCode, Level::Wilaya, Wilaya, wilaya, get_wilaya_by_code, "wilaya"
+);
+
+code_type!(
+    /// Code of a daira of the dataset.
+    /// # Example
+    /// ```
+    /// use leblad::DairaCode;
+    /// let code = DairaCode::new(101).unwrap();
+    /// assert_eq!(code.daira().name, "ADRAR");
+    /// assert!(DairaCode::new(1).is_err());
+    /// ```
+    DairaCode, Level::Daira, Daira, daira, get_daira_by_code, "daira"
+);
+
+code_type!(
+    /// Code of a baladyia of the dataset.
+    /// # Example
+    /// ```
+    /// use leblad::BaladyiaCode;
+    /// let code = BaladyiaCode::new(101).unwrap();
+    /// assert_eq!(code.baladyia().name, "ADRAR");
+    /// assert!(BaladyiaCode::new(1).is_err());
+    /// ```
+    BaladyiaCode, Level::Baladyia, Baladyia, baladyia, get_baladyia_by_code, "baladyia"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_are_checked() {
+        let code = WilayaCode::try_from(16u16).unwrap();
+        assert_eq!(u16::from(code), 16);
+        assert_eq!(<&Wilaya>::from(code).name_en, "Algiers");
+        assert_eq!(code.to_string(), "16");
+        assert_eq!(
+            DairaCode::new(1000),
+            Err(InvalidCodeError {
+                level: Level::Daira,
+                code: 1000
+            })
+        );
+        assert_eq!(BaladyiaCode::new(101).unwrap().baladyia().name, "ADRAR");
+    }
+
+    #[test]
+    fn column_values_are_checked() {
+        assert_eq!(WilayaCode::try_from(49i16).unwrap().get(), 49);
+        let error = WilayaCode::try_from(-1i16).unwrap_err();
+        assert_eq!(error.to_string(), "unknown wilaya code -1");
+        assert_eq!(BaladyiaCode::try_from(i16::MAX).unwrap_err().code, 32767);
+    }
+}
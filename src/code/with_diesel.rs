@@ -0,0 +1,115 @@
+//! Diesel support for codes, stored in `SmallInt` columns.
+
+use diesel::backend::Backend;
+use diesel::deserialize::{self, FromSql};
+use diesel::serialize::{self, Output, ToSql};
+use diesel::sql_types::SmallInt;
+
+use super::{BaladyiaCode, DairaCode, WilayaCode};
+
+macro_rules! impl_diesel {
+    ($($name:ident),*) => {$(
+        impl<DB> FromSql<SmallInt, DB> for $name
+        where
+            DB: Backend,
+            i16: FromSql<SmallInt, DB>,
+        {
+            fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<$name> {
+                Ok($name::try_from(i16::from_sql(bytes)?)?)
+            }
+        }
+
+        impl<DB> ToSql<SmallInt, DB> for $name
+        where
+            DB: Backend,
+            i16: ToSql<SmallInt, DB>,
+        {
+            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+                self.0.to_sql(out)
+            }
+        }
+    )*};
+}
+
+impl_diesel!(WilayaCode, DairaCode, BaladyiaCode);
+
+#[cfg(test)]
+mod tests {
+    use diesel::prelude::*;
+    use diesel::result::DeserializeFieldError;
+    use diesel::sqlite::SqliteConnection;
+
+    use super::*;
+    use crate::{InvalidCodeError, Wilaya};
+
+    diesel::table! {
+        addresses (id) {
+            id -> Integer,
+            wilaya_code -> SmallInt,
+            baladyia_code -> SmallInt,
+        }
+    }
+
+    #[derive(Debug, Queryable, Insertable)]
+    #[diesel(table_name = addresses)]
+    struct Address {
+        id: i32,
+        wilaya_code: WilayaCode,
+        baladyia_code: BaladyiaCode,
+    }
+
+    fn connection() -> SqliteConnection {
+        let mut connection = SqliteConnection::establish(":memory:").unwrap();
+        diesel::sql_query(
+            "CREATE TABLE addresses (id INTEGER PRIMARY KEY, wilaya_code SMALLINT NOT NULL, \
+             baladyia_code SMALLINT NOT NULL)",
+        )
+        .execute(&mut connection)
+        .unwrap();
+        connection
+    }
+
+    #[test]
+    fn codes_round_trip() {
+        let mut connection = connection();
+        let address = Address {
+            id: 1,
+            wilaya_code: WilayaCode::new(1).unwrap(),
+            baladyia_code: BaladyiaCode::new(101).unwrap(),
+        };
+        diesel::insert_into(addresses::table)
+            .values(&address)
+            .execute(&mut connection)
+            .unwrap();
+        let loaded = addresses::table
+            .filter(addresses::wilaya_code.eq(WilayaCode::new(1).unwrap()))
+            .first::<Address>(&mut connection)
+            .unwrap();
+        assert_eq!(loaded.id, 1);
+        let wilaya: &'static Wilaya = loaded.wilaya_code.into();
+        assert_eq!(wilaya.name, "Adrar");
+        assert_eq!(loaded.baladyia_code.baladyia().name, "ADRAR");
+    }
+
+    #[test]
+    fn invalid_codes_fail_to_decode() {
+        let mut connection = connection();
+        diesel::sql_query("INSERT INTO addresses VALUES (1, 100, 101)")
+            .execute(&mut connection)
+            .unwrap();
+        let error = addresses::table
+            .first::<Address>(&mut connection)
+            .unwrap_err();
+        let diesel::result::Error::DeserializationError(error) = error else {
+            panic!("unexpected error {:?}", error);
+        };
+        let error = error.downcast::<DeserializeFieldError>().unwrap().error;
+        let error = error.downcast::<InvalidCodeError>().unwrap();
+        assert_eq!(error.to_string(), "unknown wilaya code 100");
+        let codes = addresses::table
+            .select(addresses::id)
+            .load::<i32>(&mut connection)
+            .unwrap();
+        assert_eq!(codes, [1]);
+    }
+}
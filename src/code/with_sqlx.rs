@@ -0,0 +1,103 @@
+//! sqlx support for codes, stored in `SMALLINT` columns.
+
+use sqlx::decode::Decode;
+use sqlx::encode::{Encode, IsNull};
+use sqlx::error::BoxDynError;
+use sqlx::{Database, Type};
+
+use super::{BaladyiaCode, DairaCode, WilayaCode};
+
+macro_rules! impl_sqlx {
+    ($($name:ident),*) => {$(
+        impl<DB> Type<DB> for $name
+        where
+            DB: Database,
+            i16: Type<DB>,
+        {
+            fn type_info() -> DB::TypeInfo {
+                <i16 as Type<DB>>::type_info()
+            }
+
+            fn compatible(ty: &DB::TypeInfo) -> bool {
+                <i16 as Type<DB>>::compatible(ty)
+            }
+        }
+
+        impl<'r, DB> Decode<'r, DB> for $name
+        where
+            DB: Database,
+            i16: Decode<'r, DB>,
+        {
+            fn decode(value: DB::ValueRef<'r>) -> Result<$name, BoxDynError> {
+                Ok($name::try_from(i16::decode(value)?)?)
+            }
+        }
+
+        impl<'q, DB> Encode<'q, DB> for $name
+        where
+            DB: Database,
+            i16: Encode<'q, DB>,
+        {
+            fn encode_by_ref(
+                &self,
+                buf: &mut DB::ArgumentBuffer<'q>,
+            ) -> Result<IsNull, BoxDynError> {
+                self.0.encode_by_ref(buf)
+            }
+        }
+    )*};
+}
+
+impl_sqlx!(WilayaCode, DairaCode, BaladyiaCode);
+
+#[cfg(test)]
+mod tests {
+    use sqlx::{Connection, Row, SqliteConnection};
+
+    use super::*;
+
+    async fn connection() -> SqliteConnection {
+        let mut connection = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE addresses (daira_code SMALLINT NOT NULL)")
+            .execute(&mut connection)
+            .await
+            .unwrap();
+        connection
+    }
+
+    #[tokio::test]
+    async fn codes_round_trip() {
+        let mut connection = connection().await;
+        sqlx::query("INSERT INTO addresses VALUES (?)")
+            .bind(DairaCode::new(101).unwrap())
+            .execute(&mut connection)
+            .await
+            .unwrap();
+        let (code,) = sqlx::query_as::<_, (DairaCode,)>("SELECT daira_code FROM addresses")
+            .fetch_one(&mut connection)
+            .await
+            .unwrap();
+        assert_eq!(code.daira().name, "ADRAR");
+    }
+
+    #[tokio::test]
+    async fn invalid_codes_fail_to_decode() {
+        let mut connection = connection().await;
+        sqlx::query("INSERT INTO addresses VALUES (101), (1000)")
+            .execute(&mut connection)
+            .await
+            .unwrap();
+        let rows = sqlx::query("SELECT daira_code FROM addresses")
+            .fetch_all(&mut connection)
+            .await
+            .unwrap();
+        assert_eq!(rows[0].try_get::<DairaCode, _>(0).unwrap().get(), 101);
+        let error = rows[1].try_get::<DairaCode, _>(0).unwrap_err();
+        assert!(
+            error.to_string().contains("unknown daira code 1000"),
+            "{}",
+            error
+        );
+        assert!(rows[1].try_get::<WilayaCode, _>(0).is_err());
+    }
+}
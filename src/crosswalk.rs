@@ -0,0 +1,265 @@
+//! External identifiers crosswalk.
+//!
+//! Links places to their Wikidata item and OpenStreetMap relation, so leblad data can be
+//! joined with other datasets without matching names. The built-in table is embedded from
+//! `data/crosswalk.csv`, and other tables with the same format can be loaded at runtime.
+//!
+//! ONS (Office National des Statistiques) commune codes do not need a table: they are the
+//! baladyia codes written on 4 digits, see [`Baladyia::ons_code`].
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::{get_baladyia_by_code, Baladyia, Level, Place};
+
+const BUILTIN_CSV: &str = include_str!("../data/crosswalk.csv");
+
+/// External identifiers of a place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalIds {
+    /// Level of the place.
+    pub level: Level,
+    /// Code of the place, which is the mattricule for wilayas.
+    pub code: u16,
+    /// Wikidata item, e.g. `Q12345`.
+    pub wikidata: Option<String>,
+    /// OpenStreetMap relation id.
+    pub osm_relation: Option<u64>,
+}
+
+/// Error returned when a crosswalk table could not be loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrosswalkError {
+    /// Line of the table where the error was found, starting at 1.
+    pub line: usize,
+    /// What is wrong with that line.
+    pub message: String,
+}
+
+impl fmt::Display for CrosswalkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for CrosswalkError {}
+
+/// Table of external identifiers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Crosswalk {
+    entries: Vec<ExternalIds>,
+}
+
+impl Crosswalk {
+    /// Get the built-in crosswalk table.
+    /// # Example
+    /// ```
+    /// use leblad::crosswalk::Crosswalk;
+    /// use leblad::{wilaya, Place};
+    /// let wikidata = Crosswalk::builtin().wikidata_id(Place::Wilaya(wilaya!(16)));
+    /// assert_eq!(wikidata, Some("Q240405"));
+    /// ```
+    /// # Panics
+    /// This function panics if the embedded table is invalid, which is checked by our tests.
+    pub fn builtin() -> &'static Crosswalk {
+        static BUILTIN: OnceLock<Crosswalk> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            Crosswalk::from_csv(BUILTIN_CSV)
+                .unwrap_or_else(|e| panic!("invalid built-in crosswalk: {}", e))
+        })
+    }
+
+    /// Load a crosswalk table from CSV.
+    /// The header is `level,code,wikidata,osm_relation`, empty lines and lines starting with `#`
+    /// are ignored. Every row must refer to an existing place.
+    /// # Example
+    /// ```
+    /// use leblad::crosswalk::Crosswalk;
+    /// use leblad::Level;
+    /// let crosswalk = Crosswalk::from_csv("level,code,wikidata,osm_relation\nwilaya,1,Q1,42\n").unwrap();
+    /// assert_eq!(crosswalk.get(Level::Wilaya, 1).unwrap().osm_relation, Some(42));
+    /// ```
+    pub fn from_csv(csv: &str) -> Result<Crosswalk, CrosswalkError> {
+        let mut entries = vec![];
+        let mut header_found = false;
+        for (i, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: String| CrosswalkError {
+                line: i + 1,
+                message,
+            };
+            let fields = line.split(',').map(str::trim).collect::<Vec<&str>>();
+            if !header_found {
+                if fields != ["level", "code", "wikidata", "osm_relation"] {
+                    return Err(error(format!("unexpected header `{}`", line)));
+                }
+                header_found = true;
+                continue;
+            }
+            if fields.len() != 4 {
+                return Err(error(format!("expected 4 fields, found {}", fields.len())));
+            }
+            let level = Level::parse(fields[0])
+                .ok_or_else(|| error(format!("unknown level `{}`", fields[0])))?;
+            let code = fields[1]
+                .parse::<u16>()
+                .map_err(|_| error(format!("invalid code `{}`", fields[1])))?;
+            if Place::get(level, code).is_none() {
+                return Err(error(format!("unknown {} {}", level.as_str(), code)));
+            }
+            let wikidata = match fields[2] {
+                "" => None,
+                qid if is_wikidata_id(qid) => Some(qid.to_string()),
+                qid => return Err(error(format!("invalid Wikidata item `{}`", qid))),
+            };
+            let osm_relation = match fields[3] {
+                "" => None,
+                id => Some(
+                    id.parse::<u64>()
+                        .map_err(|_| error(format!("invalid OpenStreetMap relation `{}`", id)))?,
+                ),
+            };
+            entries.push(ExternalIds {
+                level,
+                code,
+                wikidata,
+                osm_relation,
+            });
+        }
+        Ok(Crosswalk { entries })
+    }
+
+    /// Get all the entries of the table.
+    pub fn entries(&self) -> &[ExternalIds] {
+        &self.entries
+    }
+
+    /// Get the external identifiers of a place by level and code.
+    pub fn get(&self, level: Level, code: u16) -> Option<&ExternalIds> {
+        self.entries
+            .iter()
+            .find(|entry| entry.level == level && entry.code == code)
+    }
+
+    /// Get the Wikidata item of a place.
+    pub fn wikidata_id(&self, place: Place) -> Option<&str> {
+        self.get(place.level(), place.code())
+            .and_then(|entry| entry.wikidata.as_deref())
+    }
+
+    /// Get the OpenStreetMap relation id of a place.
+    pub fn osm_relation_id(&self, place: Place) -> Option<u64> {
+        self.get(place.level(), place.code())
+            .and_then(|entry| entry.osm_relation)
+    }
+
+    /// Get the place of a Wikidata item.
+    pub fn find_by_wikidata_id(&self, wikidata: &str) -> Option<Place<'static>> {
+        self.entries
+            .iter()
+            .find(|entry| entry.wikidata.as_deref() == Some(wikidata))
+            .and_then(|entry| Place::get(entry.level, entry.code))
+    }
+
+    /// Get the place of an OpenStreetMap relation.
+    pub fn find_by_osm_relation_id(&self, osm_relation: u64) -> Option<Place<'static>> {
+        self.entries
+            .iter()
+            .find(|entry| entry.osm_relation == Some(osm_relation))
+            .and_then(|entry| Place::get(entry.level, entry.code))
+    }
+}
+
+fn is_wikidata_id(id: &str) -> bool {
+    id.len() > 1 && id.starts_with('Q') && id[1..].bytes().all(|b| b.is_ascii_digit())
+}
+
+impl Baladyia {
+    /// Get the ONS commune code of the baladyia.
+    /// # Example
+    /// ```
+    /// use leblad::get_baladyia_by_code;
+    /// let baladyia = get_baladyia_by_code(101).unwrap();
+    /// assert_eq!(baladyia.ons_code(), "0101");
+    /// ```
+    pub fn ons_code(&self) -> String {
+        format!("{:04}", self.code)
+    }
+}
+
+/// Get baladyia by ONS commune code.
+/// # Example
+/// ```
+/// use leblad::crosswalk::get_baladyia_by_ons_code;
+/// let baladyia = get_baladyia_by_ons_code("0101");
+/// assert_eq!(baladyia.unwrap().name, "ADRAR");
+/// ```
+pub fn get_baladyia_by_ons_code<'a>(ons_code: &str) -> Option<&'a Baladyia> {
+    if ons_code.len() != 4 || !ons_code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    ons_code.parse().ok().and_then(get_baladyia_by_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ALL_WILAYAS;
+
+    const HEADER: &str = "level,code,wikidata,osm_relation\n";
+
+    #[test]
+    fn builtin_crosswalk_is_valid() {
+        assert!(Crosswalk::from_csv(BUILTIN_CSV).is_ok());
+    }
+
+    #[test]
+    fn builtin_crosswalk_links_algiers() {
+        let crosswalk = Crosswalk::builtin();
+        let algiers = Place::Wilaya(crate::get_wilaya_by_code(16).unwrap());
+        assert_eq!(crosswalk.wikidata_id(algiers), Some("Q240405"));
+        assert_eq!(crosswalk.find_by_wikidata_id("Q240405"), Some(algiers));
+    }
+
+    #[test]
+    fn lookups_in_both_directions() {
+        let csv = format!("{}wilaya,1,Q123,456\nbaladyia,101,,789\n", HEADER);
+        let crosswalk = Crosswalk::from_csv(&csv).unwrap();
+        let wilaya = Place::Wilaya(&ALL_WILAYAS[0]);
+        assert_eq!(crosswalk.wikidata_id(wilaya), Some("Q123"));
+        assert_eq!(crosswalk.osm_relation_id(wilaya), Some(456));
+        assert_eq!(crosswalk.find_by_wikidata_id("Q123"), Some(wilaya));
+        assert_eq!(crosswalk.find_by_osm_relation_id(456), Some(wilaya));
+
+        let baladyia = crosswalk.find_by_osm_relation_id(789).unwrap();
+        assert_eq!(baladyia.level(), Level::Baladyia);
+        assert_eq!(crosswalk.wikidata_id(baladyia), None);
+    }
+
+    #[test]
+    fn invalid_rows_are_rejected() {
+        let unknown_place = format!("{}wilaya,100,,\n", HEADER);
+        assert_eq!(Crosswalk::from_csv(&unknown_place).unwrap_err().line, 2);
+        let invalid_qid = format!("{}wilaya,1,123,\n", HEADER);
+        assert!(Crosswalk::from_csv(&invalid_qid).is_err());
+        let invalid_osm = format!("{}wilaya,1,,abc\n", HEADER);
+        assert!(Crosswalk::from_csv(&invalid_osm).is_err());
+        assert!(Crosswalk::from_csv("code,level\n").is_err());
+    }
+
+    #[test]
+    fn get_existing_baladyia_by_ons_code() {
+        let res = get_baladyia_by_ons_code("0101");
+        assert!(res.is_some());
+        assert_eq!(res.unwrap().ons_code(), "0101");
+    }
+
+    #[test]
+    fn get_non_existing_baladyia_by_ons_code() {
+        assert!(get_baladyia_by_ons_code("101").is_none());
+        assert!(get_baladyia_by_ons_code("9999").is_none());
+    }
+}
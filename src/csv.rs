@@ -0,0 +1,797 @@
+//! CSV export and import of the administrative hierarchy.
+//!
+//! The flattened table has one row per baladyia with the codes and names of its daira and
+//! wilaya, for spreadsheets. Dairats without baladyiats, and wilayas without dairats, get a
+//! row with empty fields for the missing levels. Its header is [`FLAT_HEADER`].
+//!
+//! Each level can also be exported to its own table, with the code of the parent places:
+//!
+//! - wilayas: [`WILAYAS_HEADER`], lists of codes are separated by spaces,
+//! - dairats: [`DAIRATS_HEADER`],
+//! - baladyiats: [`BALADYIATS_HEADER`].
+//!
+//! A [`Dataset`] can be rebuilt from either format. The flattened table only has codes and
+//! names, so wilayas imported from it have no phone codes, postal codes nor adjacent
+//! wilayas. Fields containing commas or quotes are quoted, as in RFC 4180.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::leak::{leak, leak_str};
+use crate::{Baladyia, Daira, Wilaya};
+
+/// Header of the flattened table.
+pub const FLAT_HEADER: &str = "wilaya_code,wilaya_name,wilaya_name_ar,wilaya_name_ber,wilaya_name_en,daira_code,daira_name,daira_name_ar,daira_name_en,baladyia_code,baladyia_name,baladyia_name_ar,baladyia_name_en";
+/// Header of the wilayas table.
+pub const WILAYAS_HEADER: &str =
+    "code,name,name_ar,name_ber,name_en,phone_codes,postal_codes,adjacent_wilayas";
+/// Header of the dairats table.
+pub const DAIRATS_HEADER: &str = "code,wilaya_code,name,name_ar,name_en";
+/// Header of the baladyiats table.
+pub const BALADYIATS_HEADER: &str = "code,daira_code,wilaya_code,name,name_ar,name_en";
+
+/// Error returned when a table could not be imported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvError {
+    /// Table where the error was found: `flat`, `wilayas`, `dairats` or `baladyiats`.
+    pub table: &'static str,
+    /// Line of the table where the error was found, starting at 1.
+    pub line: usize,
+    /// What is wrong with that line.
+    pub message: String,
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} line {}: {}", self.table, self.line, self.message)
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+/// Export wilayas to the flattened table.
+/// # Example
+/// ```
+/// use leblad::csv::{to_flat_csv, FLAT_HEADER};
+/// use leblad::get_wilaya_list;
+/// let csv = to_flat_csv(get_wilaya_list());
+/// assert!(csv.starts_with(FLAT_HEADER));
+/// assert!(csv.contains("\n1,Adrar,"));
+/// ```
+pub fn to_flat_csv(wilayas: &[Wilaya]) -> String {
+    let mut csv = format!("{}\n", FLAT_HEADER);
+    for wilaya in wilayas {
+        let wilaya_fields = [
+            wilaya.mattricule.to_string(),
+            wilaya.name.to_string(),
+            wilaya.name_ar.to_string(),
+            wilaya.name_ber.to_string(),
+            wilaya.name_en.to_string(),
+        ];
+        if wilaya.dairats.is_empty() {
+            push_row(&mut csv, wilaya_fields.iter().chain(&empty(8)));
+        }
+        for daira in wilaya.dairats {
+            let daira_fields = [
+                daira.code.to_string(),
+                daira.name.to_string(),
+                daira.name_ar.to_string(),
+                daira.name_en.to_string(),
+            ];
+            let baladyiats = daira.baladyiats.unwrap_or_default();
+            if baladyiats.is_empty() {
+                let no_baladyia = empty(4);
+                let fields = wilaya_fields
+                    .iter()
+                    .chain(&daira_fields)
+                    .chain(&no_baladyia);
+                push_row(&mut csv, fields);
+            }
+            for baladyia in baladyiats {
+                let baladyia_fields = [
+                    baladyia.code.to_string(),
+                    baladyia.name.to_string(),
+                    baladyia.name_ar.to_string(),
+                    baladyia.name_en.to_string(),
+                ];
+                let fields = wilaya_fields
+                    .iter()
+                    .chain(&daira_fields)
+                    .chain(&baladyia_fields);
+                push_row(&mut csv, fields);
+            }
+        }
+    }
+    csv
+}
+
+/// Export wilayas to the wilayas table.
+/// # Example
+/// ```
+/// use leblad::csv::wilayas_to_csv;
+/// use leblad::get_wilaya_list;
+/// let csv = wilayas_to_csv(get_wilaya_list());
+/// assert_eq!(csv.lines().count(), get_wilaya_list().len() + 1);
+/// ```
+pub fn wilayas_to_csv(wilayas: &[Wilaya]) -> String {
+    let mut csv = format!("{}\n", WILAYAS_HEADER);
+    for wilaya in wilayas {
+        let fields = [
+            wilaya.mattricule.to_string(),
+            wilaya.name.to_string(),
+            wilaya.name_ar.to_string(),
+            wilaya.name_ber.to_string(),
+            wilaya.name_en.to_string(),
+            join(wilaya.phone_codes),
+            join(wilaya.postal_codes),
+            join(wilaya.adjacent_wilayas),
+        ];
+        push_row(&mut csv, &fields);
+    }
+    csv
+}
+
+/// Export the dairats of wilayas to the dairats table.
+pub fn dairats_to_csv(wilayas: &[Wilaya]) -> String {
+    let mut csv = format!("{}\n", DAIRATS_HEADER);
+    for wilaya in wilayas {
+        for daira in wilaya.dairats {
+            let fields = [
+                daira.code.to_string(),
+                wilaya.mattricule.to_string(),
+                daira.name.to_string(),
+                daira.name_ar.to_string(),
+                daira.name_en.to_string(),
+            ];
+            push_row(&mut csv, &fields);
+        }
+    }
+    csv
+}
+
+/// Export the baladyiats of wilayas to the baladyiats table.
+pub fn baladyiats_to_csv(wilayas: &[Wilaya]) -> String {
+    let mut csv = format!("{}\n", BALADYIATS_HEADER);
+    for wilaya in wilayas {
+        for daira in wilaya.dairats {
+            for baladyia in daira.baladyiats.unwrap_or_default() {
+                let fields = [
+                    baladyia.code.to_string(),
+                    daira.code.to_string(),
+                    wilaya.mattricule.to_string(),
+                    baladyia.name.to_string(),
+                    baladyia.name_ar.to_string(),
+                    baladyia.name_en.to_string(),
+                ];
+                push_row(&mut csv, &fields);
+            }
+        }
+    }
+    csv
+}
+
+/// Places imported from CSV tables.
+///
+/// The imported places are leaked so they can be used wherever the built-in ones are, so a
+/// dataset should be loaded once, e.g. at startup.
+#[derive(Debug, Clone)]
+pub struct Dataset {
+    wilayas: &'static [Wilaya],
+}
+
+impl Dataset {
+    /// Import a dataset from the flattened table.
+    ///
+    /// Rows of a same wilaya or daira must agree on its names, and the code of every daira and
+    /// baladyia must start with the mattricule of its wilaya.
+    /// # Example
+    /// ```
+    /// use leblad::csv::{to_flat_csv, Dataset};
+    /// use leblad::get_wilaya_list;
+    /// let dataset = Dataset::from_flat_csv(&to_flat_csv(get_wilaya_list())).unwrap();
+    /// assert_eq!(dataset.get_baladyia_by_code(101).unwrap().name, "ADRAR");
+    /// assert!(dataset.get_wilaya_by_code(1).unwrap().phone_codes.is_empty());
+    /// ```
+    pub fn from_flat_csv(csv: &str) -> Result<Dataset, CsvError> {
+        let mut tree = Tree::default();
+        for (line, fields) in rows("flat", csv, FLAT_HEADER)? {
+            let error = |message: String| CsvError {
+                table: "flat",
+                line,
+                message,
+            };
+            let mattricule = code(&fields[0]).map_err(error)?;
+            let wilaya = WilayaNode {
+                mattricule,
+                names: names(&fields[1], &fields[2], &fields[4]),
+                name_ber: fields[3].clone(),
+                ..WilayaNode::default()
+            };
+            match tree.wilaya(mattricule) {
+                Some(existing) if existing.names != wilaya.names => {
+                    return Err(error(format!(
+                        "inconsistent names of wilaya {}",
+                        mattricule
+                    )))
+                }
+                Some(existing) if existing.name_ber != wilaya.name_ber => {
+                    return Err(error(format!(
+                        "inconsistent names of wilaya {}",
+                        mattricule
+                    )))
+                }
+                Some(_) => {}
+                None => tree.add_wilaya(wilaya).map_err(error)?,
+            }
+            if fields[5].is_empty() {
+                if fields[9..].iter().any(|field| !field.is_empty()) {
+                    return Err(error("baladyia without daira".to_string()));
+                }
+                continue;
+            }
+            let daira = DairaNode {
+                code: code(&fields[5]).map_err(error)?,
+                names: names(&fields[6], &fields[7], &fields[8]),
+                baladyiats: None,
+            };
+            match tree.daira(daira.code) {
+                Some((wilaya, existing))
+                    if wilaya != mattricule || existing.names != daira.names =>
+                {
+                    return Err(error(format!("inconsistent daira {}", daira.code)))
+                }
+                Some(_) => {}
+                None => tree.add_daira(mattricule, daira.clone()).map_err(error)?,
+            }
+            if !fields[9].is_empty() {
+                let baladyia = BaladyiaNode {
+                    code: code(&fields[9]).map_err(error)?,
+                    names: names(&fields[10], &fields[11], &fields[12]),
+                };
+                tree.add_baladyia(daira.code, mattricule, baladyia)
+                    .map_err(error)?;
+            }
+        }
+        Ok(tree.into_dataset())
+    }
+
+    /// Import a dataset from the tables of each level.
+    ///
+    /// Every daira must refer to a wilaya of the wilayas table and every baladyia to a daira of
+    /// that wilaya, and the code of dairats and baladyiats must start with the mattricule of
+    /// their wilaya. Adjacent wilayas must be in the wilayas table too. The tables do not hold
+    /// the optional data layers, such as coordinates or statistics, so imported places have none.
+    /// # Example
+    /// ```
+    /// use leblad::csv::{baladyiats_to_csv, dairats_to_csv, wilayas_to_csv, Dataset};
+    /// use leblad::get_wilaya_list;
+    /// let wilayas = get_wilaya_list();
+    /// let tables = [
+    ///     wilayas_to_csv(wilayas),
+    ///     dairats_to_csv(wilayas),
+    ///     baladyiats_to_csv(wilayas),
+    /// ];
+    /// let dataset = Dataset::from_csvs(&tables[0], &tables[1], &tables[2]).unwrap();
+    /// assert_eq!(wilayas_to_csv(dataset.wilayas()), tables[0]);
+    /// assert_eq!(dairats_to_csv(dataset.wilayas()), tables[1]);
+    /// assert_eq!(baladyiats_to_csv(dataset.wilayas()), tables[2]);
+    /// ```
+    pub fn from_csvs(wilayas: &str, dairats: &str, baladyiats: &str) -> Result<Dataset, CsvError> {
+        let mut tree = Tree::default();
+        let mut adjacent = vec![];
+        for (line, fields) in rows("wilayas", wilayas, WILAYAS_HEADER)? {
+            let error = |message: String| CsvError {
+                table: "wilayas",
+                line,
+                message,
+            };
+            let wilaya = WilayaNode {
+                mattricule: code(&fields[0]).map_err(error)?,
+                names: names(&fields[1], &fields[2], &fields[4]),
+                name_ber: fields[3].clone(),
+                phone_codes: codes(&fields[5]).map_err(error)?,
+                postal_codes: codes(&fields[6]).map_err(error)?,
+                adjacent_wilayas: codes(&fields[7]).map_err(error)?,
+                dairats: vec![],
+            };
+            adjacent.push((line, wilaya.adjacent_wilayas.clone()));
+            tree.add_wilaya(wilaya).map_err(error)?;
+        }
+        for (line, codes) in adjacent {
+            if let Some(unknown) = codes.iter().find(|code| tree.wilaya(**code).is_none()) {
+                return Err(CsvError {
+                    table: "wilayas",
+                    line,
+                    message: format!("unknown adjacent wilaya {}", unknown),
+                });
+            }
+        }
+        for (line, fields) in rows("dairats", dairats, DAIRATS_HEADER)? {
+            let error = |message: String| CsvError {
+                table: "dairats",
+                line,
+                message,
+            };
+            let daira = DairaNode {
+                code: code(&fields[0]).map_err(error)?,
+                names: names(&fields[2], &fields[3], &fields[4]),
+                baladyiats: None,
+            };
+            let wilaya = code(&fields[1]).map_err(error)?;
+            tree.add_daira(wilaya, daira).map_err(error)?;
+        }
+        for (line, fields) in rows("baladyiats", baladyiats, BALADYIATS_HEADER)? {
+            let error = |message: String| CsvError {
+                table: "baladyiats",
+                line,
+                message,
+            };
+            let baladyia = BaladyiaNode {
+                code: code(&fields[0]).map_err(error)?,
+                names: names(&fields[3], &fields[4], &fields[5]),
+            };
+            let daira = code(&fields[1]).map_err(error)?;
+            let wilaya = code(&fields[2]).map_err(error)?;
+            tree.add_baladyia(daira, wilaya, baladyia).map_err(error)?;
+        }
+        Ok(tree.into_dataset())
+    }
+
+    /// Get the imported list of wilayas.
+    pub fn wilayas(&self) -> &'static [Wilaya] {
+        self.wilayas
+    }
+
+    /// Get an imported wilaya by mattricule.
+    pub fn get_wilaya_by_code(&self, mattricule: u16) -> Option<&'static Wilaya> {
+        self.wilayas.iter().find(|w| w.mattricule == mattricule)
+    }
+
+    /// Get an imported daira by code.
+    pub fn get_daira_by_code(&self, daira_code: u16) -> Option<&'static Daira> {
+        self.wilayas
+            .iter()
+            .flat_map(|w| w.dairats.iter())
+            .find(|d| d.code == daira_code)
+    }
+
+    /// Get an imported baladyia by code.
+    pub fn get_baladyia_by_code(&self, baladyia_code: u16) -> Option<&'static Baladyia> {
+        self.wilayas
+            .iter()
+            .flat_map(|w| w.dairats.iter())
+            .flat_map(|d| d.baladyiats.into_iter().flatten())
+            .find(|b| b.code == baladyia_code)
+    }
+}
+
+/// Names of a place: main, Arabic and English.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Names(String, String, String);
+
+#[derive(Debug, Clone, Default)]
+struct WilayaNode {
+    mattricule: u16,
+    names: Names,
+    name_ber: String,
+    phone_codes: Vec<u16>,
+    postal_codes: Vec<u16>,
+    adjacent_wilayas: Vec<u16>,
+    dairats: Vec<DairaNode>,
+}
+
+#[derive(Debug, Clone)]
+struct DairaNode {
+    code: u16,
+    names: Names,
+    baladyiats: Option<Vec<BaladyiaNode>>,
+}
+
+#[derive(Debug, Clone)]
+struct BaladyiaNode {
+    code: u16,
+    names: Names,
+}
+
+/// Places being imported, checking the consistency of codes as they are added.
+#[derive(Debug, Default)]
+struct Tree {
+    wilayas: Vec<WilayaNode>,
+    /// Wilaya index of each daira.
+    dairats: HashMap<u16, usize>,
+    /// Daira code of each baladyia.
+    baladyiats: HashMap<u16, u16>,
+}
+
+impl Tree {
+    fn wilaya(&self, mattricule: u16) -> Option<&WilayaNode> {
+        self.wilayas.iter().find(|w| w.mattricule == mattricule)
+    }
+
+    /// Gets a daira with the mattricule of its wilaya.
+    fn daira(&self, code: u16) -> Option<(u16, &DairaNode)> {
+        let wilaya = &self.wilayas[*self.dairats.get(&code)?];
+        let daira = wilaya.dairats.iter().find(|d| d.code == code)?;
+        Some((wilaya.mattricule, daira))
+    }
+
+    fn add_wilaya(&mut self, wilaya: WilayaNode) -> Result<(), String> {
+        if self.wilaya(wilaya.mattricule).is_some() {
+            return Err(format!("duplicate wilaya {}", wilaya.mattricule));
+        }
+        self.wilayas.push(wilaya);
+        Ok(())
+    }
+
+    fn add_daira(&mut self, mattricule: u16, daira: DairaNode) -> Result<(), String> {
+        let index = self
+            .wilayas
+            .iter()
+            .position(|w| w.mattricule == mattricule)
+            .ok_or_else(|| format!("unknown wilaya {}", mattricule))?;
+        if daira.code / 100 != mattricule {
+            return Err(format!(
+                "daira {} is not in wilaya {}",
+                daira.code, mattricule
+            ));
+        }
+        if self.dairats.insert(daira.code, index).is_some() {
+            return Err(format!("duplicate daira {}", daira.code));
+        }
+        self.wilayas[index].dairats.push(daira);
+        Ok(())
+    }
+
+    fn add_baladyia(
+        &mut self,
+        daira_code: u16,
+        mattricule: u16,
+        baladyia: BaladyiaNode,
+    ) -> Result<(), String> {
+        let index = *self
+            .dairats
+            .get(&daira_code)
+            .ok_or_else(|| format!("unknown daira {}", daira_code))?;
+        let wilaya = &mut self.wilayas[index];
+        if wilaya.mattricule != mattricule {
+            return Err(format!(
+                "daira {} is not in wilaya {}",
+                daira_code, mattricule
+            ));
+        }
+        if baladyia.code / 100 != mattricule {
+            return Err(format!(
+                "baladyia {} is not in wilaya {}",
+                baladyia.code, mattricule
+            ));
+        }
+        if self.baladyiats.insert(baladyia.code, daira_code).is_some() {
+            return Err(format!("duplicate baladyia {}", baladyia.code));
+        }
+        let daira = wilaya.dairats.iter_mut().find(|d| d.code == daira_code);
+        if let Some(daira) = daira {
+            daira.baladyiats.get_or_insert_with(Vec::new).push(baladyia);
+        }
+        Ok(())
+    }
+
+    fn into_dataset(self) -> Dataset {
+        let wilayas = self.wilayas.into_iter().map(to_static).collect();
+        Dataset {
+            wilayas: leak(wilayas),
+        }
+    }
+}
+
+/// Converts an imported wilaya, without the optional data layers.
+// The struct updates only fill fields when data layer features are enabled.
+#[allow(clippy::needless_update)]
+fn to_static(wilaya: WilayaNode) -> Wilaya {
+    let dairats = wilaya
+        .dairats
+        .into_iter()
+        .map(|daira| Daira {
+            code: daira.code,
+            name: leak_str(daira.names.0),
+            name_ar: leak_str(daira.names.1),
+            name_en: leak_str(daira.names.2),
+            baladyiats: daira.baladyiats.map(|baladyiats| {
+                leak(
+                    baladyiats
+                        .into_iter()
+                        .map(|baladyia| Baladyia {
+                            code: baladyia.code,
+                            name: leak_str(baladyia.names.0),
+                            name_en: leak_str(baladyia.names.2),
+                            name_ar: leak_str(baladyia.names.1),
+                            ..Baladyia::default()
+                        })
+                        .collect(),
+                ) as &[Baladyia]
+            }),
+            ..Daira::default()
+        })
+        .collect();
+    Wilaya {
+        mattricule: wilaya.mattricule,
+        name_ar: leak_str(wilaya.names.1),
+        name_ber: leak_str(wilaya.name_ber),
+        name_en: leak_str(wilaya.names.2),
+        name: leak_str(wilaya.names.0),
+        phone_codes: leak(wilaya.phone_codes),
+        postal_codes: leak(wilaya.postal_codes),
+        dairats: leak(dairats),
+        adjacent_wilayas: leak(wilaya.adjacent_wilayas),
+        ..Wilaya::default()
+    }
+}
+
+fn names(name: &str, name_ar: &str, name_en: &str) -> Names {
+    Names(name.to_string(), name_ar.to_string(), name_en.to_string())
+}
+
+fn code(field: &str) -> Result<u16, String> {
+    field
+        .parse::<u16>()
+        .map_err(|_| format!("invalid code `{}`", field))
+}
+
+/// Parses a list of codes separated by spaces.
+fn codes(field: &str) -> Result<Vec<u16>, String> {
+    field.split_whitespace().map(code).collect()
+}
+
+fn join(codes: &[u16]) -> String {
+    let codes = codes.iter().map(u16::to_string);
+    codes.collect::<Vec<String>>().join(" ")
+}
+
+fn empty(count: usize) -> Vec<String> {
+    vec![String::new(); count]
+}
+
+fn push_row<'a>(csv: &mut String, fields: impl IntoIterator<Item = &'a String>) {
+    let fields = fields.into_iter().map(|field| quote(field));
+    csv.push_str(&fields.collect::<Vec<Cow<str>>>().join(","));
+    csv.push('\n');
+}
+
+fn quote(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// Reads the rows of a table after checking its header, with their line numbers.
+/// Empty lines and lines starting with `#` are ignored.
+fn rows(
+    table: &'static str,
+    csv: &str,
+    header: &str,
+) -> Result<Vec<(usize, Vec<String>)>, CsvError> {
+    let columns = header.split(',').count();
+    let mut rows = vec![];
+    let mut header_found = false;
+    for (i, line) in csv.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |message: String| CsvError {
+            table,
+            line: i + 1,
+            message,
+        };
+        if !header_found {
+            if line.trim() != header {
+                return Err(error(format!("unexpected header `{}`", line.trim())));
+            }
+            header_found = true;
+            continue;
+        }
+        let fields = split(line).map_err(error)?;
+        if fields.len() != columns {
+            return Err(error(format!(
+                "expected {} fields, found {}",
+                columns,
+                fields.len()
+            )));
+        }
+        rows.push((i + 1, fields));
+    }
+    Ok(rows)
+}
+
+/// Splits a line into trimmed fields, which may be quoted.
+fn split(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = vec![];
+    let mut chars = line.chars().peekable();
+    loop {
+        let mut field = String::new();
+        while chars.next_if(|c| *c == ' ').is_some() {}
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next() {
+                    Some('"') if chars.next_if_eq(&'"').is_some() => field.push('"'),
+                    Some('"') => break,
+                    Some(c) => field.push(c),
+                    None => return Err("unterminated quoted field".to_string()),
+                }
+            }
+            while chars.next_if(|c| *c == ' ').is_some() {}
+            if !matches!(chars.peek(), None | Some(',')) {
+                return Err("unexpected characters after a quoted field".to_string());
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| *c != ',') {
+                field.push(c);
+            }
+            field.truncate(field.trim_end().len());
+        }
+        fields.push(field);
+        if chars.next().is_none() {
+            return Ok(fields);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ALL_WILAYAS;
+
+    #[test]
+    fn split_fields() {
+        assert_eq!(split("1, a ,").unwrap(), vec!["1", "a", ""]);
+        assert_eq!(
+            split(r#""a, b","say ""hi""" ,c"#).unwrap(),
+            vec!["a, b", "say \"hi\"", "c"]
+        );
+        assert!(split(r#""a"#).is_err());
+        assert!(split(r#""a"b,c"#).is_err());
+        assert_eq!(quote("a, \"b\""), r#""a, ""b""""#);
+    }
+
+    #[test]
+    fn flat_table_round_trip() {
+        let csv = to_flat_csv(ALL_WILAYAS);
+        let rows = csv.lines().count() - 1;
+        let baladyiats = crate::ALL_BALADYIATS.len();
+        let empty_dairats = crate::ALL_DAIRATS
+            .iter()
+            .filter(|d| d.baladyiats.is_none())
+            .count();
+        assert_eq!(rows, baladyiats + empty_dairats);
+        assert!(csv.contains(&format!("\n1,Adrar,{},", ALL_WILAYAS[0].name_ar)));
+
+        let dataset = Dataset::from_flat_csv(&csv).unwrap();
+        assert_eq!(to_flat_csv(dataset.wilayas()), csv);
+        let daira = dataset.get_daira_by_code(101).unwrap();
+        assert_eq!(daira.name, "ADRAR");
+        let baladyiats = daira.baladyiats.unwrap();
+        assert_eq!(baladyiats[0].name, "ADRAR");
+        assert!(baladyiats.iter().any(|b| b.name == "OULED AHMED TIMMI"));
+    }
+
+    #[test]
+    fn level_tables_round_trip() {
+        let dataset = Dataset::from_csvs(
+            &wilayas_to_csv(ALL_WILAYAS),
+            &dairats_to_csv(ALL_WILAYAS),
+            &baladyiats_to_csv(ALL_WILAYAS),
+        )
+        .unwrap();
+        let names = |wilayas: &[Wilaya]| {
+            wilayas
+                .iter()
+                .map(|w| (w.mattricule, w.name_ar, w.phone_codes, w.dairats.len()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(dataset.wilayas()), names(ALL_WILAYAS));
+        assert_eq!(dataset.get_daira_by_code(101).unwrap().name, "ADRAR");
+        assert_eq!(dataset.get_baladyia_by_code(101).unwrap().name, "ADRAR");
+    }
+
+    #[test]
+    fn inconsistent_flat_rows_are_rejected() {
+        let invalid = [
+            (
+                "1,Adrar,ادرار,,Adrar,,,,,101,X,X,X",
+                "baladyia without daira",
+            ),
+            (
+                "1,Adrar,ادرار,,Adrar,1601,X,X,X,,,,",
+                "daira 1601 is not in wilaya 1",
+            ),
+            (
+                "1,Adrar,ادرار,,Adrar,101,X,X,X,1601,X,X,X",
+                "baladyia 1601 is not in wilaya 1",
+            ),
+            ("x,Adrar,ادرار,,Adrar,,,,,,,,", "invalid code `x`"),
+            ("1,Adrar,ادرار,,Adrar,,,,", "expected 13 fields, found 9"),
+        ];
+        for (row, message) in invalid {
+            let csv = format!("{}\n{}\n", FLAT_HEADER, row);
+            let error = Dataset::from_flat_csv(&csv).unwrap_err();
+            assert_eq!(
+                (error.line, error.message.as_str()),
+                (2, message),
+                "{}",
+                row
+            );
+        }
+
+        let csv = format!(
+            "{}\n1,Adrar,ادرار,,Adrar,101,A,A,A,101,B,B,B\n1,Adrar,ادرار,,Adrar,101,Z,Z,Z,103,C,C,C\n",
+            FLAT_HEADER
+        );
+        let error = Dataset::from_flat_csv(&csv).unwrap_err();
+        assert_eq!(error.to_string(), "flat line 3: inconsistent daira 101");
+        let csv = format!(
+            "{}\n1,Adrar,A,,A,101,A,A,A,101,B,B,B\n1,Adrar,A,,A,101,A,A,A,101,B,B,B\n",
+            FLAT_HEADER
+        );
+        assert_eq!(
+            Dataset::from_flat_csv(&csv).unwrap_err().message,
+            "duplicate baladyia 101"
+        );
+        assert!(Dataset::from_flat_csv("code,name\n").is_err());
+    }
+
+    #[test]
+    fn inconsistent_level_tables_are_rejected() {
+        let wilayas = format!(
+            "{}\n1,Adrar,A,A,Adrar,49,1000,\n16,Alger,A,A,Algiers,21,,1\n",
+            WILAYAS_HEADER
+        );
+        let dairats = format!("{}\n101,1,ADRAR,A,ADRAR\n", DAIRATS_HEADER);
+        let baladyiats = format!("{}\n101,101,1,ADRAR,A,ADRAR\n", BALADYIATS_HEADER);
+        let dataset = Dataset::from_csvs(&wilayas, &dairats, &baladyiats).unwrap();
+        assert_eq!(
+            dataset.get_wilaya_by_code(16).unwrap().adjacent_wilayas,
+            &[1]
+        );
+
+        let error = |wilayas: &str, dairats: &str, baladyiats: &str| {
+            let wilayas = format!(
+                "{}\n1,Adrar,A,A,Adrar,49,1000,\n{}",
+                WILAYAS_HEADER, wilayas
+            );
+            let dairats = format!("{}\n101,1,ADRAR,A,ADRAR\n{}", DAIRATS_HEADER, dairats);
+            let baladyiats = format!("{}\n{}", BALADYIATS_HEADER, baladyiats);
+            Dataset::from_csvs(&wilayas, &dairats, &baladyiats)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            error("1,X,X,X,X,,,\n", "", ""),
+            "wilayas line 3: duplicate wilaya 1"
+        );
+        assert_eq!(
+            error("16,X,X,X,X,,,99\n", "", ""),
+            "wilayas line 3: unknown adjacent wilaya 99"
+        );
+        assert_eq!(
+            error("", "1601,16,X,X,X\n", ""),
+            "dairats line 3: unknown wilaya 16"
+        );
+        assert_eq!(
+            error("", "201,1,X,X,X\n", ""),
+            "dairats line 3: daira 201 is not in wilaya 1"
+        );
+        assert_eq!(
+            error("", "101,1,X,X,X\n", ""),
+            "dairats line 3: duplicate daira 101"
+        );
+        assert_eq!(
+            error("", "", "103,102,1,X,X,X\n"),
+            "baladyiats line 2: unknown daira 102"
+        );
+        assert_eq!(
+            error("16,X,X,X,X,,,\n", "", "1601,101,16,X,X,X\n"),
+            "baladyiats line 2: daira 101 is not in wilaya 16"
+        );
+    }
+}
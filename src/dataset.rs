@@ -0,0 +1,103 @@
+//! Provenance of the embedded dataset.
+
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::_auto_generated::DATASET_INFO;
+use crate::Level;
+
+/// A field of the data overridden by a patch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Override {
+    /// Level of the place.
+    pub level: Level,
+    /// Code of the place, which is the mattricule for wilayas.
+    pub code: u16,
+    /// Name of the field, e.g. `name_ar` or `baladyiats`.
+    pub field: &'static str,
+}
+
+/// Information about the upstream snapshot embedded at build time.
+///
+/// The build script fetches the latest commit of `master`, or the branch, tag or commit set in
+/// the `LEBLAD_DATA_REV` environment variable, which gives reproducible builds when pinned to a
+/// tag or commit. The revision is resolved to its commit before fetching when the GitHub API is
+/// reachable, and recorded as given otherwise. Local corrections can be applied over it with the
+/// `LEBLAD_PATCH` environment variable, see the `overlay` module for the patch format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DatasetInfo {
+    /// URL the data was fetched from.
+    pub source_url: &'static str,
+    /// Upstream commit the data was fetched from, as a full SHA, or the branch or tag it was
+    /// fetched from when the commit could not be resolved.
+    pub revision: &'static str,
+    /// Hash of the fetched data, 16 hexadecimal digits.
+    pub content_hash: &'static str,
+    /// Fetch time, in seconds since the UNIX epoch.
+    pub fetched_at: u64,
+    /// Number of wilayas.
+    pub wilayas: usize,
+    /// Number of dairats.
+    pub dairats: usize,
+    /// Number of baladyiats.
+    pub baladyiats: usize,
+    /// Fields overridden at build time by `LEBLAD_PATCH`.
+    pub overrides: &'static [Override],
+}
+
+impl DatasetInfo {
+    /// Get the fetch time.
+    pub fn fetched_at_time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.fetched_at)
+    }
+}
+
+impl fmt::Display for DatasetInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}@{} ({} wilayas, {} dairats, {} baladyiats",
+            self.revision, self.content_hash, self.wilayas, self.dairats, self.baladyiats
+        )?;
+        if !self.overrides.is_empty() {
+            write!(f, ", {} overrides", self.overrides.len())?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Get information about the embedded dataset.
+/// # Example
+/// ```
+/// use leblad::dataset_version;
+/// let info = dataset_version();
+/// assert_eq!(info.content_hash.len(), 16);
+/// println!("leblad data: {}", info);
+/// ```
+pub const fn dataset_version() -> &'static DatasetInfo {
+    &DATASET_INFO
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ALL_BALADYIATS, ALL_DAIRATS, ALL_WILAYAS};
+
+    #[test]
+    fn record_counts_match_the_data() {
+        let info = dataset_version();
+        assert_eq!(info.wilayas, ALL_WILAYAS.len());
+        assert_eq!(info.dairats, ALL_DAIRATS.len());
+        assert_eq!(info.baladyiats, ALL_BALADYIATS.len());
+    }
+
+    #[test]
+    fn provenance_is_recorded() {
+        let info = dataset_version();
+        assert!(info.source_url.contains(info.revision));
+        assert!(!info.revision.is_empty());
+        assert!(info.content_hash.bytes().all(|b| b.is_ascii_hexdigit()));
+        assert!(info.fetched_at_time() > UNIX_EPOCH);
+        assert!(info.to_string().starts_with(info.revision));
+    }
+}
@@ -0,0 +1,712 @@
+//! Differences between two snapshots of the dataset.
+//!
+//! A [`Snapshot`] is either the compiled-in dataset or a `WilayaList.json` file in the upstream
+//! format. [`diff`] compares two of them and lists the places that were added, removed, renamed
+//! or moved to another parent, along with changed phone codes, postal codes and adjacency.
+//! The resulting [`Changeset`] is printed in a human-readable form with `Display`, or exported
+//! with [`Changeset::to_json`].
+//!
+//! Places are identified by level and code, so a snapshot with two places of a level sharing a
+//! code is rejected rather than compared.
+
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde_json::{json, Value};
+
+use crate::{Level, ALL_WILAYAS};
+
+/// A place of a snapshot.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Record {
+    pub(crate) name: String,
+    pub(crate) name_ar: String,
+    pub(crate) name_en: String,
+    pub(crate) name_ber: Option<String>,
+    pub(crate) parent: Option<u16>,
+    pub(crate) phone_codes: Vec<u16>,
+    pub(crate) postal_codes: Vec<u16>,
+    pub(crate) adjacent_wilayas: Vec<u16>,
+}
+
+/// A version of the dataset.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    records: BTreeMap<(Level, u16), Record>,
+}
+
+/// Error returned when a snapshot could not be loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotError {
+    /// Path of the faulty value in the document, e.g. `[0].dairats[1].code`.
+    pub path: String,
+    /// What is wrong with that value.
+    pub message: String,
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl Snapshot {
+    /// Get a snapshot of the compiled-in dataset, or an error if it has duplicate codes.
+    pub fn compiled() -> Result<Snapshot, SnapshotError> {
+        let mut snapshot = Snapshot::default();
+        for wilaya in ALL_WILAYAS {
+            snapshot.insert(
+                Level::Wilaya,
+                wilaya.mattricule,
+                Record {
+                    name: wilaya.name.to_string(),
+                    name_ar: wilaya.name_ar.to_string(),
+                    name_en: wilaya.name_en.to_string(),
+                    name_ber: Some(wilaya.name_ber.to_string()),
+                    parent: None,
+                    phone_codes: wilaya.phone_codes.to_vec(),
+                    postal_codes: wilaya.postal_codes.to_vec(),
+                    adjacent_wilayas: wilaya.adjacent_wilayas.to_vec(),
+                },
+                "",
+            )?;
+            for daira in wilaya.dairats {
+                snapshot.insert(
+                    Level::Daira,
+                    daira.code,
+                    Record {
+                        name: daira.name.to_string(),
+                        name_ar: daira.name_ar.to_string(),
+                        name_en: daira.name_en.to_string(),
+                        parent: Some(wilaya.mattricule),
+                        ..Default::default()
+                    },
+                    "",
+                )?;
+                for baladyia in daira.baladyiats.into_iter().flatten() {
+                    snapshot.insert(
+                        Level::Baladyia,
+                        baladyia.code,
+                        Record {
+                            name: baladyia.name.to_string(),
+                            name_ar: baladyia.name_ar.to_string(),
+                            name_en: baladyia.name_en.to_string(),
+                            parent: Some(daira.code),
+                            ..Default::default()
+                        },
+                        "",
+                    )?;
+                }
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// Load a snapshot from a `WilayaList.json` document.
+    /// # Example
+    /// ```
+    /// use leblad::diff::Snapshot;
+    /// let json = r#"[{"mattricule": 1, "name": "Adrar", "name_ar": "أدرار", "name_ber": "ⴰⴷⵔⴰⵔ",
+    ///     "name_en": "Adrar", "phoneCodes": [49], "postalCodes": [1000],
+    ///     "adjacentWilayas": [], "dairats": []}]"#;
+    /// assert!(Snapshot::from_json(json).is_ok());
+    /// assert!(Snapshot::from_json("{}").is_err());
+    /// ```
+    pub fn from_json(json: &str) -> Result<Snapshot, SnapshotError> {
+        let value = serde_json::from_str::<Value>(json).map_err(|e| SnapshotError {
+            path: String::new(),
+            message: e.to_string(),
+        })?;
+        let mut snapshot = Snapshot::default();
+        for (i, wilaya) in array(&value, "")?.iter().enumerate() {
+            let path = format!("[{}]", i);
+            let mattricule = code(wilaya, &path, "mattricule")?;
+            snapshot.insert(
+                Level::Wilaya,
+                mattricule,
+                Record {
+                    name: string(wilaya, &path, "name")?,
+                    name_ar: string(wilaya, &path, "name_ar")?,
+                    name_en: string(wilaya, &path, "name_en")?,
+                    name_ber: Some(string(wilaya, &path, "name_ber")?),
+                    parent: None,
+                    phone_codes: codes(wilaya, &path, "phoneCodes")?,
+                    postal_codes: codes(wilaya, &path, "postalCodes")?,
+                    adjacent_wilayas: codes(wilaya, &path, "adjacentWilayas")?,
+                },
+                &format!("{}.mattricule", path),
+            )?;
+            let dairats_path = format!("{}.dairats", path);
+            for (j, daira) in array(field(wilaya, &path, "dairats")?, &dairats_path)?
+                .iter()
+                .enumerate()
+            {
+                let path = format!("{}[{}]", dairats_path, j);
+                let daira_code = code(daira, &path, "code")?;
+                snapshot.insert(
+                    Level::Daira,
+                    daira_code,
+                    Record {
+                        name: string(daira, &path, "name")?,
+                        name_ar: string(daira, &path, "name_ar")?,
+                        name_en: string(daira, &path, "name_en")?,
+                        parent: Some(mattricule),
+                        ..Default::default()
+                    },
+                    &format!("{}.code", path),
+                )?;
+                let baladyiats_path = format!("{}.baladyiats", path);
+                let baladyiats = match daira.get("baladyiats") {
+                    None | Some(Value::Null) => continue,
+                    Some(baladyiats) => array(baladyiats, &baladyiats_path)?,
+                };
+                for (k, baladyia) in baladyiats.iter().enumerate() {
+                    let path = format!("{}[{}]", baladyiats_path, k);
+                    snapshot.insert(
+                        Level::Baladyia,
+                        code(baladyia, &path, "code")?,
+                        Record {
+                            name: string(baladyia, &path, "name")?,
+                            name_ar: string(baladyia, &path, "name_ar")?,
+                            name_en: string(baladyia, &path, "name_en")?,
+                            parent: Some(daira_code),
+                            ..Default::default()
+                        },
+                        &format!("{}.code", path),
+                    )?;
+                }
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// Adds a place, or fails if the snapshot has another place of the level with that code.
+    fn insert(
+        &mut self,
+        level: Level,
+        code: u16,
+        record: Record,
+        path: &str,
+    ) -> Result<(), SnapshotError> {
+        match self.records.entry((level, code)) {
+            Entry::Occupied(_) => Err(error(
+                path,
+                format!("duplicate {} code {}", level.as_str(), code),
+            )),
+            Entry::Vacant(entry) => {
+                entry.insert(record);
+                Ok(())
+            }
+        }
+    }
+
+    /// Get the number of places of a level.
+    pub fn count(&self, level: Level) -> usize {
+        self.records.keys().filter(|(l, _)| *l == level).count()
+    }
+
+    /// Get a place of the snapshot.
+    #[cfg(feature = "sql")]
+    pub(crate) fn record(&self, level: Level, code: u16) -> Option<&Record> {
+        self.records.get(&(level, code))
+    }
+}
+
+fn error(path: &str, message: String) -> SnapshotError {
+    SnapshotError {
+        path: path.to_string(),
+        message,
+    }
+}
+
+fn array<'a>(value: &'a Value, path: &str) -> Result<&'a Vec<Value>, SnapshotError> {
+    value
+        .as_array()
+        .ok_or_else(|| error(path, "expected an array".to_string()))
+}
+
+fn field<'a>(value: &'a Value, path: &str, key: &str) -> Result<&'a Value, SnapshotError> {
+    value
+        .get(key)
+        .ok_or_else(|| error(path, format!("missing field `{}`", key)))
+}
+
+fn string(value: &Value, path: &str, key: &str) -> Result<String, SnapshotError> {
+    field(value, path, key)?
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| {
+            error(
+                &format!("{}.{}", path, key),
+                "expected a string".to_string(),
+            )
+        })
+}
+
+fn as_code(value: &Value, path: &str) -> Result<u16, SnapshotError> {
+    value
+        .as_u64()
+        .and_then(|code| u16::try_from(code).ok())
+        .ok_or_else(|| error(path, "expected a code".to_string()))
+}
+
+fn code(value: &Value, path: &str, key: &str) -> Result<u16, SnapshotError> {
+    as_code(field(value, path, key)?, &format!("{}.{}", path, key))
+}
+
+fn codes(value: &Value, path: &str, key: &str) -> Result<Vec<u16>, SnapshotError> {
+    let value = field(value, path, key)?;
+    let path = format!("{}.{}", path, key);
+    array(value, &path)?
+        .iter()
+        .enumerate()
+        .map(|(i, code)| as_code(code, &format!("{}[{}]", path, i)))
+        .collect()
+}
+
+/// What changed about a place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// The place is new.
+    Added {
+        /// Name of the place.
+        name: String,
+    },
+    /// The place does not exist anymore.
+    Removed {
+        /// Name of the place.
+        name: String,
+    },
+    /// One of the names of the place changed.
+    Renamed {
+        /// Name of the field, e.g. `name_ar`.
+        field: &'static str,
+        /// Previous value.
+        from: String,
+        /// New value.
+        to: String,
+    },
+    /// The place moved to another parent: a daira to another wilaya, a baladyia to another daira.
+    Moved {
+        /// Code of the previous parent.
+        from: u16,
+        /// Code of the new parent.
+        to: u16,
+    },
+    /// Codes attached to a wilaya changed.
+    CodesChanged {
+        /// Name of the field: `phone_codes`, `postal_codes` or `adjacent_wilayas`.
+        field: &'static str,
+        /// Codes that were added.
+        added: Vec<u16>,
+        /// Codes that were removed.
+        removed: Vec<u16>,
+    },
+}
+
+/// A change of a place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// Level of the place.
+    pub level: Level,
+    /// Code of the place.
+    pub code: u16,
+    /// What changed.
+    pub kind: ChangeKind,
+}
+
+impl Change {
+    /// Export the change as JSON.
+    pub fn to_json(&self) -> Value {
+        let mut value = match &self.kind {
+            ChangeKind::Added { name } => json!({ "change": "added", "name": name }),
+            ChangeKind::Removed { name } => json!({ "change": "removed", "name": name }),
+            ChangeKind::Renamed { field, from, to } => {
+                json!({ "change": "renamed", "field": field, "from": from, "to": to })
+            }
+            ChangeKind::Moved { from, to } => json!({
+                "change": "moved",
+                "parent_level": parent_level(self.level).map(|level| level.as_str()),
+                "from": from,
+                "to": to,
+            }),
+            ChangeKind::CodesChanged {
+                field,
+                added,
+                removed,
+            } => json!({
+                "change": "codes_changed",
+                "field": field,
+                "added": added,
+                "removed": removed,
+            }),
+        };
+        value["level"] = json!(self.level.as_str());
+        value["code"] = json!(self.code);
+        value
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let place = format!("{} {}", self.level.as_str(), self.code);
+        match &self.kind {
+            ChangeKind::Added { name } => write!(f, "+ {} {}", place, name),
+            ChangeKind::Removed { name } => write!(f, "- {} {}", place, name),
+            ChangeKind::Renamed { field, from, to } => {
+                write!(f, "~ {} {}: {} -> {}", place, field, from, to)
+            }
+            ChangeKind::Moved { from, to } => {
+                let parent = parent_level(self.level).map_or("", |level| level.as_str());
+                write!(f, "> {} moved from {} {} to {}", place, parent, from, to)
+            }
+            ChangeKind::CodesChanged {
+                field,
+                added,
+                removed,
+            } => {
+                write!(f, "~ {} {}:", place, field)?;
+                for code in added {
+                    write!(f, " +{}", code)?;
+                }
+                for code in removed {
+                    write!(f, " -{}", code)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn parent_level(level: Level) -> Option<Level> {
+    match level {
+        Level::Wilaya => None,
+        Level::Daira => Some(Level::Wilaya),
+        Level::Baladyia => Some(Level::Daira),
+    }
+}
+
+/// Changes between two snapshots, sorted by level and code.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Changeset {
+    changes: Vec<Change>,
+}
+
+impl Changeset {
+    /// Get all the changes.
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Check if the snapshots are identical.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Export the changeset as a JSON array of changes.
+    /// # Example
+    /// ```
+    /// use leblad::diff::{diff, Snapshot};
+    /// let changeset = diff(&Snapshot::compiled().unwrap(), &Snapshot::default());
+    /// let json = changeset.to_json();
+    /// assert_eq!(json[0]["change"], "removed");
+    /// assert_eq!(json[0]["level"], "wilaya");
+    /// ```
+    pub fn to_json(&self) -> Value {
+        Value::Array(self.changes.iter().map(Change::to_json).collect())
+    }
+}
+
+impl fmt::Display for Changeset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.changes.is_empty() {
+            return writeln!(f, "no changes");
+        }
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare two snapshots.
+/// # Example
+/// ```
+/// use leblad::diff::{diff, Snapshot};
+/// let compiled = Snapshot::compiled().unwrap();
+/// assert!(diff(&compiled, &compiled).is_empty());
+/// ```
+pub fn diff(old: &Snapshot, new: &Snapshot) -> Changeset {
+    let mut changes = vec![];
+    for (&(level, code), old_record) in &old.records {
+        let change = |kind| Change { level, code, kind };
+        let Some(new_record) = new.records.get(&(level, code)) else {
+            changes.push(change(ChangeKind::Removed {
+                name: old_record.name.clone(),
+            }));
+            continue;
+        };
+        let names = [
+            ("name", &old_record.name, &new_record.name),
+            ("name_ar", &old_record.name_ar, &new_record.name_ar),
+            ("name_en", &old_record.name_en, &new_record.name_en),
+        ];
+        for (field, from, to) in names {
+            if from != to {
+                changes.push(change(ChangeKind::Renamed {
+                    field,
+                    from: from.clone(),
+                    to: to.clone(),
+                }));
+            }
+        }
+        if let (Some(from), Some(to)) = (&old_record.name_ber, &new_record.name_ber) {
+            if from != to {
+                changes.push(change(ChangeKind::Renamed {
+                    field: "name_ber",
+                    from: from.clone(),
+                    to: to.clone(),
+                }));
+            }
+        }
+        if let (Some(from), Some(to)) = (old_record.parent, new_record.parent) {
+            if from != to {
+                changes.push(change(ChangeKind::Moved { from, to }));
+            }
+        }
+        let codes = [
+            (
+                "phone_codes",
+                &old_record.phone_codes,
+                &new_record.phone_codes,
+            ),
+            (
+                "postal_codes",
+                &old_record.postal_codes,
+                &new_record.postal_codes,
+            ),
+            (
+                "adjacent_wilayas",
+                &old_record.adjacent_wilayas,
+                &new_record.adjacent_wilayas,
+            ),
+        ];
+        for (field, from, to) in codes {
+            let added = difference(to, from);
+            let removed = difference(from, to);
+            if !added.is_empty() || !removed.is_empty() {
+                changes.push(change(ChangeKind::CodesChanged {
+                    field,
+                    added,
+                    removed,
+                }));
+            }
+        }
+    }
+    for (&(level, code), new_record) in &new.records {
+        if !old.records.contains_key(&(level, code)) {
+            changes.push(Change {
+                level,
+                code,
+                kind: ChangeKind::Added {
+                    name: new_record.name.clone(),
+                },
+            });
+        }
+    }
+    changes.sort_by_key(|change| (change.level, change.code));
+    Changeset { changes }
+}
+
+/// Codes of `a` that are not in `b`, sorted.
+fn difference(a: &[u16], b: &[u16]) -> Vec<u16> {
+    let mut codes = a
+        .iter()
+        .filter(|code| !b.contains(code))
+        .copied()
+        .collect::<Vec<u16>>();
+    codes.sort_unstable();
+    codes.dedup();
+    codes
+}
+
+/// Compare the compiled-in dataset with a `WilayaList.json` document.
+pub fn diff_with_compiled(json: &str) -> Result<Changeset, SnapshotError> {
+    Ok(diff(&Snapshot::compiled()?, &Snapshot::from_json(json)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(json: Value) -> Snapshot {
+        Snapshot::from_json(&json.to_string()).unwrap()
+    }
+
+    fn wilaya(mattricule: u16, name: &str, dairats: Value) -> Value {
+        json!({
+            "mattricule": mattricule,
+            "name": name,
+            "name_ar": "",
+            "name_ber": "",
+            "name_en": name,
+            "phoneCodes": [mattricule],
+            "postalCodes": [mattricule * 1000],
+            "adjacentWilayas": [],
+            "dairats": dairats,
+        })
+    }
+
+    fn place(code: u16, name: &str) -> Value {
+        json!({ "code": code, "name": name, "name_ar": "", "name_en": name, "baladyiats": null })
+    }
+
+    #[test]
+    fn compiled_snapshot_round_trips_through_json() {
+        let compiled = Snapshot::compiled().unwrap();
+        let json = ALL_WILAYAS
+            .iter()
+            .map(|w| {
+                json!({
+                    "mattricule": w.mattricule,
+                    "name": w.name,
+                    "name_ar": w.name_ar,
+                    "name_ber": w.name_ber,
+                    "name_en": w.name_en,
+                    "phoneCodes": w.phone_codes,
+                    "postalCodes": w.postal_codes,
+                    "adjacentWilayas": w.adjacent_wilayas,
+                    "dairats": w.dairats.iter().map(|d| json!({
+                        "code": d.code,
+                        "name": d.name,
+                        "name_ar": d.name_ar,
+                        "name_en": d.name_en,
+                        "baladyiats": d.baladyiats.map(|baladyiats| baladyiats.iter().map(|b| json!({
+                            "code": b.code,
+                            "name": b.name,
+                            "name_ar": b.name_ar,
+                            "name_en": b.name_en,
+                        })).collect::<Vec<Value>>()),
+                    })).collect::<Vec<Value>>(),
+                })
+            })
+            .collect::<Vec<Value>>();
+        assert_eq!(snapshot(Value::Array(json)), compiled);
+        assert_eq!(compiled.count(Level::Wilaya), ALL_WILAYAS.len());
+    }
+
+    #[test]
+    fn changes_at_every_level() {
+        let mut baladyia = place(101, "ADRAR");
+        baladyia.as_object_mut().unwrap().remove("baladyiats");
+        let mut daira = place(101, "ADRAR");
+        daira["baladyiats"] = json!([baladyia]);
+        let old = snapshot(json!([
+            wilaya(1, "Adrar", json!([daira, place(102, "AOULEF")])),
+            wilaya(2, "Chlef", json!([])),
+        ]));
+
+        let mut renamed = baladyia.clone();
+        renamed["name"] = json!("ADRAR VILLE");
+        let mut daira = place(101, "ADRAR");
+        daira["baladyiats"] = json!([renamed]);
+        let mut adrar = wilaya(1, "Adrar", json!([daira]));
+        adrar["postalCodes"] = json!([1000, 1001]);
+        let new = snapshot(json!([
+            adrar,
+            wilaya(49, "Timimoun", json!([place(102, "AOULEF")])),
+        ]));
+
+        let changeset = diff(&old, &new);
+        let expected = vec![
+            Change {
+                level: Level::Wilaya,
+                code: 1,
+                kind: ChangeKind::CodesChanged {
+                    field: "postal_codes",
+                    added: vec![1001],
+                    removed: vec![],
+                },
+            },
+            Change {
+                level: Level::Wilaya,
+                code: 2,
+                kind: ChangeKind::Removed {
+                    name: "Chlef".to_string(),
+                },
+            },
+            Change {
+                level: Level::Wilaya,
+                code: 49,
+                kind: ChangeKind::Added {
+                    name: "Timimoun".to_string(),
+                },
+            },
+            Change {
+                level: Level::Daira,
+                code: 102,
+                kind: ChangeKind::Moved { from: 1, to: 49 },
+            },
+            Change {
+                level: Level::Baladyia,
+                code: 101,
+                kind: ChangeKind::Renamed {
+                    field: "name",
+                    from: "ADRAR".to_string(),
+                    to: "ADRAR VILLE".to_string(),
+                },
+            },
+        ];
+        assert_eq!(changeset.changes(), expected.as_slice());
+        assert_eq!(
+            changeset.to_string(),
+            "~ wilaya 1 postal_codes: +1001\n\
+             - wilaya 2 Chlef\n\
+             + wilaya 49 Timimoun\n\
+             > daira 102 moved from wilaya 1 to 49\n\
+             ~ baladyia 101 name: ADRAR -> ADRAR VILLE\n"
+        );
+        assert_eq!(changeset.to_json()[3]["parent_level"], "wilaya");
+    }
+
+    #[test]
+    fn invalid_documents_are_rejected() {
+        let mut invalid = wilaya(1, "Adrar", json!([place(101, "ADRAR")]));
+        invalid["dairats"][0]["code"] = json!("101");
+        let error = Snapshot::from_json(&json!([invalid]).to_string()).unwrap_err();
+        assert_eq!(error.path, "[0].dairats[0].code");
+        assert!(Snapshot::from_json("[").is_err());
+    }
+
+    #[test]
+    fn duplicate_codes_are_rejected() {
+        let mut daira = place(101, "ADRAR");
+        daira["baladyiats"] = json!([place(101, "ADRAR"), place(101, "OULED AHMED TIMMI")]);
+        let json = json!([wilaya(1, "Adrar", json!([daira]))]).to_string();
+        let error = Snapshot::from_json(&json).unwrap_err();
+        assert_eq!(error.path, "[0].dairats[0].baladyiats[1].code");
+        assert_eq!(error.message, "duplicate baladyia code 101");
+
+        let json = json!([
+            wilaya(1, "Adrar", json!([place(101, "ADRAR")])),
+            wilaya(1, "Adrar", json!([])),
+        ]);
+        let error = Snapshot::from_json(&json.to_string()).unwrap_err();
+        assert_eq!(error.to_string(), "[1].mattricule: duplicate wilaya code 1");
+    }
+
+    #[test]
+    fn identical_snapshots() {
+        let compiled = Snapshot::compiled().unwrap();
+        let changeset = diff(&compiled, &compiled);
+        assert!(changeset.is_empty());
+        assert_eq!(changeset.to_string(), "no changes\n");
+    }
+}
@@ -0,0 +1,749 @@
+//! C bindings, exported by the `cdylib` build of the crate.
+//!
+//! The header is generated by cbindgen when building with the `ffi` feature, and checked in as
+//! `include/leblad.h`. `tests/c/harness.c` shows how to use it.
+//!
+//! Ownership rules:
+//!
+//! - Wilayas, dairats and baladyias are opaque pointers to the embedded dataset. They are
+//!   valid for the lifetime of the program and must not be freed. Each place has a single
+//!   address, so places can be compared by pointer.
+//! - Names are returned as [`LebladStr`], UTF-8 bytes that are *not* NUL-terminated, and
+//!   lists of codes as [`LebladCodes`]. Both point to the embedded dataset too.
+//! - Search results are allocated by the library, and must be freed with
+//!   [`leblad_places_free`].
+//!
+//! Every function accepts NULL pointers: lookups return NULL, names are empty and counts are
+//! zero. Strings passed to the library must be NUL-terminated UTF-8.
+
+use std::ffi::{c_char, CStr};
+use std::sync::OnceLock;
+
+use crate::{lookup, search, search_level, Baladyia, Daira, Level, Place, Wilaya, ALL_WILAYAS};
+
+/// Level of a place.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LebladLevel {
+    /// A wilaya.
+    Wilaya = 0,
+    /// A daira.
+    Daira = 1,
+    /// A baladyia.
+    Baladyia = 2,
+}
+
+impl From<Level> for LebladLevel {
+    fn from(level: Level) -> LebladLevel {
+        match level {
+            Level::Wilaya => LebladLevel::Wilaya,
+            Level::Daira => LebladLevel::Daira,
+            Level::Baladyia => LebladLevel::Baladyia,
+        }
+    }
+}
+
+impl From<LebladLevel> for Level {
+    fn from(level: LebladLevel) -> Level {
+        match level {
+            LebladLevel::Wilaya => Level::Wilaya,
+            LebladLevel::Daira => Level::Daira,
+            LebladLevel::Baladyia => Level::Baladyia,
+        }
+    }
+}
+
+/// UTF-8 string of the dataset, not NUL-terminated, e.g. printed with `printf("%.*s", (int)s.len, s.ptr)`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct LebladStr {
+    /// First byte of the string, never NULL.
+    pub ptr: *const u8,
+    /// Length of the string, in bytes.
+    pub len: usize,
+}
+
+impl LebladStr {
+    fn new(s: &'static str) -> LebladStr {
+        LebladStr {
+            ptr: s.as_ptr(),
+            len: s.len(),
+        }
+    }
+}
+
+/// List of codes of the dataset.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct LebladCodes {
+    /// First code of the list, never NULL.
+    pub ptr: *const u16,
+    /// Number of codes.
+    pub len: usize,
+}
+
+impl LebladCodes {
+    fn new(codes: &'static [u16]) -> LebladCodes {
+        LebladCodes {
+            ptr: codes.as_ptr(),
+            len: codes.len(),
+        }
+    }
+}
+
+/// A place found by a search.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LebladPlace {
+    /// Level of the place.
+    pub level: LebladLevel,
+    /// Code of the place, which is the mattricule for wilayas.
+    pub code: u16,
+}
+
+/// Places found by a search, to free with `leblad_places_free`.
+#[repr(C)]
+#[derive(Debug)]
+pub struct LebladPlaces {
+    /// First place, NULL when there are none.
+    pub ptr: *mut LebladPlace,
+    /// Number of places.
+    pub len: usize,
+}
+
+// The tables of the dataset are constants, which may be copied at each use. Places are
+// handed out from the hierarchy of this static instead, so that each one has a single address.
+static WILAYAS: &[Wilaya] = ALL_WILAYAS;
+
+/// Dairats and baladyiats of [`WILAYAS`] along with their parent, sorted by code.
+struct Hierarchy {
+    dairats: Vec<(&'static Daira, &'static Wilaya)>,
+    baladyiats: Vec<(&'static Baladyia, &'static Daira)>,
+}
+
+fn hierarchy() -> &'static Hierarchy {
+    static HIERARCHY: OnceLock<Hierarchy> = OnceLock::new();
+    HIERARCHY.get_or_init(|| {
+        let mut dairats = vec![];
+        let mut baladyiats = vec![];
+        for wilaya in WILAYAS {
+            for daira in wilaya.dairats {
+                dairats.push((daira, wilaya));
+                for baladyia in daira.baladyiats.into_iter().flatten() {
+                    baladyiats.push((baladyia, daira));
+                }
+            }
+        }
+        // The sort is stable, so the first place with a code is the first one in dataset
+        // order, as with `get_daira_by_code` and `get_baladyia_by_code`.
+        dairats.sort_by_key(|(daira, _)| daira.code);
+        baladyiats.sort_by_key(|(baladyia, _)| baladyia.code);
+        Hierarchy {
+            dairats,
+            baladyiats,
+        }
+    })
+}
+
+/// A daira or a baladyia, which are identified by their code.
+trait Coded {
+    fn code(&self) -> u16;
+}
+
+impl Coded for Daira {
+    fn code(&self) -> u16 {
+        self.code
+    }
+}
+
+impl Coded for Baladyia {
+    fn code(&self) -> u16 {
+        self.code
+    }
+}
+
+/// Gets the entries with a code, among entries sorted by code.
+fn with_code<'a, T: Coded, P>(entries: &'a [(&'a T, P)], code: u16) -> &'a [(&'a T, P)] {
+    let start = entries.partition_point(|(place, _)| place.code() < code);
+    let len = entries[start..].partition_point(|(place, _)| place.code() == code);
+    &entries[start..start + len]
+}
+
+/// Gets the parent of a place handed out by this library.
+fn parent_of<T: Coded, P: Copy>(entries: &[(&'static T, P)], place: &T) -> Option<P> {
+    with_code(entries, place.code())
+        .iter()
+        .find(|(other, _)| std::ptr::eq(*other, place))
+        .map(|(_, parent)| *parent)
+}
+
+/// Gets the place of the hierarchy equal to a place of the dataset tables.
+fn canonical<T: Coded + PartialEq, P>(entries: &[(&'static T, P)], place: &T) -> *const T {
+    let place = with_code(entries, place.code())
+        .iter()
+        .find(|(other, _)| *other == place);
+    place.map_or(std::ptr::null(), |(place, _)| *place)
+}
+
+fn wilaya_ptr(wilaya: Option<&'static Wilaya>) -> *const Wilaya {
+    wilaya.map_or(std::ptr::null(), |wilaya| wilaya)
+}
+
+fn daira_ptr(daira: Option<&'static Daira>) -> *const Daira {
+    daira.map_or(std::ptr::null(), |daira| daira)
+}
+
+fn baladyia_ptr(baladyia: Option<&'static Baladyia>) -> *const Baladyia {
+    baladyia.map_or(std::ptr::null(), |baladyia| baladyia)
+}
+
+fn find_wilaya(predicate: impl Fn(&Wilaya) -> bool) -> *const Wilaya {
+    wilaya_ptr(WILAYAS.iter().find(|wilaya| predicate(wilaya)))
+}
+
+/// Reads a NUL-terminated UTF-8 string, or returns `None` for NULL or invalid UTF-8.
+unsafe fn read_str<'a>(s: *const c_char) -> Option<&'a str> {
+    if s.is_null() {
+        return None;
+    }
+    CStr::from_ptr(s).to_str().ok()
+}
+
+/// Looks up the first place of a level with a name in any language.
+unsafe fn lookup_name(level: Level, name: *const c_char) -> Option<Place<'static>> {
+    let name = read_str(name)?;
+    // Codes are looked up by the functions taking integers.
+    if name.trim().parse::<u16>().is_ok() {
+        return None;
+    }
+    lookup(level, name).into_iter().next()
+}
+
+/// Get the number of wilayas.
+#[no_mangle]
+pub extern "C" fn leblad_wilaya_count() -> usize {
+    ALL_WILAYAS.len()
+}
+
+/// Get a wilaya by index, from 0 to `leblad_wilaya_count() - 1`, or NULL.
+#[no_mangle]
+pub extern "C" fn leblad_wilaya_at(index: usize) -> *const Wilaya {
+    wilaya_ptr(WILAYAS.get(index))
+}
+
+/// Get a wilaya by mattricule, or NULL.
+#[no_mangle]
+pub extern "C" fn leblad_wilaya_by_code(mattricule: u16) -> *const Wilaya {
+    find_wilaya(|wilaya| wilaya.mattricule == mattricule)
+}
+
+/// Get a wilaya by postal code, or NULL.
+#[no_mangle]
+pub extern "C" fn leblad_wilaya_by_zip_code(zip_code: u16) -> *const Wilaya {
+    find_wilaya(|wilaya| wilaya.postal_codes.contains(&zip_code))
+}
+
+/// Get a wilaya by phone code, or NULL.
+#[no_mangle]
+pub extern "C" fn leblad_wilaya_by_phone_code(phone_code: u16) -> *const Wilaya {
+    find_wilaya(|wilaya| wilaya.phone_codes.contains(&phone_code))
+}
+
+/// Get a wilaya by name in any language, ignoring case and accents, or NULL.
+///
+/// # Safety
+///
+/// `name` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_wilaya_by_name(name: *const c_char) -> *const Wilaya {
+    match lookup_name(Level::Wilaya, name) {
+        Some(Place::Wilaya(wilaya)) => leblad_wilaya_by_code(wilaya.mattricule),
+        _ => std::ptr::null(),
+    }
+}
+
+/// Get the mattricule of a wilaya, or 0.
+///
+/// # Safety
+///
+/// `wilaya` must be NULL or a wilaya returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_wilaya_code(wilaya: *const Wilaya) -> u16 {
+    wilaya.as_ref().map_or(0, |wilaya| wilaya.mattricule)
+}
+
+/// Get the name of a wilaya.
+///
+/// # Safety
+///
+/// `wilaya` must be NULL or a wilaya returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_wilaya_name(wilaya: *const Wilaya) -> LebladStr {
+    LebladStr::new(wilaya.as_ref().map_or("", |wilaya| wilaya.name))
+}
+
+/// Get the Arabic name of a wilaya.
+///
+/// # Safety
+///
+/// `wilaya` must be NULL or a wilaya returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_wilaya_name_ar(wilaya: *const Wilaya) -> LebladStr {
+    LebladStr::new(wilaya.as_ref().map_or("", |wilaya| wilaya.name_ar))
+}
+
+/// Get the Berber name of a wilaya, in Tifinagh.
+///
+/// # Safety
+///
+/// `wilaya` must be NULL or a wilaya returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_wilaya_name_ber(wilaya: *const Wilaya) -> LebladStr {
+    LebladStr::new(wilaya.as_ref().map_or("", |wilaya| wilaya.name_ber))
+}
+
+/// Get the English name of a wilaya.
+///
+/// # Safety
+///
+/// `wilaya` must be NULL or a wilaya returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_wilaya_name_en(wilaya: *const Wilaya) -> LebladStr {
+    LebladStr::new(wilaya.as_ref().map_or("", |wilaya| wilaya.name_en))
+}
+
+/// Get the phone codes of a wilaya.
+///
+/// # Safety
+///
+/// `wilaya` must be NULL or a wilaya returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_wilaya_phone_codes(wilaya: *const Wilaya) -> LebladCodes {
+    LebladCodes::new(wilaya.as_ref().map_or(&[], |wilaya| wilaya.phone_codes))
+}
+
+/// Get the postal codes of a wilaya.
+///
+/// # Safety
+///
+/// `wilaya` must be NULL or a wilaya returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_wilaya_postal_codes(wilaya: *const Wilaya) -> LebladCodes {
+    LebladCodes::new(wilaya.as_ref().map_or(&[], |wilaya| wilaya.postal_codes))
+}
+
+/// Get the mattricules of the wilayas adjacent to a wilaya.
+///
+/// # Safety
+///
+/// `wilaya` must be NULL or a wilaya returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_wilaya_adjacent_wilayas(wilaya: *const Wilaya) -> LebladCodes {
+    LebladCodes::new(
+        wilaya
+            .as_ref()
+            .map_or(&[], |wilaya| wilaya.adjacent_wilayas),
+    )
+}
+
+/// Get the number of dairats of a wilaya.
+///
+/// # Safety
+///
+/// `wilaya` must be NULL or a wilaya returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_wilaya_daira_count(wilaya: *const Wilaya) -> usize {
+    wilaya.as_ref().map_or(0, |wilaya| wilaya.dairats.len())
+}
+
+/// Get a daira of a wilaya by index, from 0 to `leblad_wilaya_daira_count(wilaya) - 1`, or
+/// NULL.
+///
+/// # Safety
+///
+/// `wilaya` must be NULL or a wilaya returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_wilaya_daira_at(
+    wilaya: *const Wilaya,
+    index: usize,
+) -> *const Daira {
+    daira_ptr(wilaya.as_ref().and_then(|wilaya| wilaya.dairats.get(index)))
+}
+
+/// Get a daira by code, or NULL.
+#[no_mangle]
+pub extern "C" fn leblad_daira_by_code(code: u16) -> *const Daira {
+    let dairats = with_code(&hierarchy().dairats, code);
+    daira_ptr(dairats.first().map(|(daira, _)| *daira))
+}
+
+/// Get a daira by name in any language, ignoring case and accents, or NULL.
+///
+/// # Safety
+///
+/// `name` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_daira_by_name(name: *const c_char) -> *const Daira {
+    match lookup_name(Level::Daira, name) {
+        Some(Place::Daira(daira)) => canonical(&hierarchy().dairats, daira),
+        _ => std::ptr::null(),
+    }
+}
+
+/// Get the code of a daira, or 0.
+///
+/// # Safety
+///
+/// `daira` must be NULL or a daira returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_daira_code(daira: *const Daira) -> u16 {
+    daira.as_ref().map_or(0, |daira| daira.code)
+}
+
+/// Get the wilaya of a daira, or NULL.
+///
+/// # Safety
+///
+/// `daira` must be NULL or a daira returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_daira_wilaya(daira: *const Daira) -> *const Wilaya {
+    let daira = daira.as_ref();
+    wilaya_ptr(daira.and_then(|daira| parent_of(&hierarchy().dairats, daira)))
+}
+
+/// Get the name of a daira.
+///
+/// # Safety
+///
+/// `daira` must be NULL or a daira returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_daira_name(daira: *const Daira) -> LebladStr {
+    LebladStr::new(daira.as_ref().map_or("", |daira| daira.name))
+}
+
+/// Get the Arabic name of a daira.
+///
+/// # Safety
+///
+/// `daira` must be NULL or a daira returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_daira_name_ar(daira: *const Daira) -> LebladStr {
+    LebladStr::new(daira.as_ref().map_or("", |daira| daira.name_ar))
+}
+
+/// Get the English name of a daira.
+///
+/// # Safety
+///
+/// `daira` must be NULL or a daira returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_daira_name_en(daira: *const Daira) -> LebladStr {
+    LebladStr::new(daira.as_ref().map_or("", |daira| daira.name_en))
+}
+
+/// Get the number of baladyiats of a daira, which is 0 when they are unknown.
+///
+/// # Safety
+///
+/// `daira` must be NULL or a daira returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_daira_baladyia_count(daira: *const Daira) -> usize {
+    daira
+        .as_ref()
+        .map_or(0, |daira| daira.baladyiats.map_or(0, <[Baladyia]>::len))
+}
+
+/// Get a baladyia of a daira by index, from 0 to `leblad_daira_baladyia_count(daira) - 1`,
+/// or NULL.
+///
+/// # Safety
+///
+/// `daira` must be NULL or a daira returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_daira_baladyia_at(
+    daira: *const Daira,
+    index: usize,
+) -> *const Baladyia {
+    let baladyiats = daira.as_ref().and_then(|daira| daira.baladyiats);
+    baladyia_ptr(baladyiats.and_then(|baladyiats| baladyiats.get(index)))
+}
+
+/// Get a baladyia by code, or NULL.
+#[no_mangle]
+pub extern "C" fn leblad_baladyia_by_code(code: u16) -> *const Baladyia {
+    let baladyiats = with_code(&hierarchy().baladyiats, code);
+    baladyia_ptr(baladyiats.first().map(|(baladyia, _)| *baladyia))
+}
+
+/// Get a baladyia by name in any language, ignoring case and accents, or NULL.
+///
+/// # Safety
+///
+/// `name` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_baladyia_by_name(name: *const c_char) -> *const Baladyia {
+    match lookup_name(Level::Baladyia, name) {
+        Some(Place::Baladyia(baladyia)) => canonical(&hierarchy().baladyiats, baladyia),
+        _ => std::ptr::null(),
+    }
+}
+
+/// Get the code of a baladyia, or 0.
+///
+/// # Safety
+///
+/// `baladyia` must be NULL or a baladyia returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_baladyia_code(baladyia: *const Baladyia) -> u16 {
+    baladyia.as_ref().map_or(0, |baladyia| baladyia.code)
+}
+
+/// Get the daira of a baladyia, or NULL.
+///
+/// # Safety
+///
+/// `baladyia` must be NULL or a baladyia returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_baladyia_daira(baladyia: *const Baladyia) -> *const Daira {
+    let baladyia = baladyia.as_ref();
+    daira_ptr(baladyia.and_then(|baladyia| parent_of(&hierarchy().baladyiats, baladyia)))
+}
+
+/// Get the name of a baladyia.
+///
+/// # Safety
+///
+/// `baladyia` must be NULL or a baladyia returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_baladyia_name(baladyia: *const Baladyia) -> LebladStr {
+    LebladStr::new(baladyia.as_ref().map_or("", |baladyia| baladyia.name))
+}
+
+/// Get the Arabic name of a baladyia.
+///
+/// # Safety
+///
+/// `baladyia` must be NULL or a baladyia returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_baladyia_name_ar(baladyia: *const Baladyia) -> LebladStr {
+    LebladStr::new(baladyia.as_ref().map_or("", |baladyia| baladyia.name_ar))
+}
+
+/// Get the English name of a baladyia.
+///
+/// # Safety
+///
+/// `baladyia` must be NULL or a baladyia returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_baladyia_name_en(baladyia: *const Baladyia) -> LebladStr {
+    LebladStr::new(baladyia.as_ref().map_or("", |baladyia| baladyia.name_en))
+}
+
+/// Search places of every level by name or code, best matches first, keeping at most `limit`
+/// results. The results must be freed with `leblad_places_free`.
+///
+/// # Safety
+///
+/// `query` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_search(query: *const c_char, limit: usize) -> LebladPlaces {
+    places(read_str(query).map(search), limit)
+}
+
+/// Search places of one level by name or code, see `leblad_search`. `level` is a
+/// `LebladLevel`, and other values find no places.
+///
+/// # Safety
+///
+/// `query` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_search_level(
+    level: u32,
+    query: *const c_char,
+    limit: usize,
+) -> LebladPlaces {
+    let level = match level {
+        0 => Level::Wilaya,
+        1 => Level::Daira,
+        2 => Level::Baladyia,
+        _ => return places(None, limit),
+    };
+    places(
+        read_str(query).map(|query| search_level(level, query)),
+        limit,
+    )
+}
+
+/// Free the results of a search.
+///
+/// # Safety
+///
+/// `places` must be returned by `leblad_search` or `leblad_search_level`, and not be freed
+/// already.
+#[no_mangle]
+pub unsafe extern "C" fn leblad_places_free(places: LebladPlaces) {
+    if !places.ptr.is_null() {
+        let places = std::ptr::slice_from_raw_parts_mut(places.ptr, places.len);
+        drop(Box::from_raw(places));
+    }
+}
+
+fn places(results: Option<Vec<Place<'static>>>, limit: usize) -> LebladPlaces {
+    let places = results
+        .unwrap_or_default()
+        .into_iter()
+        .take(limit)
+        .map(|place| LebladPlace {
+            level: place.level().into(),
+            code: place.code(),
+        })
+        .collect::<Box<[LebladPlace]>>();
+    if places.is_empty() {
+        return LebladPlaces {
+            ptr: std::ptr::null_mut(),
+            len: 0,
+        };
+    }
+    let len = places.len();
+    LebladPlaces {
+        ptr: Box::into_raw(places).cast::<LebladPlace>(),
+        len,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+
+    unsafe fn string(s: LebladStr) -> &'static str {
+        std::str::from_utf8(std::slice::from_raw_parts(s.ptr, s.len)).unwrap()
+    }
+
+    #[test]
+    fn lookups_and_names() {
+        unsafe {
+            let adrar = leblad_wilaya_by_code(1);
+            assert_eq!(string(leblad_wilaya_name(adrar)), "Adrar");
+            assert_eq!(string(leblad_wilaya_name_ar(adrar)), ALL_WILAYAS[0].name_ar);
+            assert_eq!(leblad_wilaya_by_zip_code(1000), adrar);
+            assert_eq!(
+                leblad_wilaya_by_phone_code(ALL_WILAYAS[0].phone_codes[0]),
+                adrar
+            );
+            let name = CString::new("ADRAR").unwrap();
+            assert_eq!(leblad_wilaya_by_name(name.as_ptr()), adrar);
+            let codes = leblad_wilaya_postal_codes(adrar);
+            assert!(std::slice::from_raw_parts(codes.ptr, codes.len).contains(&1000));
+
+            let name = CString::new("ouled ahmed timmi").unwrap();
+            let baladyia = leblad_baladyia_by_name(name.as_ptr());
+            assert_eq!(string(leblad_baladyia_name(baladyia)), "OULED AHMED TIMMI");
+            let daira = leblad_baladyia_daira(baladyia);
+            assert_eq!(daira, leblad_daira_by_code(101));
+            assert_eq!(leblad_daira_wilaya(daira), adrar);
+            assert_eq!(string(leblad_daira_name(daira)), "ADRAR");
+        }
+    }
+
+    #[test]
+    fn null_and_unknown_places() {
+        unsafe {
+            assert!(leblad_wilaya_by_code(100).is_null());
+            assert!(leblad_daira_by_code(1000).is_null());
+            assert!(leblad_wilaya_by_name(std::ptr::null()).is_null());
+            let code = CString::new("1").unwrap();
+            assert!(leblad_wilaya_by_name(code.as_ptr()).is_null());
+            let invalid = [0xffu8 as c_char, 0];
+            assert!(leblad_daira_by_name(invalid.as_ptr()).is_null());
+            assert_eq!(string(leblad_baladyia_name(std::ptr::null())), "");
+            assert_eq!(leblad_wilaya_phone_codes(std::ptr::null()).len, 0);
+            assert_eq!(leblad_daira_baladyia_count(std::ptr::null()), 0);
+            assert!(leblad_daira_baladyia_at(std::ptr::null(), 0).is_null());
+        }
+    }
+
+    #[test]
+    fn iteration() {
+        unsafe {
+            let count = leblad_wilaya_count();
+            assert_eq!(count, ALL_WILAYAS.len());
+            assert!(leblad_wilaya_at(count).is_null());
+            let adrar = leblad_wilaya_at(0);
+            let dairats = ALL_WILAYAS[0].dairats;
+            assert_eq!(leblad_wilaya_daira_count(adrar), dairats.len());
+            assert!(leblad_wilaya_daira_at(adrar, dairats.len()).is_null());
+            let daira = leblad_wilaya_daira_at(adrar, 0);
+            let baladyiats = dairats[0].baladyiats.unwrap();
+            assert_eq!(leblad_daira_baladyia_count(daira), baladyiats.len());
+            let baladyia = leblad_daira_baladyia_at(daira, 0);
+            assert_eq!(string(leblad_baladyia_name(baladyia)), "ADRAR");
+        }
+    }
+
+    #[test]
+    fn search_results_are_owned() {
+        unsafe {
+            let query = CString::new("adrar").unwrap();
+            let places = leblad_search(query.as_ptr(), 2);
+            assert_eq!(places.len, 2);
+            let first = *places.ptr;
+            assert_eq!(
+                first,
+                LebladPlace {
+                    level: LebladLevel::Wilaya,
+                    code: 1
+                }
+            );
+            leblad_places_free(places);
+            let level = LebladLevel::Baladyia as u32;
+            let places = leblad_search_level(level, query.as_ptr(), 10);
+            assert_eq!((*places.ptr).level, LebladLevel::Baladyia);
+            leblad_places_free(places);
+            let places = leblad_search_level(3, query.as_ptr(), 10);
+            assert!(places.ptr.is_null());
+            assert_eq!(places.len, 0);
+            leblad_places_free(places);
+            let places = leblad_search(query.as_ptr(), 0);
+            assert!(places.ptr.is_null());
+            leblad_places_free(places);
+        }
+    }
+
+    #[test]
+    fn duplicate_codes_keep_their_parent() {
+        let baladyias: &'static [Baladyia] = Box::leak(Box::new([
+            Baladyia {
+                code: 101,
+                name: "FIRST",
+                ..Default::default()
+            },
+            Baladyia {
+                code: 101,
+                name: "SECOND",
+                ..Default::default()
+            },
+        ]));
+        let dairats: &'static [Daira] = Box::leak(Box::new([
+            Daira {
+                code: 101,
+                ..Default::default()
+            },
+            Daira {
+                code: 102,
+                ..Default::default()
+            },
+        ]));
+        let entries = [(&baladyias[0], &dairats[0]), (&baladyias[1], &dairats[1])];
+        assert_eq!(with_code(&entries, 101).len(), 2);
+        assert!(with_code(&entries, 102).is_empty());
+        assert!(std::ptr::eq(
+            parent_of(&entries, &baladyias[1]).unwrap(),
+            &dairats[1]
+        ));
+        let copy = baladyias[1].clone();
+        assert!(parent_of(&entries, &copy).is_none());
+        assert!(std::ptr::eq(canonical(&entries, &copy), &baladyias[1]));
+    }
+}
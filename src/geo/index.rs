@@ -0,0 +1,166 @@
+//! R-tree indexes for spatial queries over places.
+
+use std::f64::consts::PI;
+use std::sync::OnceLock;
+
+use rstar::primitives::{GeomWithData, Rectangle};
+use rstar::RTree;
+
+use super::{BoundingBox, Coordinates, EARTH_RADIUS_KM};
+use crate::{Baladyia, Daira, Place, Wilaya, ALL_BALADYIATS, ALL_DAIRATS, ALL_WILAYAS};
+
+/// Spatial index over the places of a single level.
+///
+/// Places are indexed by their coordinates, or the center of their bounding box when they have
+/// none, and by their bounding box. Places with neither are not indexed.
+pub struct SpatialIndex<T: 'static> {
+    /// Points on the unit sphere, whose euclidean distance grows with the great-circle distance.
+    points: RTree<GeomWithData<[f64; 3], &'static T>>,
+    boxes: RTree<GeomWithData<Rectangle<[f64; 2]>, &'static T>>,
+}
+
+impl<T> SpatialIndex<T> {
+    fn new<I: Iterator<Item = (&'static T, Place<'static>)>>(places: I) -> Self {
+        let mut points = vec![];
+        let mut boxes = vec![];
+        for (item, place) in places {
+            let bounding_box = place.bounding_box();
+            let point = place
+                .coordinates()
+                .or_else(|| bounding_box.map(|bounding_box| bounding_box.center()));
+            if let Some(point) = point {
+                points.push(GeomWithData::new(unit_vector(&point), item));
+            }
+            if let Some(bounding_box) = bounding_box {
+                let rectangle = Rectangle::from_aabb(bounding_box.envelope());
+                boxes.push(GeomWithData::new(rectangle, item));
+            }
+        }
+        Self {
+            points: RTree::bulk_load(points),
+            boxes: RTree::bulk_load(boxes),
+        }
+    }
+
+    /// Get the places whose bounding box intersects a bounding box.
+    /// # Example
+    /// ```
+    /// use leblad::geo::{baladyiats_index, BoundingBox, Coordinates};
+    /// let viewport = BoundingBox::new(Coordinates::new(27.5, -0.5), Coordinates::new(28.0, 0.0));
+    /// let baladyiats = baladyiats_index().in_bounding_box(&viewport);
+    /// assert!(baladyiats.iter().any(|baladyia| baladyia.name == "ADRAR"));
+    /// ```
+    pub fn in_bounding_box(&self, bounding_box: &BoundingBox) -> Vec<&'static T> {
+        self.boxes
+            .locate_in_envelope_intersecting(&bounding_box.envelope())
+            .map(|rectangle| rectangle.data)
+            .collect()
+    }
+
+    /// Get the places within a radius of a point, from the nearest to the farthest.
+    /// # Example
+    /// ```
+    /// use leblad::geo::{wilayas_index, Coordinates};
+    /// let wilayas = wilayas_index().within_radius(&Coordinates::new(27.9, -0.3), 30.0);
+    /// assert_eq!(wilayas[0].name, "Adrar");
+    /// ```
+    pub fn within_radius(&self, center: &Coordinates, radius_km: f64) -> Vec<&'static T> {
+        let chord = 2.0 * ((radius_km / EARTH_RADIUS_KM).min(PI) / 2.0).sin();
+        self.points
+            .nearest_neighbor_iter_with_distance_2(&unit_vector(center))
+            .take_while(|(_, distance_2)| *distance_2 <= chord * chord)
+            .map(|(point, _)| point.data)
+            .collect()
+    }
+
+    /// Get the `k` nearest places to a point, from the nearest to the farthest.
+    /// # Example
+    /// ```
+    /// use leblad::geo::{dairats_index, Coordinates};
+    /// let dairats = dairats_index().nearest(&Coordinates::new(27.9, -0.3), 1);
+    /// assert_eq!(dairats[0].name, "ADRAR");
+    /// ```
+    pub fn nearest(&self, center: &Coordinates, k: usize) -> Vec<&'static T> {
+        self.points
+            .nearest_neighbor_iter(&unit_vector(center))
+            .take(k)
+            .map(|point| point.data)
+            .collect()
+    }
+}
+
+/// Converts coordinates to a point on the unit sphere.
+fn unit_vector(point: &Coordinates) -> [f64; 3] {
+    let (latitude, longitude) = (point.latitude.to_radians(), point.longitude.to_radians());
+    [
+        latitude.cos() * longitude.cos(),
+        latitude.cos() * longitude.sin(),
+        latitude.sin(),
+    ]
+}
+
+/// Get the spatial index of all wilayas.
+pub fn wilayas_index() -> &'static SpatialIndex<Wilaya> {
+    static INDEX: OnceLock<SpatialIndex<Wilaya>> = OnceLock::new();
+    INDEX.get_or_init(|| SpatialIndex::new(ALL_WILAYAS.iter().map(|w| (w, Place::Wilaya(w)))))
+}
+
+/// Get the spatial index of all dairats.
+pub fn dairats_index() -> &'static SpatialIndex<Daira> {
+    static INDEX: OnceLock<SpatialIndex<Daira>> = OnceLock::new();
+    INDEX.get_or_init(|| SpatialIndex::new(ALL_DAIRATS.iter().map(|d| (d, Place::Daira(d)))))
+}
+
+/// Get the spatial index of all baladyiats.
+pub fn baladyiats_index() -> &'static SpatialIndex<Baladyia> {
+    static INDEX: OnceLock<SpatialIndex<Baladyia>> = OnceLock::new();
+    INDEX.get_or_init(|| SpatialIndex::new(ALL_BALADYIATS.iter().map(|b| (b, Place::Baladyia(b)))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_vectors_distance_follows_great_circle_distance() {
+        let (a, b) = (Coordinates::new(36.75, 3.06), Coordinates::new(22.79, 5.52));
+        let (u, v) = (unit_vector(&a), unit_vector(&b));
+        let chord = ((u[0] - v[0]).powi(2) + (u[1] - v[1]).powi(2) + (u[2] - v[2]).powi(2)).sqrt();
+        let distance = 2.0 * EARTH_RADIUS_KM * (chord / 2.0).asin();
+        assert!((distance - a.distance_km(&b)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn radius_search_is_sorted_and_bounded() {
+        let center = Coordinates::new(27.9, -0.3);
+        let res = wilayas_index().within_radius(&center, 2_000.0);
+        assert!(!res.is_empty());
+        let distances = res
+            .iter()
+            .map(|wilaya| wilaya.coordinates.unwrap().distance_km(&center))
+            .collect::<Vec<f64>>();
+        assert!(distances.windows(2).all(|w| w[0] <= w[1]));
+        assert!(distances.iter().all(|distance| *distance <= 2_000.0));
+        assert!(wilayas_index()
+            .within_radius(&Coordinates::new(0.0, 0.0), 10.0)
+            .is_empty());
+    }
+
+    #[test]
+    fn k_nearest_places() {
+        let center = Coordinates::new(27.9, -0.3);
+        let res = baladyiats_index().nearest(&center, 2);
+        assert_eq!(res.len(), 2);
+        assert_eq!(*res[0], ALL_WILAYAS[0].dairats[0].baladyiats.unwrap()[0]);
+        assert!(baladyiats_index().nearest(&center, 0).is_empty());
+    }
+
+    #[test]
+    fn bounding_box_search() {
+        let viewport = BoundingBox::new(Coordinates::new(27.5, -0.5), Coordinates::new(28.0, 0.0));
+        let res = wilayas_index().in_bounding_box(&viewport);
+        assert_eq!(res, vec![&ALL_WILAYAS[0]]);
+        let elsewhere = BoundingBox::new(Coordinates::new(0.0, 0.0), Coordinates::new(1.0, 1.0));
+        assert!(wilayas_index().in_bounding_box(&elsewhere).is_empty());
+    }
+}
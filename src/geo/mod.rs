@@ -0,0 +1,591 @@
+//! Geographic coordinates, distances and reverse geocoding.
+//!
+//! Places carry a reference point in their `coordinates` field, embedded at build time from
+//! `data/coordinates.csv`. Places missing from that file have no coordinates, and are ignored
+//! by the lookups of this module.
+//!
+//! Boundaries of wilayas, dairats and baladyiats are embedded at build time from
+//! `data/boundaries.geojson`, simplified to about 100 meters, and indexed in one R-tree per
+//! level to locate points without any network access.
+//!
+//! Spatial queries over places (bounding box, radius and k-nearest) go through a
+//! [`SpatialIndex`].
+
+use std::sync::OnceLock;
+
+use rstar::{RTree, RTreeObject, AABB};
+
+use crate::{Baladyia, Daira, Level, Place, Wilaya, ALL_WILAYAS};
+
+pub use index::{baladyiats_index, dairats_index, wilayas_index, SpatialIndex};
+
+mod index;
+
+/// Mean radius of the Earth, in kilometers.
+const EARTH_RADIUS_KM: f64 = 6_371.008_8;
+
+/// Latitude and longitude of a point, in degrees.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    /// Latitude, in degrees.
+    pub latitude: f64,
+    /// Longitude, in degrees.
+    pub longitude: f64,
+}
+
+impl Coordinates {
+    /// Create coordinates from a latitude and a longitude, in degrees.
+    pub const fn new(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+        }
+    }
+
+    /// Get the great-circle distance to other coordinates, in kilometers.
+    /// # Example
+    /// ```
+    /// use leblad::geo::Coordinates;
+    /// let alger = Coordinates::new(36.7538, 3.0588);
+    /// let oran = Coordinates::new(35.6969, -0.6331);
+    /// assert_eq!(alger.distance_km(&oran).round(), 351.0);
+    /// ```
+    pub fn distance_km(&self, other: &Coordinates) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lon = (other.longitude - self.longitude).to_radians();
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+    }
+}
+
+/// Rectangle delimited by its south-west and north-east corners.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    /// South-west corner.
+    pub min: Coordinates,
+    /// North-east corner.
+    pub max: Coordinates,
+}
+
+impl BoundingBox {
+    /// Create a bounding box from its south-west and north-east corners.
+    pub const fn new(min: Coordinates, max: Coordinates) -> Self {
+        Self { min, max }
+    }
+
+    /// Get the smallest bounding box around some points, or `None` if there are no points.
+    /// # Example
+    /// ```
+    /// use leblad::geo::{BoundingBox, Coordinates};
+    /// let points = [Coordinates::new(36.0, 3.0), Coordinates::new(35.0, 4.0)];
+    /// let bounding_box = BoundingBox::around(points).unwrap();
+    /// assert_eq!(bounding_box.min, Coordinates::new(35.0, 3.0));
+    /// assert_eq!(bounding_box.max, Coordinates::new(36.0, 4.0));
+    /// ```
+    pub fn around<I: IntoIterator<Item = Coordinates>>(points: I) -> Option<BoundingBox> {
+        points.into_iter().fold(None, |bounding_box, point| {
+            let BoundingBox { min, max } = bounding_box.unwrap_or(BoundingBox::new(point, point));
+            Some(BoundingBox::new(
+                Coordinates::new(
+                    min.latitude.min(point.latitude),
+                    min.longitude.min(point.longitude),
+                ),
+                Coordinates::new(
+                    max.latitude.max(point.latitude),
+                    max.longitude.max(point.longitude),
+                ),
+            ))
+        })
+    }
+
+    /// Check whether a point is inside the bounding box.
+    pub fn contains(&self, point: &Coordinates) -> bool {
+        (self.min.latitude..=self.max.latitude).contains(&point.latitude)
+            && (self.min.longitude..=self.max.longitude).contains(&point.longitude)
+    }
+
+    /// Check whether two bounding boxes intersect.
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.min.latitude <= other.max.latitude
+            && other.min.latitude <= self.max.latitude
+            && self.min.longitude <= other.max.longitude
+            && other.min.longitude <= self.max.longitude
+    }
+
+    /// Get the center of the bounding box.
+    pub fn center(&self) -> Coordinates {
+        Coordinates::new(
+            (self.min.latitude + self.max.latitude) / 2.0,
+            (self.min.longitude + self.max.longitude) / 2.0,
+        )
+    }
+
+    fn envelope(&self) -> AABB<[f64; 2]> {
+        AABB::from_corners(
+            [self.min.longitude, self.min.latitude],
+            [self.max.longitude, self.max.latitude],
+        )
+    }
+}
+
+impl Place<'_> {
+    /// Get the place's coordinates.
+    /// # Example
+    /// ```
+    /// use leblad::{wilaya, Place};
+    /// let place = Place::Wilaya(wilaya!(1));
+    /// assert!(place.coordinates().is_some());
+    /// ```
+    pub fn coordinates(&self) -> Option<Coordinates> {
+        match self {
+            Place::Wilaya(wilaya) => wilaya.coordinates,
+            Place::Daira(daira) => daira.coordinates,
+            Place::Baladyia(baladyia) => baladyia.coordinates,
+        }
+    }
+
+    /// Get the place's boundary, if one is embedded.
+    pub fn boundary(&self) -> Option<&'static Boundary> {
+        boundaries()
+            .iter()
+            .find(|boundary| boundary.level == self.level() && boundary.code == self.code())
+    }
+
+    /// Get the place's bounding box.
+    /// It is the bounding box of its boundary when one is embedded, otherwise the bounding box
+    /// of the coordinates of the place and of the places it contains.
+    /// # Example
+    /// ```
+    /// use leblad::{baladyia, Place};
+    /// let place = Place::Baladyia(baladyia!(101));
+    /// let bounding_box = place.bounding_box().unwrap();
+    /// assert!(bounding_box.contains(&place.coordinates().unwrap()));
+    /// ```
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        if let Some(boundary) = self.boundary() {
+            return Some(boundary.bounding_box());
+        }
+        let mut points = self.coordinates().into_iter().collect::<Vec<Coordinates>>();
+        let dairats = match self {
+            Place::Wilaya(wilaya) => wilaya.dairats,
+            Place::Daira(daira) => std::slice::from_ref(*daira),
+            Place::Baladyia(_) => &[],
+        };
+        for daira in dairats {
+            points.extend(daira.coordinates);
+            for baladyia in daira.baladyiats.into_iter().flatten() {
+                points.extend(baladyia.coordinates);
+            }
+        }
+        BoundingBox::around(points)
+    }
+}
+
+/// Get the distance between two places, in kilometers.
+/// Returns `None` if one of them has no coordinates.
+/// # Example
+/// ```
+/// use leblad::geo::distance_km;
+/// use leblad::{wilaya, Place};
+/// let distance = distance_km(Place::Wilaya(wilaya!(1)), Place::Wilaya(wilaya!(16))).unwrap();
+/// assert!((distance - 1_036.0).abs() < 1.0);
+/// ```
+pub fn distance_km(a: Place, b: Place) -> Option<f64> {
+    Some(a.coordinates()?.distance_km(&b.coordinates()?))
+}
+
+/// Get the nearest baladyia to a point.
+/// # Example
+/// ```
+/// use leblad::geo::nearest_baladyia;
+/// let baladyia = nearest_baladyia(27.9, -0.3);
+/// assert_eq!(baladyia.unwrap().name, "ADRAR");
+/// ```
+pub fn nearest_baladyia<'a>(latitude: f64, longitude: f64) -> Option<&'a Baladyia> {
+    baladyiats_index()
+        .nearest(&Coordinates::new(latitude, longitude), 1)
+        .first()
+        .copied()
+}
+
+/// Boundary of a place.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Boundary {
+    /// Level of the place.
+    pub level: Level,
+    /// Code of the place, which is the mattricule for wilayas.
+    pub code: u16,
+    /// Polygons of the place. The first ring of each polygon is its outline, the others are holes.
+    pub polygons: &'static [&'static [&'static [Coordinates]]],
+}
+
+impl Boundary {
+    /// Check whether a point is inside the boundary.
+    /// # Example
+    /// ```
+    /// use leblad::geo::{Boundary, Coordinates};
+    /// use leblad::Level;
+    /// const BOUNDARY: Boundary = Boundary {
+    ///     level: Level::Wilaya,
+    ///     code: 1,
+    ///     polygons: &[&[&[
+    ///         Coordinates::new(0.0, 0.0),
+    ///         Coordinates::new(0.0, 1.0),
+    ///         Coordinates::new(1.0, 1.0),
+    ///         Coordinates::new(1.0, 0.0),
+    ///     ]]],
+    /// };
+    /// assert!(BOUNDARY.contains(&Coordinates::new(0.5, 0.5)));
+    /// assert!(!BOUNDARY.contains(&Coordinates::new(1.5, 0.5)));
+    /// ```
+    pub fn contains(&self, point: &Coordinates) -> bool {
+        // Even-odd rule, so holes are excluded from their polygon.
+        self.polygons.iter().any(|rings| {
+            rings
+                .iter()
+                .filter(|ring| ring_contains(ring, point))
+                .count()
+                % 2
+                == 1
+        })
+    }
+
+    /// Get the bounding box of the boundary.
+    pub fn bounding_box(&self) -> BoundingBox {
+        let points = self
+            .polygons
+            .iter()
+            .flat_map(|rings| rings.iter())
+            .flat_map(|ring| ring.iter())
+            .copied();
+        BoundingBox::around(points).unwrap_or_default()
+    }
+}
+
+/// Ray casting test of a point against a single ring.
+fn ring_contains(ring: &[Coordinates], point: &Coordinates) -> bool {
+    let mut inside = false;
+    let mut j = ring.len().wrapping_sub(1);
+    for i in 0..ring.len() {
+        let (a, b) = (ring[i], ring[j]);
+        if (a.latitude > point.latitude) != (b.latitude > point.latitude)
+            && point.longitude
+                < (b.longitude - a.longitude) * (point.latitude - a.latitude)
+                    / (b.latitude - a.latitude)
+                    + a.longitude
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Get all the embedded boundaries.
+pub fn boundaries() -> &'static [Boundary] {
+    crate::_auto_generated::BOUNDARIES
+}
+
+/// A place along with the places containing it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Location<'a> {
+    /// Wilaya of the location.
+    pub wilaya: &'a Wilaya,
+    /// Daira of the location, unless the location is a wilaya.
+    pub daira: Option<&'a Daira>,
+    /// Baladyia of the location, if the location is a baladyia.
+    pub baladyia: Option<&'a Baladyia>,
+}
+
+impl<'a> Location<'a> {
+    /// Get the location of a wilaya by mattricule.
+    /// # Example
+    /// ```
+    /// use leblad::geo::Location;
+    /// let location = Location::of_wilaya(1).unwrap();
+    /// assert_eq!(location.wilaya.name, "Adrar");
+    /// assert!(location.daira.is_none());
+    /// ```
+    pub fn of_wilaya(mattricule: u16) -> Option<Location<'a>> {
+        ALL_WILAYAS
+            .iter()
+            .find(|wilaya| wilaya.mattricule == mattricule)
+            .map(|wilaya| Location {
+                wilaya,
+                daira: None,
+                baladyia: None,
+            })
+    }
+
+    /// Get the location of a daira by code.
+    /// # Example
+    /// ```
+    /// use leblad::geo::Location;
+    /// let location = Location::of_daira(101).unwrap();
+    /// assert_eq!(location.wilaya.name, "Adrar");
+    /// assert_eq!(location.daira.unwrap().name, "ADRAR");
+    /// assert!(location.baladyia.is_none());
+    /// ```
+    pub fn of_daira(daira_code: u16) -> Option<Location<'a>> {
+        ALL_WILAYAS.iter().find_map(|wilaya| {
+            wilaya
+                .dairats
+                .iter()
+                .find(|daira| daira.code == daira_code)
+                .map(|daira| Location {
+                    wilaya,
+                    daira: Some(daira),
+                    baladyia: None,
+                })
+        })
+    }
+
+    /// Get the location of a baladyia by code.
+    /// # Example
+    /// ```
+    /// use leblad::geo::Location;
+    /// let location = Location::of_baladyia(101).unwrap();
+    /// assert_eq!(location.wilaya.name, "Adrar");
+    /// assert_eq!(location.daira.unwrap().name, "ADRAR");
+    /// ```
+    pub fn of_baladyia(baladyia_code: u16) -> Option<Location<'a>> {
+        ALL_WILAYAS.iter().find_map(|wilaya| {
+            wilaya.dairats.iter().find_map(|daira| {
+                daira
+                    .baladyiats
+                    .and_then(|baladyiats| baladyiats.iter().find(|b| b.code == baladyia_code))
+                    .map(|baladyia| Location {
+                        wilaya,
+                        daira: Some(daira),
+                        baladyia: Some(baladyia),
+                    })
+            })
+        })
+    }
+
+    /// Get the location of a place by level and code.
+    pub fn of(level: Level, code: u16) -> Option<Location<'a>> {
+        match level {
+            Level::Wilaya => Location::of_wilaya(code),
+            Level::Daira => Location::of_daira(code),
+            Level::Baladyia => Location::of_baladyia(code),
+        }
+    }
+}
+
+/// Boundary stored in the R-tree along with its bounding box.
+struct IndexedBoundary {
+    boundary: &'static Boundary,
+    envelope: AABB<[f64; 2]>,
+}
+
+impl RTreeObject for IndexedBoundary {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
+}
+
+/// R-trees of boundaries, one per level from the finest to the coarsest.
+struct BoundaryIndex {
+    trees: [RTree<IndexedBoundary>; 3],
+}
+
+impl BoundaryIndex {
+    fn new(boundaries: &'static [Boundary]) -> BoundaryIndex {
+        let tree = |level: Level| {
+            let boundaries = boundaries
+                .iter()
+                .filter(|boundary| boundary.level == level)
+                .map(|boundary| IndexedBoundary {
+                    boundary,
+                    envelope: boundary.bounding_box().envelope(),
+                })
+                .collect();
+            RTree::bulk_load(boundaries)
+        };
+        BoundaryIndex {
+            trees: [
+                tree(Level::Baladyia),
+                tree(Level::Daira),
+                tree(Level::Wilaya),
+            ],
+        }
+    }
+
+    fn locate<'a>(&self, point: &Coordinates) -> Option<Location<'a>> {
+        let envelope = AABB::from_point([point.longitude, point.latitude]);
+        self.trees.iter().find_map(|tree| {
+            tree.locate_in_envelope_intersecting(&envelope)
+                .find(|indexed| indexed.boundary.contains(point))
+                .and_then(|indexed| Location::of(indexed.boundary.level, indexed.boundary.code))
+        })
+    }
+}
+
+/// Locate the place containing a point, along with the places containing it.
+/// Boundaries are looked up from the finest level to the coarsest, so the location is a
+/// baladyia when its boundary is embedded, otherwise a daira or a wilaya.
+/// Returns `None` if the point is outside of every embedded boundary.
+/// # Example
+/// ```
+/// use leblad::geo::locate;
+/// if let Some(location) = locate(36.7538, 3.0588) {
+///     assert_eq!(location.wilaya.mattricule, 16);
+/// }
+/// assert!(locate(0.0, 0.0).is_none());
+/// ```
+pub fn locate<'a>(latitude: f64, longitude: f64) -> Option<Location<'a>> {
+    static INDEX: OnceLock<BoundaryIndex> = OnceLock::new();
+    INDEX
+        .get_or_init(|| BoundaryIndex::new(boundaries()))
+        .locate(&Coordinates::new(latitude, longitude))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ALL_WILAYAS;
+
+    #[test]
+    fn distance_to_itself_is_zero() {
+        let point = Coordinates::new(36.75, 3.06);
+        assert_eq!(point.distance_km(&point), 0.0);
+    }
+
+    #[test]
+    fn distance_is_symmetric() {
+        let a = Coordinates::new(36.75, 3.06);
+        let b = Coordinates::new(22.79, 5.52);
+        assert_eq!(a.distance_km(&b), b.distance_km(&a));
+        assert!((a.distance_km(&b) - 1_571.0).abs() < 5.0);
+    }
+
+    #[test]
+    fn distance_between_places_without_coordinates() {
+        let wilaya = Place::Wilaya(&ALL_WILAYAS[0]);
+        let daira = Daira {
+            coordinates: None,
+            ..ALL_WILAYAS[0].dairats[0].clone()
+        };
+        assert!(distance_km(wilaya, Place::Daira(&daira)).is_none());
+    }
+
+    const SQUARE: &[Coordinates] = &[
+        Coordinates::new(27.0, -1.0),
+        Coordinates::new(27.0, 1.0),
+        Coordinates::new(29.0, 1.0),
+        Coordinates::new(29.0, -1.0),
+    ];
+    const HOLE: &[Coordinates] = &[
+        Coordinates::new(27.5, -0.5),
+        Coordinates::new(27.5, 0.5),
+        Coordinates::new(28.5, 0.5),
+        Coordinates::new(28.5, -0.5),
+    ];
+    const TEST_BOUNDARIES: &[Boundary] = &[
+        Boundary {
+            level: Level::Baladyia,
+            code: 101,
+            polygons: &[&[SQUARE, HOLE]],
+        },
+        Boundary {
+            level: Level::Daira,
+            code: 101,
+            polygons: &[&[SQUARE]],
+        },
+        Boundary {
+            level: Level::Wilaya,
+            code: 16,
+            polygons: &[&[&[
+                Coordinates::new(36.6, 2.9),
+                Coordinates::new(36.6, 3.3),
+                Coordinates::new(36.9, 3.3),
+                Coordinates::new(36.9, 2.9),
+            ]]],
+        },
+    ];
+
+    #[test]
+    fn boundary_contains_points_outside_of_holes() {
+        let boundary = TEST_BOUNDARIES[0];
+        assert!(boundary.contains(&Coordinates::new(27.2, 0.0)));
+        assert!(!boundary.contains(&Coordinates::new(28.0, 0.0)));
+        assert!(!boundary.contains(&Coordinates::new(30.0, 0.0)));
+    }
+
+    #[test]
+    fn boundary_bounding_box() {
+        let res = TEST_BOUNDARIES[0].bounding_box();
+        assert_eq!(res.min, Coordinates::new(27.0, -1.0));
+        assert_eq!(res.max, Coordinates::new(29.0, 1.0));
+    }
+
+    #[test]
+    fn bounding_boxes_intersection() {
+        let a = BoundingBox::new(Coordinates::new(0.0, 0.0), Coordinates::new(2.0, 2.0));
+        let b = BoundingBox::new(Coordinates::new(1.0, 1.0), Coordinates::new(3.0, 3.0));
+        let c = BoundingBox::new(Coordinates::new(2.5, 2.5), Coordinates::new(4.0, 4.0));
+        assert!(a.intersects(&b));
+        assert!(b.intersects(&c));
+        assert!(!a.intersects(&c));
+        assert_eq!(a.center(), Coordinates::new(1.0, 1.0));
+    }
+
+    #[test]
+    fn wilaya_bounding_box_covers_its_places() {
+        let res = Place::Wilaya(&ALL_WILAYAS[0]).bounding_box();
+        assert!(res.is_some());
+        let baladyia = &ALL_WILAYAS[0].dairats[0].baladyiats.unwrap()[0];
+        assert!(res.unwrap().contains(&baladyia.coordinates.unwrap()));
+    }
+
+    #[test]
+    fn locate_point_inside_baladyia() {
+        let index = BoundaryIndex::new(TEST_BOUNDARIES);
+        let res = index.locate(&Coordinates::new(27.2, 0.0));
+        assert!(res.is_some());
+        let location = res.unwrap();
+        assert_eq!(*location.wilaya, ALL_WILAYAS[0]);
+        assert_eq!(location.daira, Some(&ALL_WILAYAS[0].dairats[0]));
+        assert_eq!(location.baladyia.unwrap().code, 101);
+    }
+
+    #[test]
+    fn locate_falls_back_to_coarser_levels() {
+        let index = BoundaryIndex::new(TEST_BOUNDARIES);
+        let location = index.locate(&Coordinates::new(28.0, 0.0)).unwrap();
+        assert_eq!(location.daira.unwrap().code, 101);
+        assert!(location.baladyia.is_none());
+
+        let location = index.locate(&Coordinates::new(36.7538, 3.0588)).unwrap();
+        assert_eq!(location.wilaya.mattricule, 16);
+        assert!(location.daira.is_none());
+    }
+
+    #[test]
+    fn locate_point_outside_of_boundaries() {
+        let index = BoundaryIndex::new(TEST_BOUNDARIES);
+        assert!(index.locate(&Coordinates::new(30.0, 0.0)).is_none());
+        assert!(index.locate(&Coordinates::new(36.0, 3.0)).is_none());
+    }
+
+    #[test]
+    fn embedded_boundaries_refer_to_existing_places() {
+        for boundary in boundaries() {
+            assert!(Place::get(boundary.level, boundary.code).is_some());
+        }
+    }
+
+    #[test]
+    fn get_nearest_baladyia() {
+        let res = nearest_baladyia(27.9, -0.3);
+        assert!(res.is_some());
+        assert_eq!(
+            *res.unwrap(),
+            ALL_WILAYAS[0].dairats[0].baladyiats.unwrap()[0]
+        );
+    }
+}
@@ -0,0 +1,214 @@
+//! GeoJSON export of the administrative hierarchy.
+//!
+//! Every place becomes a `Feature` whose properties hold its codes and names. With the `geo`
+//! feature, the geometry is the place's boundary when one is embedded, or its coordinates;
+//! otherwise it is `null`.
+
+use serde_json::{json, Map, Value};
+
+use crate::{Baladyia, Daira, Level, Place, Wilaya, ALL_WILAYAS};
+
+/// Export places of a level as a GeoJSON `FeatureCollection`.
+/// # Example
+/// ```
+/// use leblad::geojson::to_geojson;
+/// use leblad::Level;
+/// let collection = to_geojson(Level::Wilaya);
+/// assert_eq!(collection["type"], "FeatureCollection");
+/// assert_eq!(collection["features"][0]["properties"]["name"], "Adrar");
+/// ```
+pub fn to_geojson(level: Level) -> Value {
+    let features = match level {
+        Level::Wilaya => ALL_WILAYAS.iter().map(wilaya_feature).collect(),
+        Level::Daira => ALL_WILAYAS
+            .iter()
+            .flat_map(|wilaya| {
+                wilaya
+                    .dairats
+                    .iter()
+                    .map(move |daira| daira_feature(wilaya, daira))
+            })
+            .collect(),
+        Level::Baladyia => ALL_WILAYAS
+            .iter()
+            .flat_map(|wilaya| {
+                wilaya.dairats.iter().flat_map(move |daira| {
+                    daira
+                        .baladyiats
+                        .into_iter()
+                        .flatten()
+                        .map(move |baladyia| baladyia_feature(wilaya, daira, baladyia))
+                })
+            })
+            .collect(),
+    };
+    json!({
+        "type": "FeatureCollection",
+        "features": Value::Array(features),
+    })
+}
+
+/// Export all wilayas as a GeoJSON `FeatureCollection`.
+pub fn wilayas_to_geojson() -> Value {
+    to_geojson(Level::Wilaya)
+}
+
+/// Export all dairats as a GeoJSON `FeatureCollection`.
+pub fn dairats_to_geojson() -> Value {
+    to_geojson(Level::Daira)
+}
+
+/// Export all baladyiats as a GeoJSON `FeatureCollection`.
+pub fn baladyiats_to_geojson() -> Value {
+    to_geojson(Level::Baladyia)
+}
+
+fn wilaya_feature(wilaya: &Wilaya) -> Value {
+    let properties = json!({
+        "level": Level::Wilaya.as_str(),
+        "code": wilaya.mattricule,
+        "iso_code": wilaya.iso_code(),
+        "name": wilaya.name,
+        "name_ar": wilaya.name_ar,
+        "name_ber": wilaya.name_ber,
+        "name_en": wilaya.name_en,
+        "phone_codes": wilaya.phone_codes,
+        "postal_codes": wilaya.postal_codes,
+        "adjacent_wilayas": wilaya.adjacent_wilayas,
+    });
+    feature(Place::Wilaya(wilaya), properties)
+}
+
+fn daira_feature(wilaya: &Wilaya, daira: &Daira) -> Value {
+    let properties = json!({
+        "level": Level::Daira.as_str(),
+        "code": daira.code,
+        "name": daira.name,
+        "name_ar": daira.name_ar,
+        "name_en": daira.name_en,
+        "wilaya_code": wilaya.mattricule,
+        "phone_codes": wilaya.phone_codes,
+    });
+    feature(Place::Daira(daira), properties)
+}
+
+fn baladyia_feature(wilaya: &Wilaya, daira: &Daira, baladyia: &Baladyia) -> Value {
+    let properties = json!({
+        "level": Level::Baladyia.as_str(),
+        "code": baladyia.code,
+        "name": baladyia.name,
+        "name_ar": baladyia.name_ar,
+        "name_en": baladyia.name_en,
+        "wilaya_code": wilaya.mattricule,
+        "daira_code": daira.code,
+        "phone_codes": wilaya.phone_codes,
+    });
+    feature(Place::Baladyia(baladyia), properties)
+}
+
+fn feature(place: Place, properties: Value) -> Value {
+    let mut feature = Map::new();
+    feature.insert("type".to_string(), json!("Feature"));
+    feature.insert(
+        "id".to_string(),
+        json!(format!("{}-{}", place.level().as_str(), place.code())),
+    );
+    feature.insert("geometry".to_string(), geometry(place));
+    feature.insert("properties".to_string(), properties);
+    Value::Object(feature)
+}
+
+#[cfg(feature = "geo")]
+fn geometry(place: Place) -> Value {
+    if let Some(boundary) = place.boundary() {
+        let polygons = boundary
+            .polygons
+            .iter()
+            .map(|rings| {
+                rings
+                    .iter()
+                    .map(|ring| {
+                        ring.iter()
+                            .map(|point| json!([point.longitude, point.latitude]))
+                            .collect::<Vec<Value>>()
+                    })
+                    .collect::<Vec<Vec<Value>>>()
+            })
+            .collect::<Vec<Vec<Vec<Value>>>>();
+        return match polygons.as_slice() {
+            [polygon] => json!({ "type": "Polygon", "coordinates": polygon }),
+            _ => json!({ "type": "MultiPolygon", "coordinates": polygons }),
+        };
+    }
+    match place.coordinates() {
+        Some(point) => json!({
+            "type": "Point",
+            "coordinates": [point.longitude, point.latitude],
+        }),
+        None => Value::Null,
+    }
+}
+
+#[cfg(not(feature = "geo"))]
+fn geometry(_place: Place) -> Value {
+    Value::Null
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wilayas_feature_collection() {
+        let res = wilayas_to_geojson();
+        let features = res["features"].as_array().unwrap();
+        assert_eq!(features.len(), ALL_WILAYAS.len());
+        let properties = &features[0]["properties"];
+        assert_eq!(properties["code"], ALL_WILAYAS[0].mattricule);
+        assert_eq!(properties["name_ber"], ALL_WILAYAS[0].name_ber);
+        assert_eq!(
+            properties["adjacent_wilayas"],
+            json!(ALL_WILAYAS[0].adjacent_wilayas)
+        );
+        assert_eq!(features[0]["id"], "wilaya-1");
+    }
+
+    #[test]
+    fn dairats_feature_collection() {
+        let res = dairats_to_geojson();
+        let count = ALL_WILAYAS
+            .iter()
+            .map(|wilaya| wilaya.dairats.len())
+            .sum::<usize>();
+        assert_eq!(res["features"].as_array().unwrap().len(), count);
+        assert_eq!(res["features"][0]["properties"]["wilaya_code"], 1);
+    }
+
+    #[test]
+    fn baladyiats_feature_collection() {
+        let res = baladyiats_to_geojson();
+        let properties = &res["features"][0]["properties"];
+        assert_eq!(properties["daira_code"], ALL_WILAYAS[0].dairats[0].code);
+        assert_eq!(properties["phone_codes"], json!(ALL_WILAYAS[0].phone_codes));
+    }
+
+    #[cfg(not(feature = "geo"))]
+    #[test]
+    fn geometry_is_null_without_geo() {
+        let res = wilayas_to_geojson();
+        assert!(res["features"][0]["geometry"].is_null());
+    }
+
+    #[cfg(feature = "geo")]
+    #[test]
+    fn geometry_falls_back_to_coordinates() {
+        let res = wilayas_to_geojson();
+        let geometry = &res["features"][0]["geometry"];
+        let coordinates = ALL_WILAYAS[0].coordinates.unwrap();
+        assert_eq!(geometry["type"], "Point");
+        assert_eq!(
+            geometry["coordinates"],
+            json!([coordinates.longitude, coordinates.latitude])
+        );
+    }
+}
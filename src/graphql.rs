@@ -0,0 +1,435 @@
+//! GraphQL schema over the administrative hierarchy, built with `async-graphql`.
+//!
+//! [`QueryRoot`] mirrors the lookups of the crate root, and the `Wilaya`, `Daira` and
+//! `Baladyia` types link to each other, so a wilaya can be fetched with its dairats,
+//! baladyiats and neighbours in one query. Use [`schema`] to serve it as is, or merge
+//! [`QueryRoot`] into an existing schema with `async_graphql::MergedObject`.
+//!
+//! The types link to each other in cycles, so queries are limited in depth and complexity,
+//! where a list counts as its usual length times the complexity of its fields.
+//!
+//! # Example
+//! ```
+//! # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+//! let schema = leblad::graphql::schema();
+//! let response = schema
+//!     .execute("{ wilaya(mattricule: 1) { name dairats { code baladyiats { name } } } }")
+//!     .await;
+//! let data = response.data.into_json().unwrap();
+//! assert_eq!(data["wilaya"]["name"], "Adrar");
+//! assert_eq!(data["wilaya"]["dairats"][0]["code"], 101);
+//! # });
+//! ```
+
+use async_graphql::{EmptyMutation, EmptySubscription, Object, Schema, Union};
+
+use crate::{
+    get_baladyia_by_code, get_daira_by_baladyia_name, get_daira_by_code,
+    get_wilaya_by_baladyia_name, get_wilaya_by_code, get_wilaya_by_daira_name,
+    get_wilaya_by_iso_code, get_wilaya_by_phone_code, get_wilaya_by_zip_code, search, search_level,
+    Baladyia, Daira, Level, Place, Wilaya, ALL_WILAYAS,
+};
+
+/// The schema served by [`schema`].
+pub type LebladSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Maximum depth of the queries, in nested fields.
+pub const MAX_DEPTH: usize = 8;
+
+/// Maximum complexity of the queries, which allows listing every baladyia of every wilaya.
+pub const MAX_COMPLEXITY: usize = 20_000;
+
+/// Build the schema, without mutations nor subscriptions, limited to [`MAX_DEPTH`] and
+/// [`MAX_COMPLEXITY`].
+pub fn schema() -> LebladSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+/// Root of the queries.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Every wilaya.
+    #[graphql(complexity = "58 * child_complexity")]
+    async fn wilayas(&self) -> Vec<WilayaObject> {
+        ALL_WILAYAS.iter().map(WilayaObject).collect()
+    }
+
+    /// A wilaya by mattricule.
+    async fn wilaya(&self, mattricule: u16) -> Option<WilayaObject> {
+        get_wilaya_by_code(mattricule).map(WilayaObject)
+    }
+
+    /// The wilaya of a postal code.
+    async fn wilaya_by_zip_code(&self, zip_code: u16) -> Option<WilayaObject> {
+        get_wilaya_by_zip_code(zip_code).map(WilayaObject)
+    }
+
+    /// The wilaya of a phone code.
+    async fn wilaya_by_phone_code(&self, phone_code: u16) -> Option<WilayaObject> {
+        get_wilaya_by_phone_code(phone_code).map(WilayaObject)
+    }
+
+    /// A wilaya by ISO 3166-2 code, e.g. `DZ-01`.
+    async fn wilaya_by_iso_code(&self, iso_code: String) -> Option<WilayaObject> {
+        get_wilaya_by_iso_code(&iso_code).map(WilayaObject)
+    }
+
+    /// The wilaya of a daira, by name.
+    async fn wilaya_by_daira_name(&self, daira_name: String) -> Option<WilayaObject> {
+        get_wilaya_by_daira_name(&daira_name).map(WilayaObject)
+    }
+
+    /// The wilaya of a baladyia, by name.
+    async fn wilaya_by_baladyia_name(&self, baladyia_name: String) -> Option<WilayaObject> {
+        // The lookup borrows the name, so the wilaya is looked up again by mattricule.
+        get_wilaya_by_baladyia_name(&baladyia_name)
+            .and_then(|wilaya| get_wilaya_by_code(wilaya.mattricule))
+            .map(WilayaObject)
+    }
+
+    /// A daira by code.
+    async fn daira(&self, code: u16) -> Option<DairaObject> {
+        get_daira_by_code(code).map(DairaObject)
+    }
+
+    /// The daira of a baladyia, by name.
+    async fn daira_by_baladyia_name(&self, baladyia_name: String) -> Option<DairaObject> {
+        get_daira_by_baladyia_name(&baladyia_name)
+            .and_then(|daira| get_daira_by_code(daira.code))
+            .map(DairaObject)
+    }
+
+    /// A baladyia by code.
+    async fn baladyia(&self, code: u16) -> Option<BaladyiaObject> {
+        get_baladyia_by_code(code).map(BaladyiaObject)
+    }
+
+    /// Places matching a name or code, best matches first, optionally of a single level:
+    /// `wilaya`, `daira` or `baladyia`.
+    #[graphql(complexity = "limit * child_complexity")]
+    async fn search(
+        &self,
+        query: String,
+        level: Option<String>,
+        #[graphql(default = 20)] limit: usize,
+    ) -> async_graphql::Result<Vec<PlaceObject>> {
+        let places = match level {
+            Some(level) => match Level::parse(&level) {
+                Some(level) => search_level(level, &query),
+                None => return Err(format!("unknown level `{}`", level).into()),
+            },
+            None => search(&query),
+        };
+        Ok(places
+            .into_iter()
+            .take(limit)
+            .map(PlaceObject::from)
+            .collect())
+    }
+}
+
+/// A wilaya.
+#[derive(Debug, Clone, Copy)]
+pub struct WilayaObject(pub &'static Wilaya);
+
+#[Object(name = "Wilaya")]
+impl WilayaObject {
+    async fn mattricule(&self) -> u16 {
+        self.0.mattricule
+    }
+
+    async fn name(&self) -> &str {
+        self.0.name
+    }
+
+    async fn name_ar(&self) -> &str {
+        self.0.name_ar
+    }
+
+    async fn name_ber(&self) -> &str {
+        self.0.name_ber
+    }
+
+    async fn name_en(&self) -> &str {
+        self.0.name_en
+    }
+
+    /// ISO 3166-2 code, e.g. `DZ-01`.
+    async fn iso_code(&self) -> String {
+        self.0.iso_code()
+    }
+
+    async fn phone_codes(&self) -> &[u16] {
+        self.0.phone_codes
+    }
+
+    async fn postal_codes(&self) -> &[u16] {
+        self.0.postal_codes
+    }
+
+    /// Mattricules of the adjacent wilayas.
+    async fn adjacent_wilayas(&self) -> &[u16] {
+        self.0.adjacent_wilayas
+    }
+
+    /// The adjacent wilayas.
+    #[graphql(complexity = "10 * child_complexity")]
+    async fn neighbours(&self) -> Vec<WilayaObject> {
+        self.0
+            .adjacent_wilayas
+            .iter()
+            .filter_map(|mattricule| get_wilaya_by_code(*mattricule))
+            .map(WilayaObject)
+            .collect()
+    }
+
+    #[graphql(complexity = "20 * child_complexity")]
+    async fn dairats(&self) -> Vec<DairaObject> {
+        self.0.dairats.iter().map(DairaObject).collect()
+    }
+
+    /// The baladyiats of every daira of the wilaya.
+    #[graphql(complexity = "60 * child_complexity")]
+    async fn baladyiats(&self) -> Vec<BaladyiaObject> {
+        self.0
+            .dairats
+            .iter()
+            .flat_map(|daira| daira.baladyiats.into_iter().flatten())
+            .map(BaladyiaObject)
+            .collect()
+    }
+}
+
+/// A daira.
+#[derive(Debug, Clone, Copy)]
+pub struct DairaObject(pub &'static Daira);
+
+#[Object(name = "Daira")]
+impl DairaObject {
+    async fn code(&self) -> u16 {
+        self.0.code
+    }
+
+    async fn name(&self) -> &str {
+        self.0.name
+    }
+
+    async fn name_ar(&self) -> &str {
+        self.0.name_ar
+    }
+
+    async fn name_en(&self) -> &str {
+        self.0.name_en
+    }
+
+    /// The wilaya of the daira.
+    async fn wilaya(&self) -> Option<WilayaObject> {
+        match Place::Daira(self.0).parent() {
+            Some(Place::Wilaya(wilaya)) => Some(WilayaObject(wilaya)),
+            _ => None,
+        }
+    }
+
+    /// The baladyiats of the daira, or null when the dataset does not list them.
+    #[graphql(complexity = "10 * child_complexity")]
+    async fn baladyiats(&self) -> Option<Vec<BaladyiaObject>> {
+        let baladyiats = self.0.baladyiats?;
+        Some(baladyiats.iter().map(BaladyiaObject).collect())
+    }
+}
+
+/// A baladyia.
+#[derive(Debug, Clone, Copy)]
+pub struct BaladyiaObject(pub &'static Baladyia);
+
+#[Object(name = "Baladyia")]
+impl BaladyiaObject {
+    async fn code(&self) -> u16 {
+        self.0.code
+    }
+
+    async fn name(&self) -> &str {
+        self.0.name
+    }
+
+    async fn name_ar(&self) -> &str {
+        self.0.name_ar
+    }
+
+    async fn name_en(&self) -> &str {
+        self.0.name_en
+    }
+
+    /// The daira of the baladyia.
+    async fn daira(&self) -> Option<DairaObject> {
+        match Place::Baladyia(self.0).parent() {
+            Some(Place::Daira(daira)) => Some(DairaObject(daira)),
+            _ => None,
+        }
+    }
+
+    /// The wilaya of the baladyia.
+    async fn wilaya(&self) -> Option<WilayaObject> {
+        match Place::Baladyia(self.0)
+            .parent()
+            .and_then(|daira| daira.parent())
+        {
+            Some(Place::Wilaya(wilaya)) => Some(WilayaObject(wilaya)),
+            _ => None,
+        }
+    }
+}
+
+/// A wilaya, daira or baladyia.
+#[derive(Debug, Clone, Copy, Union)]
+#[graphql(name = "Place")]
+pub enum PlaceObject {
+    /// A wilaya.
+    Wilaya(WilayaObject),
+    /// A daira.
+    Daira(DairaObject),
+    /// A baladyia.
+    Baladyia(BaladyiaObject),
+}
+
+impl From<Place<'static>> for PlaceObject {
+    fn from(place: Place<'static>) -> PlaceObject {
+        match place {
+            Place::Wilaya(wilaya) => PlaceObject::Wilaya(WilayaObject(wilaya)),
+            Place::Daira(daira) => PlaceObject::Daira(DairaObject(daira)),
+            Place::Baladyia(baladyia) => PlaceObject::Baladyia(BaladyiaObject(baladyia)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_graphql::Value;
+
+    async fn query(query: &str) -> serde_json::Value {
+        let response = schema().execute(query).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        response.data.into_json().unwrap()
+    }
+
+    #[tokio::test]
+    async fn nested_hierarchy() {
+        let data = query(
+            "{ wilaya(mattricule: 1) {
+                mattricule isoCode
+                dairats {
+                    code wilaya { name }
+                    baladyiats { code name daira { code } wilaya { name } }
+                }
+                baladyiats { name }
+            } }",
+        )
+        .await;
+        let wilaya = &data["wilaya"];
+        assert_eq!(wilaya["isoCode"], "DZ-01");
+        assert_eq!(wilaya["dairats"][0]["wilaya"]["name"], "Adrar");
+        let baladyiats = wilaya["dairats"][0]["baladyiats"].as_array().unwrap();
+        assert_eq!(baladyiats[0]["code"], 101);
+        assert_eq!(baladyiats[0]["name"], "ADRAR");
+        let baladyia = baladyiats
+            .iter()
+            .find(|baladyia| baladyia["name"] == "OULED AHMED TIMMI")
+            .unwrap();
+        assert_eq!(baladyia["daira"]["code"], 101);
+        assert_eq!(baladyia["wilaya"]["name"], "Adrar");
+        let names = wilaya["baladyiats"].as_array().unwrap();
+        assert!(names.contains(&serde_json::json!({ "name": "ADRAR" })));
+        assert!(names.contains(&serde_json::json!({ "name": "OULED AHMED TIMMI" })));
+    }
+
+    #[tokio::test]
+    async fn neighbours_are_adjacent_wilayas() {
+        let data =
+            query("{ wilayas { mattricule adjacentWilayas neighbours { mattricule } } }").await;
+        for wilaya in data["wilayas"].as_array().unwrap() {
+            for neighbour in wilaya["neighbours"].as_array().unwrap() {
+                let adjacent = wilaya["adjacentWilayas"].as_array().unwrap();
+                assert!(adjacent.contains(&neighbour["mattricule"]));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn lookups() {
+        let data = query(
+            "{ wilayaByZipCode(zipCode: 1000) { name }
+               wilayaByIsoCode(isoCode: \"DZ-16\") { mattricule }
+               wilayaByDairaName(dairaName: \"ADRAR\") { mattricule }
+               daira(code: 101) { wilaya { mattricule } }
+               baladyia(code: 9999) { name } }",
+        )
+        .await;
+        assert_eq!(data["wilayaByZipCode"]["name"], "Adrar");
+        assert_eq!(data["wilayaByIsoCode"]["mattricule"], 16);
+        assert_eq!(data["wilayaByDairaName"]["mattricule"], 1);
+        assert_eq!(data["daira"]["wilaya"]["mattricule"], 1);
+        assert_eq!(data["baladyia"], serde_json::Value::Null);
+    }
+
+    #[tokio::test]
+    async fn search_places() {
+        let data = query(
+            "{ search(query: \"adrar\", limit: 2) {
+                __typename
+                ... on Wilaya { mattricule }
+                ... on Daira { code }
+            } }",
+        )
+        .await;
+        assert_eq!(data["search"][0]["__typename"], "Wilaya");
+        assert_eq!(data["search"][1]["code"], 101);
+        assert_eq!(data["search"].as_array().unwrap().len(), 2);
+
+        let response = schema()
+            .execute("{ search(query: \"adrar\", level: \"commune\") { __typename } }")
+            .await;
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(response.data, Value::Null);
+    }
+
+    #[tokio::test]
+    async fn cyclic_queries_are_limited() {
+        query("{ wilayas { dairats { baladyiats { name } } } }").await;
+        query("{ wilayas { neighbours { neighbours { name } } } }").await;
+
+        let response = schema()
+            .execute("{ wilayas { neighbours { neighbours { neighbours { name } } } } }")
+            .await;
+        assert_eq!(response.errors.len(), 1);
+        assert!(response.errors[0].message.contains("too complex"));
+        let response = schema()
+            .execute(
+                "{ baladyia(code: 101) { daira { baladyiats { daira { baladyiats {
+                    daira { baladyiats { daira { code } } } } } } } } }",
+            )
+            .await;
+        assert_eq!(response.errors.len(), 1);
+        assert!(response.errors[0].message.contains("nested too deep"));
+    }
+
+    #[test]
+    fn sdl_names_the_types() {
+        let sdl = schema().sdl();
+        for name in [
+            "type Wilaya",
+            "type Daira",
+            "type Baladyia",
+            "union Place",
+            "neighbours",
+        ] {
+            assert!(sdl.contains(name), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,354 @@
+//! gRPC service over the dataset, built with `tonic`.
+//!
+//! The messages and the `LebladService` service are defined in `proto/leblad.proto`, which
+//! other languages can use to generate their clients. [`Leblad`] implements the service: add
+//! [`server`] to a `tonic::transport::Server`, e.g.
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), tonic::transport::Error> {
+//! tonic::transport::Server::builder()
+//!     .add_service(leblad::grpc::server())
+//!     .serve("127.0.0.1:50051".parse().unwrap())
+//!     .await
+//! # }
+//! ```
+
+use tokio_stream::Iter;
+use tonic::{Request, Response, Status};
+
+use crate::{
+    get_baladyia_by_code, get_daira_by_code, get_wilaya_by_code, get_wilaya_by_iso_code,
+    get_wilaya_by_phone_code, get_wilaya_by_zip_code, search, search_level, Baladyia, Daira, Level,
+    Place, Wilaya, ALL_WILAYAS,
+};
+
+/// Messages, client and server generated from `proto/leblad.proto`.
+#[allow(missing_docs, clippy::all)]
+pub mod proto {
+    tonic::include_proto!("leblad.v1");
+}
+
+use proto::leblad_service_server::{LebladService, LebladServiceServer};
+
+/// Maximum number of search results when the request does not set a limit.
+const SEARCH_LIMIT: usize = 20;
+
+/// A stream of messages sent from a list.
+pub type ListStream<T> = Iter<std::vec::IntoIter<Result<T, Status>>>;
+
+/// Implementation of the `LebladService` over the built-in data.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Leblad;
+
+/// Build the service, ready to be added to a `tonic` server.
+pub fn server() -> LebladServiceServer<Leblad> {
+    LebladServiceServer::new(Leblad)
+}
+
+impl From<&Wilaya> for proto::Wilaya {
+    fn from(wilaya: &Wilaya) -> proto::Wilaya {
+        let codes = |codes: &[u16]| codes.iter().copied().map(u32::from).collect();
+        proto::Wilaya {
+            mattricule: wilaya.mattricule.into(),
+            name: wilaya.name.to_string(),
+            name_ar: wilaya.name_ar.to_string(),
+            name_ber: wilaya.name_ber.to_string(),
+            name_en: wilaya.name_en.to_string(),
+            phone_codes: codes(wilaya.phone_codes),
+            postal_codes: codes(wilaya.postal_codes),
+            adjacent_wilayas: codes(wilaya.adjacent_wilayas),
+            dairats: wilaya.dairats.iter().map(proto::Daira::from).collect(),
+        }
+    }
+}
+
+impl From<&Daira> for proto::Daira {
+    fn from(daira: &Daira) -> proto::Daira {
+        proto::Daira {
+            code: daira.code.into(),
+            name: daira.name.to_string(),
+            name_ar: daira.name_ar.to_string(),
+            name_en: daira.name_en.to_string(),
+            baladyiats: daira
+                .baladyiats
+                .into_iter()
+                .flatten()
+                .map(proto::Baladyia::from)
+                .collect(),
+            baladyiats_known: daira.baladyiats.is_some(),
+        }
+    }
+}
+
+impl From<&Baladyia> for proto::Baladyia {
+    fn from(baladyia: &Baladyia) -> proto::Baladyia {
+        proto::Baladyia {
+            code: baladyia.code.into(),
+            name: baladyia.name.to_string(),
+            name_ar: baladyia.name_ar.to_string(),
+            name_en: baladyia.name_en.to_string(),
+        }
+    }
+}
+
+impl From<Place<'_>> for proto::Place {
+    fn from(place: Place) -> proto::Place {
+        use proto::place::Place as Kind;
+        let place = match place {
+            Place::Wilaya(wilaya) => Kind::Wilaya(wilaya.into()),
+            Place::Daira(daira) => Kind::Daira(daira.into()),
+            Place::Baladyia(baladyia) => Kind::Baladyia(baladyia.into()),
+        };
+        proto::Place { place: Some(place) }
+    }
+}
+
+/// Reads a code of a request, which must fit in 16 bits.
+fn code(code: u32) -> Result<u16, Status> {
+    u16::try_from(code).map_err(|_| Status::invalid_argument(format!("invalid code {}", code)))
+}
+
+fn found<T, M: for<'a> From<&'a T>>(place: Option<&T>, what: &str) -> Result<Response<M>, Status> {
+    match place {
+        Some(place) => Ok(Response::new(place.into())),
+        None => Err(Status::not_found(format!("{} not found", what))),
+    }
+}
+
+fn stream<T>(messages: impl Iterator<Item = T>) -> Response<ListStream<T>> {
+    let messages = messages.map(Ok).collect::<Vec<Result<T, Status>>>();
+    Response::new(tokio_stream::iter(messages))
+}
+
+#[tonic::async_trait]
+impl LebladService for Leblad {
+    async fn get_wilaya(
+        &self,
+        request: Request<proto::CodeRequest>,
+    ) -> Result<Response<proto::Wilaya>, Status> {
+        found(get_wilaya_by_code(code(request.get_ref().code)?), "wilaya")
+    }
+
+    async fn get_wilaya_by_zip_code(
+        &self,
+        request: Request<proto::CodeRequest>,
+    ) -> Result<Response<proto::Wilaya>, Status> {
+        let zip_code = code(request.get_ref().code)?;
+        found(get_wilaya_by_zip_code(zip_code), "postal code")
+    }
+
+    async fn get_wilaya_by_phone_code(
+        &self,
+        request: Request<proto::CodeRequest>,
+    ) -> Result<Response<proto::Wilaya>, Status> {
+        let phone_code = code(request.get_ref().code)?;
+        found(get_wilaya_by_phone_code(phone_code), "phone code")
+    }
+
+    async fn get_wilaya_by_iso_code(
+        &self,
+        request: Request<proto::IsoCodeRequest>,
+    ) -> Result<Response<proto::Wilaya>, Status> {
+        found(
+            get_wilaya_by_iso_code(&request.get_ref().iso_code),
+            "ISO code",
+        )
+    }
+
+    async fn get_daira(
+        &self,
+        request: Request<proto::CodeRequest>,
+    ) -> Result<Response<proto::Daira>, Status> {
+        found(get_daira_by_code(code(request.get_ref().code)?), "daira")
+    }
+
+    async fn get_baladyia(
+        &self,
+        request: Request<proto::CodeRequest>,
+    ) -> Result<Response<proto::Baladyia>, Status> {
+        found(
+            get_baladyia_by_code(code(request.get_ref().code)?),
+            "baladyia",
+        )
+    }
+
+    type ListWilayasStream = ListStream<proto::Wilaya>;
+
+    async fn list_wilayas(
+        &self,
+        _request: Request<proto::ListWilayasRequest>,
+    ) -> Result<Response<Self::ListWilayasStream>, Status> {
+        Ok(stream(ALL_WILAYAS.iter().map(proto::Wilaya::from)))
+    }
+
+    type ListDairatsStream = ListStream<proto::Daira>;
+
+    async fn list_dairats(
+        &self,
+        request: Request<proto::CodeRequest>,
+    ) -> Result<Response<Self::ListDairatsStream>, Status> {
+        let wilaya = get_wilaya_by_code(code(request.get_ref().code)?)
+            .ok_or_else(|| Status::not_found("wilaya not found"))?;
+        Ok(stream(wilaya.dairats.iter().map(proto::Daira::from)))
+    }
+
+    type ListBaladyiatsStream = ListStream<proto::Baladyia>;
+
+    async fn list_baladyiats(
+        &self,
+        request: Request<proto::CodeRequest>,
+    ) -> Result<Response<Self::ListBaladyiatsStream>, Status> {
+        let daira = get_daira_by_code(code(request.get_ref().code)?)
+            .ok_or_else(|| Status::not_found("daira not found"))?;
+        let baladyiats = daira.baladyiats.into_iter().flatten();
+        Ok(stream(baladyiats.map(proto::Baladyia::from)))
+    }
+
+    async fn search(
+        &self,
+        request: Request<proto::SearchRequest>,
+    ) -> Result<Response<proto::SearchResponse>, Status> {
+        let request = request.get_ref();
+        let places = match request.level() {
+            proto::Level::Unspecified => search(&request.query),
+            proto::Level::Wilaya => search_level(Level::Wilaya, &request.query),
+            proto::Level::Daira => search_level(Level::Daira, &request.query),
+            proto::Level::Baladyia => search_level(Level::Baladyia, &request.query),
+        };
+        let limit = match request.limit {
+            0 => SEARCH_LIMIT,
+            limit => limit as usize,
+        };
+        Ok(Response::new(proto::SearchResponse {
+            places: places
+                .into_iter()
+                .take(limit)
+                .map(proto::Place::from)
+                .collect(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proto::leblad_service_client::LebladServiceClient;
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tokio_stream::StreamExt;
+    use tonic::transport::{Channel, Server};
+    use tonic::Code;
+
+    /// Serves the service on a local socket in the background, returning a client.
+    async fn client() -> LebladServiceClient<Channel> {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(
+            Server::builder()
+                .add_service(server())
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+        LebladServiceClient::connect(format!("http://{}", address))
+            .await
+            .unwrap()
+    }
+
+    fn code(code: u32) -> proto::CodeRequest {
+        proto::CodeRequest { code }
+    }
+
+    #[tokio::test]
+    async fn lookups() {
+        let mut client = client().await;
+        let wilaya = client.get_wilaya(code(1)).await.unwrap().into_inner();
+        assert_eq!(wilaya.name, "Adrar");
+        assert_eq!(wilaya.dairats[0].code, 101);
+        assert_eq!(wilaya.dairats[0].baladyiats[0].name, "ADRAR");
+        assert!(wilaya.dairats[0].baladyiats_known);
+
+        let wilaya = client.get_wilaya_by_zip_code(code(1000)).await.unwrap();
+        assert_eq!(wilaya.get_ref().mattricule, 1);
+        let iso_code = proto::IsoCodeRequest {
+            iso_code: "DZ-16".to_string(),
+        };
+        let wilaya = client.get_wilaya_by_iso_code(iso_code).await.unwrap();
+        assert_eq!(wilaya.get_ref().mattricule, 16);
+        let daira = client.get_daira(code(101)).await.unwrap();
+        assert_eq!(daira.get_ref().name, "ADRAR");
+        assert_eq!(daira.get_ref().baladyiats[0].code, 101);
+        let baladyia = client.get_baladyia(code(101)).await.unwrap();
+        assert_eq!(baladyia.get_ref().name, "ADRAR");
+    }
+
+    #[test]
+    fn unknown_baladyiats_are_flagged() {
+        let daira = Daira {
+            code: 102,
+            baladyiats: None,
+            ..Default::default()
+        };
+        let res = proto::Daira::from(&daira);
+        assert!(res.baladyiats.is_empty());
+        assert!(!res.baladyiats_known);
+    }
+
+    #[tokio::test]
+    async fn errors() {
+        let mut client = client().await;
+        let status = client.get_wilaya(code(99)).await.unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+        let status = client.get_baladyia(code(70000)).await.unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+        let status = client.get_wilaya_by_phone_code(code(0)).await.unwrap_err();
+        assert_eq!(status.message(), "phone code not found");
+        let status = client.list_dairats(code(99)).await.unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn streams() {
+        let mut client = client().await;
+        let request = proto::ListWilayasRequest {};
+        let wilayas = client.list_wilayas(request).await.unwrap().into_inner();
+        let wilayas = wilayas.collect::<Result<Vec<_>, _>>().await.unwrap();
+        assert_eq!(wilayas.len(), ALL_WILAYAS.len());
+        assert_eq!(wilayas[0], proto::Wilaya::from(&ALL_WILAYAS[0]));
+
+        let dairats = client.list_dairats(code(1)).await.unwrap().into_inner();
+        let codes = dairats.map(|daira| daira.unwrap().code).collect::<Vec<_>>();
+        let codes = codes.await;
+        assert_eq!(codes[0], 101);
+        assert_eq!(codes.len(), ALL_WILAYAS[0].dairats.len());
+
+        let baladyiats = client.list_baladyiats(code(101)).await.unwrap();
+        let names = baladyiats
+            .into_inner()
+            .map(|baladyia| baladyia.unwrap().name);
+        let names = names.collect::<Vec<_>>().await;
+        assert_eq!(names[0], "ADRAR");
+        assert!(names.contains(&"OULED AHMED TIMMI".to_string()));
+    }
+
+    #[tokio::test]
+    async fn search_places() {
+        let mut client = client().await;
+        let request = proto::SearchRequest {
+            query: "adrar".to_string(),
+            level: proto::Level::Daira.into(),
+            limit: 0,
+        };
+        let places = client.search(request).await.unwrap().into_inner().places;
+        assert!(matches!(
+            &places[0].place,
+            Some(proto::place::Place::Daira(daira)) if daira.code == 101
+        ));
+
+        let request = proto::SearchRequest {
+            query: "adrar".to_string(),
+            level: proto::Level::Unspecified.into(),
+            limit: 1,
+        };
+        let places = client.search(request).await.unwrap().into_inner().places;
+        assert_eq!(places, vec![Place::Wilaya(&ALL_WILAYAS[0]).into()]);
+    }
+}
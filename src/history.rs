@@ -0,0 +1,469 @@
+//! Historical administrative divisions and code migrations.
+//!
+//! Algeria was divided into 31 wilayas in 1974, 48 in 1984 and 58 since the 2019 reform.
+//! Every reorganization is described by a list of changes, embedded from `data/history.csv`,
+//! telling which places were carved out of, or renumbered from, which other places.
+//! [`migrate`] uses them to map codes stored at some point in time onto another year's
+//! hierarchy. Wilayas were numbered differently before 1974, so earlier years are not supported.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::Level;
+
+const BUILTIN_CSV: &str = include_str!("../data/history.csv");
+
+/// A reorganization of the administrative divisions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reorganization {
+    /// Year the reorganization took effect.
+    pub year: u16,
+    /// Number of wilayas afterwards, numbered from 1.
+    pub wilayas: u16,
+    /// What changed.
+    pub description: &'static str,
+}
+
+const REORGANIZATIONS: &[Reorganization] = &[
+    Reorganization {
+        year: 1974,
+        wilayas: 31,
+        description: "Division into 31 wilayas, numbered 1 to 31 in alphabetical order.",
+    },
+    Reorganization {
+        year: 1984,
+        wilayas: 48,
+        description: "Creation of 17 wilayas, numbered 32 to 48.",
+    },
+    Reorganization {
+        year: 2019,
+        wilayas: 58,
+        description: "Creation of 10 wilayas in the south, numbered 49 to 58.",
+    },
+];
+
+/// Get all reorganizations, from the oldest to the latest.
+/// # Example
+/// ```
+/// use leblad::history::reorganizations;
+/// let latest = reorganizations().last().unwrap();
+/// assert_eq!((latest.year, latest.wilayas), (2019, 58));
+/// ```
+pub fn reorganizations() -> &'static [Reorganization] {
+    REORGANIZATIONS
+}
+
+/// Get the reorganization in effect in a year, or `None` before 1974.
+pub fn reorganization_in_effect(year: u16) -> Option<&'static Reorganization> {
+    REORGANIZATIONS.iter().rev().find(|r| r.year <= year)
+}
+
+/// Kind of change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    /// The place was carved out of the previous place, which still exists.
+    Split,
+    /// The previous place was renumbered, it does not exist anymore.
+    Moved,
+}
+
+impl ChangeKind {
+    /// Get the kind's name, as used in data files.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Split => "split",
+            ChangeKind::Moved => "moved",
+        }
+    }
+}
+
+/// A change made by a reorganization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Change {
+    /// Year of the reorganization.
+    pub year: u16,
+    /// Level of the place.
+    pub level: Level,
+    /// Code of the place after the reorganization.
+    pub code: u16,
+    /// Code of the place it comes from before the reorganization.
+    pub previous_code: u16,
+    /// Kind of change.
+    pub kind: ChangeKind,
+}
+
+/// Error returned when a history table could not be loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryError {
+    /// Line of the table where the error was found, starting at 1.
+    pub line: usize,
+    /// What is wrong with that line.
+    pub message: String,
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for HistoryError {}
+
+/// Error returned when a code could not be migrated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationError {
+    /// The year is before 1974.
+    UnsupportedYear(u16),
+    /// The wilaya did not exist in the year the code is from.
+    UnknownWilaya {
+        /// Mattricule of the wilaya.
+        code: u16,
+        /// Year the code is from.
+        year: u16,
+    },
+    /// The table has no changes of the level for a reorganization the migration goes through,
+    /// so the codes of that level cannot be mapped across it.
+    MissingHistory {
+        /// Level of the place.
+        level: Level,
+        /// Year of the reorganization.
+        year: u16,
+    },
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::UnsupportedYear(year) => {
+                write!(f, "year {} is before the 1974 numbering", year)
+            }
+            MigrationError::UnknownWilaya { code, year } => {
+                write!(f, "wilaya {} did not exist in {}", code, year)
+            }
+            MigrationError::MissingHistory { level, year } => {
+                write!(f, "no {} changes are known for {}", level.as_str(), year)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+/// Table of changes between reorganizations.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct History {
+    changes: Vec<Change>,
+}
+
+impl History {
+    /// Get the built-in history table.
+    /// # Panics
+    /// This function panics if the embedded table is invalid, which is checked by our tests.
+    pub fn builtin() -> &'static History {
+        static BUILTIN: OnceLock<History> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            History::from_csv(BUILTIN_CSV)
+                .unwrap_or_else(|e| panic!("invalid built-in history: {}", e))
+        })
+    }
+
+    /// Load a history table from CSV.
+    /// The header is `year,level,code,previous_code,kind`, empty lines and lines starting with
+    /// `#` are ignored. The year must be one of a known reorganization.
+    /// # Example
+    /// ```
+    /// use leblad::history::History;
+    /// let csv = "year,level,code,previous_code,kind\n2019,baladyia,4901,102,moved\n";
+    /// let history = History::from_csv(csv).unwrap();
+    /// assert_eq!(history.changes_in(2019).len(), 1);
+    /// ```
+    pub fn from_csv(csv: &str) -> Result<History, HistoryError> {
+        let mut changes = vec![];
+        let mut header_found = false;
+        for (i, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: String| HistoryError {
+                line: i + 1,
+                message,
+            };
+            let fields = line.split(',').map(str::trim).collect::<Vec<&str>>();
+            if !header_found {
+                if fields != ["year", "level", "code", "previous_code", "kind"] {
+                    return Err(error(format!("unexpected header `{}`", line)));
+                }
+                header_found = true;
+                continue;
+            }
+            if fields.len() != 5 {
+                return Err(error(format!("expected 5 fields, found {}", fields.len())));
+            }
+            let year = fields[0]
+                .parse::<u16>()
+                .ok()
+                .filter(|year| REORGANIZATIONS.iter().any(|r| r.year == *year))
+                .ok_or_else(|| error(format!("unknown reorganization `{}`", fields[0])))?;
+            let level = Level::parse(fields[1])
+                .ok_or_else(|| error(format!("unknown level `{}`", fields[1])))?;
+            let code = fields[2]
+                .parse::<u16>()
+                .map_err(|_| error(format!("invalid code `{}`", fields[2])))?;
+            let previous_code = fields[3]
+                .parse::<u16>()
+                .map_err(|_| error(format!("invalid code `{}`", fields[3])))?;
+            let kind = [ChangeKind::Split, ChangeKind::Moved]
+                .into_iter()
+                .find(|kind| kind.as_str() == fields[4])
+                .ok_or_else(|| error(format!("unknown kind `{}`", fields[4])))?;
+            changes.push(Change {
+                year,
+                level,
+                code,
+                previous_code,
+                kind,
+            });
+        }
+        Ok(History { changes })
+    }
+
+    /// Get all the changes of the table.
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Get the changes made by the reorganization of a year.
+    pub fn changes_in(&self, year: u16) -> Vec<&Change> {
+        self.changes.iter().filter(|c| c.year == year).collect()
+    }
+
+    /// Map the code of a place from one year onto another year's hierarchy.
+    ///
+    /// Going forward, a place that was split returns its own code along with the codes of the
+    /// places carved out of it, since a record could now belong to any of them. Going backward,
+    /// a place returns the codes of the places it was carved out of. Codes are sorted.
+    ///
+    /// Every wilaya created by a reorganization must be listed, so wilayas without changes are
+    /// kept as is. Dairats and baladyiats are only kept as is when the table lists changes of
+    /// their level for that reorganization, and the migration fails otherwise.
+    /// # Example
+    /// ```
+    /// use leblad::history::History;
+    /// use leblad::Level;
+    /// let history = History::builtin();
+    /// assert_eq!(history.migrate(Level::Wilaya, 1, 2010, 2020), Ok(vec![1, 49, 50]));
+    /// assert_eq!(history.migrate(Level::Wilaya, 49, 2020, 2010), Ok(vec![1]));
+    /// ```
+    pub fn migrate(
+        &self,
+        level: Level,
+        code: u16,
+        from_year: u16,
+        to_year: u16,
+    ) -> Result<Vec<u16>, MigrationError> {
+        for year in [from_year, to_year] {
+            if reorganization_in_effect(year).is_none() {
+                return Err(MigrationError::UnsupportedYear(year));
+            }
+        }
+        if level == Level::Wilaya && !wilaya_existed(code, from_year) {
+            return Err(MigrationError::UnknownWilaya {
+                code,
+                year: from_year,
+            });
+        }
+        let forward = from_year <= to_year;
+        let (after, until) = if forward {
+            (from_year, to_year)
+        } else {
+            (to_year, from_year)
+        };
+        let mut years = REORGANIZATIONS
+            .iter()
+            .map(|r| r.year)
+            .filter(|year| after < *year && *year <= until)
+            .collect::<Vec<u16>>();
+        if !forward {
+            years.reverse();
+        }
+        let mut codes = vec![code];
+        for year in years {
+            let changes = self
+                .changes
+                .iter()
+                .filter(|c| c.year == year && c.level == level)
+                .collect::<Vec<&Change>>();
+            if level != Level::Wilaya && changes.is_empty() {
+                return Err(MigrationError::MissingHistory { level, year });
+            }
+            let mut migrated = vec![];
+            for code in codes {
+                if forward {
+                    let successors = changes.iter().filter(|c| c.previous_code == code);
+                    if !successors.clone().any(|c| c.kind == ChangeKind::Moved) {
+                        migrated.push(code);
+                    }
+                    migrated.extend(successors.map(|c| c.code));
+                } else {
+                    let predecessors = changes
+                        .iter()
+                        .filter(|c| c.code == code)
+                        .map(|c| c.previous_code)
+                        .collect::<Vec<u16>>();
+                    if predecessors.is_empty() {
+                        migrated.push(code);
+                    }
+                    migrated.extend(predecessors);
+                }
+            }
+            migrated.sort_unstable();
+            migrated.dedup();
+            codes = migrated;
+        }
+        Ok(codes)
+    }
+}
+
+fn wilaya_existed(code: u16, year: u16) -> bool {
+    reorganization_in_effect(year).is_some_and(|r| (1..=r.wilayas).contains(&code))
+}
+
+/// Map a wilaya mattricule from one year onto another year's hierarchy, using the built-in
+/// history table. See [`History::migrate`].
+/// # Example
+/// ```
+/// use leblad::history::migrate;
+/// assert_eq!(migrate(11, 2015, 2024), Ok(vec![11, 53, 54]));
+/// assert_eq!(migrate(33, 1990, 1980), Ok(vec![30]));
+/// assert!(migrate(50, 2015, 2024).is_err());
+/// ```
+pub fn migrate(code: u16, from_year: u16, to_year: u16) -> Result<Vec<u16>, MigrationError> {
+    History::builtin().migrate(Level::Wilaya, code, from_year, to_year)
+}
+
+/// Map a baladyia code from one year onto another year's hierarchy, using the built-in
+/// history table. See [`History::migrate`].
+///
+/// The built-in table does not list the renumbering of communes yet, so migrating a baladyia
+/// across a reorganization fails with [`MigrationError::MissingHistory`].
+/// # Example
+/// ```
+/// use leblad::history::{migrate_baladyia, MigrationError};
+/// use leblad::Level;
+/// assert_eq!(migrate_baladyia(101, 2020, 2024), Ok(vec![101]));
+/// assert_eq!(
+///     migrate_baladyia(101, 2018, 2019),
+///     Err(MigrationError::MissingHistory { level: Level::Baladyia, year: 2019 })
+/// );
+/// ```
+pub fn migrate_baladyia(
+    code: u16,
+    from_year: u16,
+    to_year: u16,
+) -> Result<Vec<u16>, MigrationError> {
+    History::builtin().migrate(Level::Baladyia, code, from_year, to_year)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "year,level,code,previous_code,kind\n";
+
+    #[test]
+    fn builtin_history_is_valid() {
+        let history = History::from_csv(BUILTIN_CSV).unwrap();
+        for change in history.changes() {
+            let reorganization = reorganization_in_effect(change.year).unwrap();
+            let previous = reorganization_in_effect(change.year - 1).unwrap();
+            if change.level == Level::Wilaya {
+                assert!(change.code > previous.wilayas && change.code <= reorganization.wilayas);
+                assert!(wilaya_existed(change.previous_code, change.year - 1));
+            }
+        }
+    }
+
+    #[test]
+    fn every_new_wilaya_has_a_parent() {
+        for window in REORGANIZATIONS.windows(2) {
+            let changes = History::builtin().changes_in(window[1].year);
+            for code in window[0].wilayas + 1..=window[1].wilayas {
+                assert!(changes.iter().any(|c| c.code == code), "wilaya {}", code);
+            }
+        }
+    }
+
+    #[test]
+    fn migrate_wilayas_across_reorganizations() {
+        assert_eq!(migrate(20, 1980, 2024), Ok(vec![20, 32, 45]));
+        assert_eq!(migrate(30, 1980, 2024), Ok(vec![30, 33, 55, 56]));
+        assert_eq!(migrate(56, 2024, 1980), Ok(vec![30]));
+        assert_eq!(migrate(35, 2000, 1980), Ok(vec![15, 16]));
+        assert_eq!(migrate(16, 2000, 2000), Ok(vec![16]));
+        assert_eq!(migrate(16, 1984, 2019), Ok(vec![16]));
+    }
+
+    #[test]
+    fn migrate_invalid_codes_and_years() {
+        assert_eq!(
+            migrate(40, 1980, 2024),
+            Err(MigrationError::UnknownWilaya {
+                code: 40,
+                year: 1980
+            })
+        );
+        assert_eq!(
+            migrate(1, 1970, 2024),
+            Err(MigrationError::UnsupportedYear(1970))
+        );
+        assert!(migrate(0, 2024, 1980).is_err());
+    }
+
+    #[test]
+    fn migrate_baladyiats_without_history() {
+        let csv = format!("{}2019,baladyia,4901,102,moved\n", HEADER);
+        let history = History::from_csv(&csv).unwrap();
+        assert_eq!(
+            history.migrate(Level::Baladyia, 101, 1980, 2019),
+            Err(MigrationError::MissingHistory {
+                level: Level::Baladyia,
+                year: 1984
+            })
+        );
+        assert_eq!(
+            history.migrate(Level::Baladyia, 101, 2018, 2024),
+            Ok(vec![101])
+        );
+    }
+
+    #[test]
+    fn migrate_moved_baladyiats() {
+        let csv = format!(
+            "{}2019,baladyia,4901,102,moved\n2019,baladyia,4902,102,split\n",
+            HEADER
+        );
+        let history = History::from_csv(&csv).unwrap();
+        assert_eq!(
+            history.migrate(Level::Baladyia, 102, 2018, 2019),
+            Ok(vec![4901, 4902])
+        );
+        assert_eq!(
+            history.migrate(Level::Baladyia, 4901, 2019, 2018),
+            Ok(vec![102])
+        );
+        assert_eq!(
+            history.migrate(Level::Baladyia, 101, 2018, 2019),
+            Ok(vec![101])
+        );
+    }
+
+    #[test]
+    fn invalid_rows_are_rejected() {
+        let unknown_year = format!("{}2000,wilaya,49,1,split\n", HEADER);
+        assert_eq!(History::from_csv(&unknown_year).unwrap_err().line, 2);
+        let unknown_kind = format!("{}2019,wilaya,49,1,merged\n", HEADER);
+        assert!(History::from_csv(&unknown_kind).is_err());
+        assert!(History::from_csv("year,code\n").is_err());
+    }
+}
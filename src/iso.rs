@@ -0,0 +1,170 @@
+//! ISO 3166-2:DZ subdivision codes.
+//!
+//! Every wilaya is identified in ISO 3166-2 by `DZ-` followed by its two digits mattricule,
+//! e.g. `DZ-16` for Alger.
+//!
+//! The 10 wilayas created by the 2019 reform (mattricules 49 to 58) were only added to
+//! ISO 3166-2:DZ afterwards, so some providers still only accept `DZ-01` to `DZ-48`.
+//! Their status is exposed through [`IsoStatus`], and [`Wilaya::iso_fallback_code`] gives
+//! the code of the wilaya they were carved out of.
+
+use crate::{get_wilaya_by_code, Wilaya};
+
+/// Mattricules of the wilayas created in 2019, along with the wilaya they were part of.
+const REFORM_2019_PARENTS: &[(u16, u16)] = &[
+    (49, 1),
+    (50, 1),
+    (51, 7),
+    (52, 8),
+    (53, 11),
+    (54, 11),
+    (55, 30),
+    (56, 33),
+    (57, 39),
+    (58, 47),
+];
+
+/// ISO 3166-2:DZ status of a wilaya.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IsoStatus {
+    /// Wilaya listed in ISO 3166-2:DZ before the 2019 reform (`DZ-01` to `DZ-48`).
+    Original,
+    /// Wilaya created in 2019 (`DZ-49` to `DZ-58`), which older copies of the standard do not know.
+    Reform2019 {
+        /// Mattricule of the wilaya it was carved out of.
+        parent: u16,
+    },
+}
+
+impl Wilaya {
+    /// Get the ISO 3166-2 code of the wilaya.
+    /// # Example
+    /// ```
+    /// use leblad::get_wilaya_by_code;
+    /// let wilaya = get_wilaya_by_code(1).unwrap();
+    /// assert_eq!(wilaya.iso_code(), "DZ-01");
+    /// ```
+    pub fn iso_code(&self) -> String {
+        format!("DZ-{:02}", self.mattricule)
+    }
+
+    /// Get the ISO 3166-2:DZ status of the wilaya.
+    /// # Example
+    /// ```
+    /// use leblad::{get_wilaya_by_code, IsoStatus};
+    /// let wilaya = get_wilaya_by_code(1).unwrap();
+    /// assert_eq!(wilaya.iso_status(), IsoStatus::Original);
+    /// ```
+    pub fn iso_status(&self) -> IsoStatus {
+        REFORM_2019_PARENTS
+            .iter()
+            .find(|(mattricule, _)| *mattricule == self.mattricule)
+            .map_or(IsoStatus::Original, |(_, parent)| IsoStatus::Reform2019 {
+                parent: *parent,
+            })
+    }
+
+    /// Get the ISO 3166-2 code to use with systems that only know the pre-2019 wilayas.
+    /// For wilayas created in 2019 this is the code of the wilaya they were part of,
+    /// otherwise it is the same as [`Wilaya::iso_code`].
+    /// # Example
+    /// ```
+    /// use leblad::get_wilaya_by_code;
+    /// let wilaya = get_wilaya_by_code(1).unwrap();
+    /// assert_eq!(wilaya.iso_fallback_code(), "DZ-01");
+    /// ```
+    pub fn iso_fallback_code(&self) -> String {
+        match self.iso_status() {
+            IsoStatus::Original => self.iso_code(),
+            IsoStatus::Reform2019 { parent } => format!("DZ-{:02}", parent),
+        }
+    }
+}
+
+/// Parse an ISO 3166-2:DZ code into a wilaya mattricule.
+/// Both `DZ-16` and `16` forms are accepted, the prefix is case insensitive.
+/// # Example
+/// ```
+/// use leblad::parse_iso_code;
+/// assert_eq!(parse_iso_code("DZ-16"), Some(16));
+/// assert_eq!(parse_iso_code("16"), Some(16));
+/// assert_eq!(parse_iso_code("FR-75"), None);
+/// ```
+pub fn parse_iso_code(iso_code: &str) -> Option<u16> {
+    let iso_code = iso_code.trim();
+    let digits = match iso_code.get(..3) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("DZ-") => &iso_code[3..],
+        _ => iso_code,
+    };
+    if digits.is_empty() || digits.len() > 2 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// Get wilaya by ISO 3166-2 code.
+/// # Example
+/// ```
+/// use leblad::get_wilaya_by_iso_code;
+/// let wilaya = get_wilaya_by_iso_code("DZ-01");
+/// assert_eq!(wilaya.unwrap().name, "Adrar");
+/// ```
+pub fn get_wilaya_by_iso_code<'a>(iso_code: &str) -> Option<&'a Wilaya> {
+    parse_iso_code(iso_code).and_then(get_wilaya_by_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ALL_WILAYAS;
+
+    #[test]
+    fn iso_code_is_zero_padded() {
+        assert_eq!(ALL_WILAYAS[0].iso_code(), "DZ-01");
+    }
+
+    #[test]
+    fn iso_status_of_original_wilaya() {
+        let res = get_wilaya_by_code(1).unwrap();
+        assert_eq!(res.iso_status(), IsoStatus::Original);
+        assert_eq!(res.iso_fallback_code(), "DZ-01");
+    }
+
+    #[test]
+    fn iso_status_of_2019_wilaya() {
+        let res = get_wilaya_by_code(49).unwrap();
+        assert_eq!(res.iso_status(), IsoStatus::Reform2019 { parent: 1 });
+        assert_eq!(res.iso_code(), "DZ-49");
+        assert_eq!(res.iso_fallback_code(), "DZ-01");
+    }
+
+    #[test]
+    fn parse_valid_iso_codes() {
+        assert_eq!(parse_iso_code("DZ-16"), Some(16));
+        assert_eq!(parse_iso_code("dz-01"), Some(1));
+        assert_eq!(parse_iso_code(" 16 "), Some(16));
+        assert_eq!(parse_iso_code("1"), Some(1));
+    }
+
+    #[test]
+    fn parse_invalid_iso_codes() {
+        assert_eq!(parse_iso_code(""), None);
+        assert_eq!(parse_iso_code("DZ-"), None);
+        assert_eq!(parse_iso_code("DZ-016"), None);
+        assert_eq!(parse_iso_code("FR-16"), None);
+        assert_eq!(parse_iso_code("DZ-+1"), None);
+    }
+
+    #[test]
+    fn get_existing_wilaya_by_iso_code() {
+        let res = get_wilaya_by_iso_code("DZ-01");
+        assert!(res.is_some());
+        assert_eq!(*res.unwrap(), ALL_WILAYAS[0]);
+    }
+
+    #[test]
+    fn get_non_existing_wilaya_by_iso_code() {
+        let res = get_wilaya_by_iso_code("DZ-99");
+        assert!(res.is_none());
+    }
+}
@@ -0,0 +1,9 @@
+//! Leaking of places built at runtime, so they can be used wherever the built-in ones are.
+
+pub(crate) fn leak<T>(items: Vec<T>) -> &'static [T] {
+    Box::leak(items.into_boxed_slice())
+}
+
+pub(crate) fn leak_str(s: String) -> &'static str {
+    Box::leak(s.into_boxed_str())
+}
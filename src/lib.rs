@@ -13,10 +13,111 @@
 //! assert_eq!(wilayas[0].name, "Adrar");
 //! ```
 
-use _auto_generated::ALL_WILAYAS;
+use _auto_generated::{ALL_BALADYIATS, ALL_DAIRATS, ALL_WILAYAS};
 pub use _auto_generated::{Baladyia, Daira, Wilaya};
+pub use code::{BaladyiaCode, DairaCode, InvalidCodeError, WilayaCode};
+pub use dataset::{dataset_version, DatasetInfo, Override};
+pub use iso::{get_wilaya_by_iso_code, parse_iso_code, IsoStatus};
+pub use place::{Language, Level, Place};
+pub use region::{get_wilayas_in_region, Grouping, Region, UnknownWilayaError};
+pub use search::{lookup, search, search_level};
 
 mod _auto_generated;
+mod code;
+#[cfg(feature = "crosswalk")]
+pub mod crosswalk;
+#[cfg(feature = "csv")]
+pub mod csv;
+mod dataset;
+#[cfg(feature = "diff")]
+pub mod diff;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "geo")]
+pub mod geo;
+#[cfg(feature = "geojson")]
+pub mod geojson;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "history")]
+pub mod history;
+mod iso;
+#[cfg(any(feature = "csv", feature = "overlay"))]
+mod leak;
+#[cfg(feature = "overlay")]
+pub mod overlay;
+mod place;
+#[cfg(feature = "python")]
+pub mod python;
+mod region;
+mod search;
+#[cfg(feature = "sql")]
+pub mod sql;
+#[cfg(feature = "stats")]
+pub mod stats;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::_auto_generated::*;
+}
+
+/// Get a wilaya at compile time, by code or by name.
+///
+/// Codes are matched as written, so only the plain and zero-padded forms are accepted, e.g.
+/// `wilaya!(1)` and `wilaya!(01)`, but not `wilaya!(0x1)` or `wilaya!(1u16)`.
+/// # Example
+/// ```
+/// use leblad::wilaya;
+/// let wilaya = wilaya!(1);
+/// assert_eq!(wilaya.name, "Adrar");
+/// assert_eq!(wilaya!(01), wilaya);
+/// let wilaya = wilaya!("Adrar");
+/// assert_eq!(wilaya.mattricule, 1);
+/// ```
+/// - It can be used in const contexts as well:
+/// ```
+/// use leblad::{wilaya, Wilaya};
+/// const ADRAR: &Wilaya = wilaya!(1);
+/// assert_eq!(ADRAR.name, "Adrar");
+/// ```
+/// - Unknown codes or names do not compile:
+/// ```compile_fail
+/// use leblad::wilaya;
+/// let wilaya = wilaya!(100);
+/// ```
+#[macro_export]
+macro_rules! wilaya {
+    ($wilaya:tt) => {
+        $crate::__wilaya!($wilaya)
+    };
+}
+
+/// Get a baladyia at compile time, by code.
+///
+/// As with [`wilaya!`], the plain and zero-padded forms of codes are accepted, e.g.
+/// `baladyia!(101)` and `baladyia!(0101)`.
+/// # Example
+/// ```
+/// use leblad::baladyia;
+/// let baladyia = baladyia!(101);
+/// assert_eq!(baladyia.name, "ADRAR");
+/// assert_eq!(baladyia!(0101), baladyia);
+/// ```
+/// - Unknown codes do not compile:
+/// ```compile_fail
+/// use leblad::baladyia;
+/// let baladyia = baladyia!(1);
+/// ```
+#[macro_export]
+macro_rules! baladyia {
+    ($code:tt) => {
+        $crate::__baladyia!($code)
+    };
+}
 
 /// Get all wilayas.
 /// # Example
@@ -62,7 +163,7 @@ mod _auto_generated;
 ///
 /// assert_eq!(filtered_wilayas[0].name_en, "Adrar");
 /// ```
-pub fn get_wilaya_list<'a>() -> &'a [Wilaya] {
+pub const fn get_wilaya_list<'a>() -> &'a [Wilaya] {
     ALL_WILAYAS
 }
 
@@ -76,7 +177,7 @@ pub fn get_wilaya_list<'a>() -> &'a [Wilaya] {
 pub fn get_wilaya_by_zip_code<'a>(zip_code: u16) -> Option<&'a Wilaya> {
     ALL_WILAYAS
         .iter()
-        .find(|wilaya| wilaya.postal_codes.iter().any(|pc| *pc == zip_code))
+        .find(|wilaya| wilaya.postal_codes.contains(&zip_code))
 }
 
 /// Get wilaya by code.
@@ -86,10 +187,21 @@ pub fn get_wilaya_by_zip_code<'a>(zip_code: u16) -> Option<&'a Wilaya> {
 /// let wilaya = get_wilaya_by_code(1);
 /// assert_eq!(wilaya.unwrap().name, "Adrar");
 /// ```
-pub fn get_wilaya_by_code<'a>(mattricule: u16) -> Option<&'a Wilaya> {
-    ALL_WILAYAS
-        .iter()
-        .find(|wilaya| wilaya.mattricule == mattricule)
+/// - This function is `const`, so it can be used to build static tables:
+/// ```
+/// use leblad::{get_wilaya_by_code, Wilaya};
+/// const ADRAR: Option<&Wilaya> = get_wilaya_by_code(1);
+/// assert_eq!(ADRAR.unwrap().name, "Adrar");
+/// ```
+pub const fn get_wilaya_by_code<'a>(mattricule: u16) -> Option<&'a Wilaya> {
+    let mut i = 0;
+    while i < ALL_WILAYAS.len() {
+        if ALL_WILAYAS[i].mattricule == mattricule {
+            return Some(&ALL_WILAYAS[i]);
+        }
+        i += 1;
+    }
+    None
 }
 
 /// Get adjacent wilayas.
@@ -144,7 +256,7 @@ pub fn get_dairats_for_wilaya<'a>(mattricule: u16) -> Option<&'a [Daira]> {
 pub fn get_wilaya_by_phone_code<'a>(phone_code: u16) -> Option<&'a Wilaya> {
     ALL_WILAYAS
         .iter()
-        .find(|wilaya| wilaya.phone_codes.iter().any(|pc| *pc == phone_code))
+        .find(|wilaya| wilaya.phone_codes.contains(&phone_code))
 }
 
 /// Get wilaya by daira name.
@@ -198,6 +310,83 @@ pub fn get_baladyiats_for_daira_code<'a>(daira_code: u16) -> Option<&'a [Baladyi
     })
 }
 
+/// Get daira by code.
+///
+/// Should several dairats share a code, the first one in dataset order is returned.
+/// # Example
+/// ```
+/// use leblad::get_daira_by_code;
+/// let daira = get_daira_by_code(101);
+/// assert_eq!(daira.unwrap().name, "ADRAR");
+/// ```
+/// - This function is `const`, so it can be used to build static tables:
+/// ```
+/// use leblad::{get_daira_by_code, Daira};
+/// const ADRAR: Option<&Daira> = get_daira_by_code(101);
+/// assert_eq!(ADRAR.unwrap().name, "ADRAR");
+/// ```
+pub const fn get_daira_by_code<'a>(daira_code: u16) -> Option<&'a Daira> {
+    find_daira(ALL_DAIRATS, daira_code)
+}
+
+/// Get baladyia by code.
+///
+/// Codes are unique in the upstream dataset. Should a patch give the same code to several
+/// baladyias, the first one in dataset order is returned.
+/// # Example
+/// ```
+/// use leblad::get_baladyia_by_code;
+/// let baladyia = get_baladyia_by_code(101);
+/// assert_eq!(baladyia.unwrap().name, "ADRAR");
+/// ```
+/// - This function is `const`, so it can be used to build static tables:
+/// ```
+/// use leblad::{get_baladyia_by_code, Baladyia};
+/// const ADRAR: Option<&Baladyia> = get_baladyia_by_code(101);
+/// assert_eq!(ADRAR.unwrap().name, "ADRAR");
+/// ```
+pub const fn get_baladyia_by_code<'a>(baladyia_code: u16) -> Option<&'a Baladyia> {
+    find_baladyia(ALL_BALADYIATS, baladyia_code)
+}
+
+// `ALL_DAIRATS` and `ALL_BALADYIATS` are stably sorted by code at build time, so places
+// sharing a code keep their dataset order. These searches return the first of them, the
+// same place as the `baladyia!` macro.
+
+const fn find_daira(dairats: &[Daira], code: u16) -> Option<&Daira> {
+    let (mut low, mut high) = (0, dairats.len());
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if dairats[mid].code < code {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    if low < dairats.len() && dairats[low].code == code {
+        Some(&dairats[low])
+    } else {
+        None
+    }
+}
+
+const fn find_baladyia(baladyiats: &[Baladyia], code: u16) -> Option<&Baladyia> {
+    let (mut low, mut high) = (0, baladyiats.len());
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if baladyiats[mid].code < code {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    if low < baladyiats.len() && baladyiats[low].code == code {
+        Some(&baladyiats[low])
+    } else {
+        None
+    }
+}
+
 /// Get phone codes for wilaya.
 /// # Example
 /// ```
@@ -271,7 +460,7 @@ pub fn get_baladyiats_for_wilaya(wilaya_name: &str) -> Option<Vec<Baladyia>> {
 pub fn get_wilaya_by_baladyia_name(baladyia_name: &str) -> Option<&Wilaya> {
     ALL_WILAYAS.iter().find(|wilaya| {
         wilaya.dairats.iter().any(|daira| {
-            daira.baladyiats.as_ref().map_or(false, |baladyiats| {
+            daira.baladyiats.as_ref().is_some_and(|baladyiats| {
                 baladyiats
                     .iter()
                     .any(|baladyia| baladyia.name == baladyia_name)
@@ -480,6 +669,44 @@ mod tests {
         assert!(res.is_none());
     }
 
+    #[test]
+    fn get_existing_daira_by_code() {
+        let res = get_daira_by_code(101);
+        assert!(res.is_some());
+        assert_eq!(*res.unwrap(), ALL_WILAYAS[0].dairats[0]);
+    }
+
+    #[test]
+    fn get_non_existing_daira_by_code() {
+        let res = get_daira_by_code(1_000);
+        assert!(res.is_none());
+    }
+
+    #[test]
+    fn get_existing_baladyia_by_code() {
+        let res = get_baladyia_by_code(101);
+        assert!(res.is_some());
+        assert_eq!(*res.unwrap(), ALL_WILAYAS[0].dairats[0].baladyiats.unwrap()[0]);
+    }
+
+    #[test]
+    fn get_non_existing_baladyia_by_code() {
+        let res = get_baladyia_by_code(1_000);
+        assert!(res.is_none());
+    }
+
+    #[test]
+    fn get_wilaya_by_code_in_const_context() {
+        const RES: Option<&Wilaya> = get_wilaya_by_code(1);
+        assert_eq!(RES, ALL_WILAYAS.first());
+    }
+
+    #[test]
+    fn all_dairats_and_baladyiats_are_sorted_by_code() {
+        assert!(ALL_DAIRATS.windows(2).all(|w| w[0].code <= w[1].code));
+        assert!(ALL_BALADYIATS.windows(2).all(|w| w[0].code <= w[1].code));
+    }
+
     #[test]
     fn get_existing_phone_codes_for_wilaya() {
         let res = get_phone_codes_for_wilaya("Adrar");
@@ -512,8 +739,8 @@ mod tests {
         assert!(res.is_some());
         let mut baladyiats = vec![];
         for daira in ALL_WILAYAS[0].dairats.iter() {
-            if daira.baladyiats.is_some() {
-                for baladyia in daira.baladyiats.unwrap().iter() {
+            if let Some(daira_baladyiats) = daira.baladyiats {
+                for baladyia in daira_baladyiats.iter() {
                     baladyiats.push(baladyia.clone());
                 }
             }
@@ -552,4 +779,52 @@ mod tests {
         let res = get_daira_by_baladyia_name("TIZELABINE");
         assert!(res.is_none());
     }
+
+    #[test]
+    fn duplicate_codes_resolve_to_the_first_place() {
+        let baladyia = |code, name| Baladyia {
+            code,
+            name,
+            ..Default::default()
+        };
+        let baladyiats = [
+            baladyia(101, "ADRAR"),
+            baladyia(102, "FIRST"),
+            baladyia(102, "SECOND"),
+            baladyia(102, "THIRD"),
+            baladyia(104, "LAST"),
+        ];
+        assert_eq!(find_baladyia(&baladyiats, 102).unwrap().name, "FIRST");
+        assert_eq!(find_baladyia(&baladyiats, 104).unwrap().name, "LAST");
+        assert!(find_baladyia(&baladyiats, 103).is_none());
+        assert!(find_baladyia(&baladyiats, 105).is_none());
+        let dairats = [101, 101, 102].map(|code| Daira {
+            code,
+            name: if code == 101 { "FIRST" } else { "OTHER" },
+            ..Default::default()
+        });
+        assert_eq!(find_daira(&dairats, 101).unwrap().name, "FIRST");
+        assert!(find_daira(&[], 101).is_none());
+    }
+
+    #[test]
+    fn wilaya_macro_by_code() {
+        let res = wilaya!(1);
+        assert_eq!(*res, ALL_WILAYAS[0]);
+        let res = wilaya!(01);
+        assert_eq!(*res, ALL_WILAYAS[0]);
+    }
+
+    #[test]
+    fn wilaya_macro_by_name() {
+        let res = wilaya!("Adrar");
+        assert_eq!(*res, ALL_WILAYAS[0]);
+    }
+
+    #[test]
+    fn baladyia_macro_by_code() {
+        let res = baladyia!(101);
+        assert_eq!(*res, ALL_WILAYAS[0].dairats[0].baladyiats.unwrap()[0]);
+        assert_eq!(baladyia!(0101), res);
+    }
 }
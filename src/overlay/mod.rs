@@ -0,0 +1,315 @@
+//! Local corrections applied over the built-in data.
+//!
+//! A patch lists fields to override by level and code, and can replace the baladyiats of a
+//! daira, e.g. when upstream has none. In JSON:
+//!
+//! ```json
+//! {
+//!     "wilayas": [{ "mattricule": 1, "name_ar": "أدرار", "phone_codes": [49] }],
+//!     "dairats": [{ "code": 102, "baladyiats": [
+//!         { "code": 102, "name": "AOULEF", "name_en": "AOULEF", "name_ar": "أولف" }
+//!     ] }],
+//!     "baladyiats": [{ "code": 101, "name_en": "ADRAR" }]
+//! }
+//! ```
+//!
+//! TOML patches have the same structure, e.g. `[[wilayas]]` tables. Wilayas accept `name`,
+//! `name_ar`, `name_ber`, `name_en`, `phone_codes`, `postal_codes` and `adjacent_wilayas`,
+//! dairats accept `name`, `name_ar`, `name_en` and `baladyiats`, and baladyiats accept
+//! `name`, `name_ar` and `name_en`. Unknown fields and places are rejected.
+//!
+//! Patches are applied at build time by pointing the `LEBLAD_PATCH` environment variable to
+//! a file, in which case [`crate::dataset_version`] reports the overridden fields, or at
+//! runtime with an [`Overlay`].
+
+use std::fmt;
+
+use crate::leak::{leak, leak_str};
+use crate::{Baladyia, Daira, Level, Override, Wilaya, ALL_WILAYAS};
+
+mod schema;
+
+/// Error returned when a patch is invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchError {
+    /// What is wrong with the patch.
+    pub message: String,
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid patch: {}", self.message)
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// The built-in data with a patch applied.
+///
+/// The patched places are leaked so they can be used wherever the built-in ones are, so an
+/// overlay should be loaded once, e.g. at startup.
+#[derive(Debug, Clone)]
+pub struct Overlay {
+    wilayas: &'static [Wilaya],
+    overrides: Vec<Override>,
+}
+
+impl Overlay {
+    /// Apply a JSON patch over the built-in data.
+    /// # Example
+    /// ```
+    /// use leblad::overlay::Overlay;
+    /// use leblad::{Level, Override};
+    /// let overlay = Overlay::from_json(r#"{"baladyiats": [{"code": 101, "name_en": "Adrar"}]}"#).unwrap();
+    /// assert_eq!(overlay.get_baladyia_by_code(101).unwrap().name_en, "Adrar");
+    /// assert_eq!(
+    ///     overlay.overrides(),
+    ///     &[Override { level: Level::Baladyia, code: 101, field: "name_en" }]
+    /// );
+    /// ```
+    pub fn from_json(json: &str) -> Result<Overlay, PatchError> {
+        Overlay::new(schema::Patch::from_json(json))
+    }
+
+    /// Apply a TOML patch over the built-in data.
+    /// # Example
+    /// ```
+    /// use leblad::overlay::Overlay;
+    /// let overlay = Overlay::from_toml("[[wilayas]]\nmattricule = 1\nphone_codes = [49, 29]\n").unwrap();
+    /// assert_eq!(overlay.get_wilaya_by_code(1).unwrap().phone_codes, &[49, 29]);
+    /// assert!(Overlay::from_toml("[[wilayas]]\nmattricule = 1\nzip = 1000\n").is_err());
+    /// ```
+    pub fn from_toml(toml: &str) -> Result<Overlay, PatchError> {
+        Overlay::new(schema::Patch::from_toml(toml))
+    }
+
+    fn new(patch: Result<schema::Patch, String>) -> Result<Overlay, PatchError> {
+        let mut wilayas = ALL_WILAYAS.iter().map(to_owned).collect::<Vec<_>>();
+        let overrides = patch
+            .and_then(|patch| patch.apply(&mut wilayas))
+            .map_err(|message| PatchError { message })?;
+        let overrides = overrides
+            .into_iter()
+            .map(|(level, code, field)| {
+                let level = Level::parse(level).ok_or_else(|| PatchError {
+                    message: format!("unknown level `{}`", level),
+                })?;
+                Ok(Override { level, code, field })
+            })
+            .collect::<Result<_, PatchError>>()?;
+        Ok(Overlay {
+            wilayas: leak(wilayas.into_iter().map(to_static).collect()),
+            overrides,
+        })
+    }
+
+    /// Get the fields overridden by the patch, in the order they were applied.
+    pub fn overrides(&self) -> &[Override] {
+        &self.overrides
+    }
+
+    /// Check if a place has a field overridden by the patch.
+    pub fn is_overridden(&self, level: Level, code: u16) -> bool {
+        self.overrides
+            .iter()
+            .any(|o| o.level == level && o.code == code)
+    }
+
+    /// Get the patched list of wilayas.
+    pub fn wilayas(&self) -> &'static [Wilaya] {
+        self.wilayas
+    }
+
+    /// Get a patched wilaya by mattricule.
+    pub fn get_wilaya_by_code(&self, mattricule: u16) -> Option<&'static Wilaya> {
+        self.wilayas.iter().find(|w| w.mattricule == mattricule)
+    }
+
+    /// Get a patched daira by code.
+    pub fn get_daira_by_code(&self, daira_code: u16) -> Option<&'static Daira> {
+        self.wilayas
+            .iter()
+            .flat_map(|w| w.dairats.iter())
+            .find(|d| d.code == daira_code)
+    }
+
+    /// Get a patched baladyia by code.
+    pub fn get_baladyia_by_code(&self, baladyia_code: u16) -> Option<&'static Baladyia> {
+        self.wilayas
+            .iter()
+            .flat_map(|w| w.dairats.iter())
+            .flat_map(|d| d.baladyiats.into_iter().flatten())
+            .find(|b| b.code == baladyia_code)
+    }
+}
+
+fn to_owned(wilaya: &Wilaya) -> schema::Wilaya {
+    schema::Wilaya {
+        mattricule: wilaya.mattricule,
+        name_ar: wilaya.name_ar.to_string(),
+        name_ber: wilaya.name_ber.to_string(),
+        name_en: wilaya.name_en.to_string(),
+        name: wilaya.name.to_string(),
+        phone_codes: wilaya.phone_codes.to_vec(),
+        postal_codes: wilaya.postal_codes.to_vec(),
+        dairats: wilaya
+            .dairats
+            .iter()
+            .map(|daira| schema::Daira {
+                code: daira.code,
+                name: daira.name.to_string(),
+                name_ar: daira.name_ar.to_string(),
+                name_en: daira.name_en.to_string(),
+                baladyiats: daira.baladyiats.map(|baladyiats| {
+                    baladyiats
+                        .iter()
+                        .map(|baladyia| schema::Baladyia {
+                            code: baladyia.code,
+                            name: baladyia.name.to_string(),
+                            name_en: baladyia.name_en.to_string(),
+                            name_ar: baladyia.name_ar.to_string(),
+                        })
+                        .collect()
+                }),
+            })
+            .collect(),
+        adjacent_wilayas: wilaya.adjacent_wilayas.to_vec(),
+    }
+}
+
+/// Converts a patched wilaya back, keeping the optional data layers of the built-in places.
+// The struct updates only fill fields when data layer features are enabled.
+#[allow(clippy::needless_update)]
+fn to_static(wilaya: schema::Wilaya) -> Wilaya {
+    let dairats = wilaya
+        .dairats
+        .into_iter()
+        .map(|daira| {
+            let baladyiats = daira.baladyiats.map(|baladyiats| {
+                leak(
+                    baladyiats
+                        .into_iter()
+                        .map(|baladyia| Baladyia {
+                            code: baladyia.code,
+                            name: leak_str(baladyia.name),
+                            name_en: leak_str(baladyia.name_en),
+                            name_ar: leak_str(baladyia.name_ar),
+                            ..crate::get_baladyia_by_code(baladyia.code)
+                                .cloned()
+                                .unwrap_or_default()
+                        })
+                        .collect(),
+                ) as &[Baladyia]
+            });
+            Daira {
+                code: daira.code,
+                name: leak_str(daira.name),
+                name_ar: leak_str(daira.name_ar),
+                name_en: leak_str(daira.name_en),
+                baladyiats,
+                ..crate::get_daira_by_code(daira.code)
+                    .cloned()
+                    .unwrap_or_default()
+            }
+        })
+        .collect();
+    Wilaya {
+        mattricule: wilaya.mattricule,
+        name_ar: leak_str(wilaya.name_ar),
+        name_ber: leak_str(wilaya.name_ber),
+        name_en: leak_str(wilaya.name_en),
+        name: leak_str(wilaya.name),
+        phone_codes: leak(wilaya.phone_codes),
+        postal_codes: leak(wilaya.postal_codes),
+        dairats: leak(dairats),
+        adjacent_wilayas: leak(wilaya.adjacent_wilayas),
+        ..crate::get_wilaya_by_code(wilaya.mattricule)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_patch_keeps_the_data() {
+        let overlay = Overlay::from_json("{}").unwrap();
+        assert_eq!(overlay.wilayas(), ALL_WILAYAS);
+        assert!(overlay.overrides().is_empty());
+    }
+
+    #[test]
+    fn patch_every_level() {
+        let daira = &ALL_WILAYAS[0].dairats[0];
+        let json = format!(
+            r#"{{
+                "wilayas": [{{ "mattricule": 1, "name_ar": "ادرار", "adjacent_wilayas": [16] }}],
+                "dairats": [{{ "code": {0}, "name_en": "Daira", "baladyiats": [
+                    {{ "code": 198, "name": "NEW", "name_en": "NEW", "name_ar": "جديد" }}
+                ] }}],
+                "baladyiats": [{{ "code": 198, "name": "NOUVELLE" }}]
+            }}"#,
+            daira.code
+        );
+        let overlay = Overlay::from_json(&json).unwrap();
+        let wilaya = overlay.get_wilaya_by_code(1).unwrap();
+        assert_eq!(wilaya.name_ar, "ادرار");
+        assert_eq!(wilaya.adjacent_wilayas, &[16]);
+        assert_eq!(wilaya.name, ALL_WILAYAS[0].name);
+        assert_eq!(
+            overlay.get_daira_by_code(daira.code).unwrap().name_en,
+            "Daira"
+        );
+        assert_eq!(overlay.get_baladyia_by_code(198).unwrap().name, "NOUVELLE");
+        let fields = overlay
+            .overrides()
+            .iter()
+            .map(|o| (o.level, o.field))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                (Level::Wilaya, "name_ar"),
+                (Level::Wilaya, "adjacent_wilayas"),
+                (Level::Daira, "name_en"),
+                (Level::Daira, "baladyiats"),
+                (Level::Baladyia, "name"),
+            ]
+        );
+        assert!(overlay.is_overridden(Level::Baladyia, 198));
+        assert!(!overlay.is_overridden(Level::Wilaya, 16));
+    }
+
+    #[test]
+    fn invalid_patches_are_rejected() {
+        let invalid = [
+            r#"{ "wilayas": [{ "mattricule": 99 }] }"#,
+            r#"{ "wilayas": [{ "mattricule": 1, "adjacent_wilayas": [99] }] }"#,
+            r#"{ "wilayas": [{ "mattricule": 1, "zip": 1000 }] }"#,
+            r#"{ "communes": [] }"#,
+            r#"{ "baladyiats": [{ "code": 9999, "name": "X" }] }"#,
+            r#"{ "dairats": [{ "code": 101, "baladyiats": [
+                { "code": 1601, "name": "X", "name_en": "X", "name_ar": "X" }
+            ] }] }"#,
+        ];
+        for patch in invalid {
+            assert!(Overlay::from_json(patch).is_err(), "{}", patch);
+        }
+    }
+
+    #[test]
+    fn duplicate_baladyiats_are_rejected() {
+        let baladyia = ALL_WILAYAS[0].dairats[0].baladyiats.unwrap()[0].code;
+        let other = ALL_WILAYAS[0].dairats[1].code;
+        let patch = format!(
+            r#"{{ "dairats": [{{ "code": {}, "baladyiats": [
+                {{ "code": {}, "name": "X", "name_en": "X", "name_ar": "X" }}
+            ] }}] }}"#,
+            other, baladyia
+        );
+        let error = Overlay::from_json(&patch).unwrap_err();
+        assert!(error.message.contains("duplicate"));
+    }
+}
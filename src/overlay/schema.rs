@@ -0,0 +1,223 @@
+//! Owned dataset records and patch files.
+//!
+//! This module is shared with the build script, which applies `LEBLAD_PATCH` before generating
+//! the built-in data, so it must not depend on the rest of the crate.
+
+use serde::Deserialize;
+
+/// A field overridden by a patch: level, code and name of the field.
+pub type Override = (&'static str, u16, &'static str);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Baladyia {
+    pub code: u16,
+    pub name: String,
+    pub name_en: String,
+    pub name_ar: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Daira {
+    pub code: u16,
+    pub name: String,
+    pub name_ar: String,
+    pub name_en: String,
+    pub baladyiats: Option<Vec<Baladyia>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Wilaya {
+    pub mattricule: u16,
+    pub name_ar: String,
+    pub name_ber: String,
+    pub name_en: String,
+    pub name: String,
+    #[serde(rename = "phoneCodes")]
+    pub phone_codes: Vec<u16>,
+    #[serde(rename = "postalCodes")]
+    pub postal_codes: Vec<u16>,
+    pub dairats: Vec<Daira>,
+    #[serde(rename = "adjacentWilayas")]
+    pub adjacent_wilayas: Vec<u16>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Patch {
+    #[serde(default)]
+    pub wilayas: Vec<WilayaPatch>,
+    #[serde(default)]
+    pub dairats: Vec<DairaPatch>,
+    #[serde(default)]
+    pub baladyiats: Vec<BaladyiaPatch>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WilayaPatch {
+    pub mattricule: u16,
+    pub name: Option<String>,
+    pub name_ar: Option<String>,
+    pub name_ber: Option<String>,
+    pub name_en: Option<String>,
+    pub phone_codes: Option<Vec<u16>>,
+    pub postal_codes: Option<Vec<u16>>,
+    pub adjacent_wilayas: Option<Vec<u16>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DairaPatch {
+    pub code: u16,
+    pub name: Option<String>,
+    pub name_ar: Option<String>,
+    pub name_en: Option<String>,
+    pub baladyiats: Option<Vec<Baladyia>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BaladyiaPatch {
+    pub code: u16,
+    pub name: Option<String>,
+    pub name_ar: Option<String>,
+    pub name_en: Option<String>,
+}
+
+impl Patch {
+    pub fn from_json(json: &str) -> Result<Patch, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Patch, String> {
+        toml::from_str(toml).map_err(|e| e.to_string())
+    }
+
+    /// Applies the patch, returning the overridden fields in order.
+    pub fn apply(&self, wilayas: &mut [Wilaya]) -> Result<Vec<Override>, String> {
+        let mut overrides = vec![];
+        let mattricules = wilayas.iter().map(|w| w.mattricule).collect::<Vec<u16>>();
+        for patch in &self.wilayas {
+            let code = patch.mattricule;
+            let wilaya = wilayas
+                .iter_mut()
+                .find(|w| w.mattricule == code)
+                .ok_or_else(|| format!("unknown wilaya {}", code))?;
+            if let Some(unknown) = patch
+                .adjacent_wilayas
+                .iter()
+                .flatten()
+                .find(|adjacent| !mattricules.contains(adjacent))
+            {
+                return Err(format!(
+                    "wilaya {}: unknown adjacent wilaya {}",
+                    code, unknown
+                ));
+            }
+            let mut set_name = |target: &mut String, value: &Option<String>, field| {
+                set(target, value, ("wilaya", code, field), &mut overrides)
+            };
+            set_name(&mut wilaya.name, &patch.name, "name");
+            set_name(&mut wilaya.name_ar, &patch.name_ar, "name_ar");
+            set_name(&mut wilaya.name_ber, &patch.name_ber, "name_ber");
+            set_name(&mut wilaya.name_en, &patch.name_en, "name_en");
+            let mut set_codes = |target: &mut Vec<u16>, value: &Option<Vec<u16>>, field| {
+                set(target, value, ("wilaya", code, field), &mut overrides)
+            };
+            set_codes(&mut wilaya.phone_codes, &patch.phone_codes, "phone_codes");
+            set_codes(
+                &mut wilaya.postal_codes,
+                &patch.postal_codes,
+                "postal_codes",
+            );
+            set_codes(
+                &mut wilaya.adjacent_wilayas,
+                &patch.adjacent_wilayas,
+                "adjacent_wilayas",
+            );
+        }
+        for patch in &self.dairats {
+            let code = patch.code;
+            if let Some(baladyiats) = &patch.baladyiats {
+                validate_baladyiats(wilayas, code, baladyiats)?;
+            }
+            let daira = wilayas
+                .iter_mut()
+                .flat_map(|w| w.dairats.iter_mut())
+                .find(|d| d.code == code)
+                .ok_or_else(|| format!("unknown daira {}", code))?;
+            let mut set_name = |target: &mut String, value: &Option<String>, field| {
+                set(target, value, ("daira", code, field), &mut overrides)
+            };
+            set_name(&mut daira.name, &patch.name, "name");
+            set_name(&mut daira.name_ar, &patch.name_ar, "name_ar");
+            set_name(&mut daira.name_en, &patch.name_en, "name_en");
+            if let Some(baladyiats) = &patch.baladyiats {
+                daira.baladyiats = Some(baladyiats.clone());
+                overrides.push(("daira", code, "baladyiats"));
+            }
+        }
+        for patch in &self.baladyiats {
+            let code = patch.code;
+            let baladyia = wilayas
+                .iter_mut()
+                .flat_map(|w| w.dairats.iter_mut())
+                .flat_map(|d| d.baladyiats.iter_mut().flatten())
+                .find(|b| b.code == code)
+                .ok_or_else(|| format!("unknown baladyia {}", code))?;
+            let mut set_name = |target: &mut String, value: &Option<String>, field| {
+                set(target, value, ("baladyia", code, field), &mut overrides)
+            };
+            set_name(&mut baladyia.name, &patch.name, "name");
+            set_name(&mut baladyia.name_ar, &patch.name_ar, "name_ar");
+            set_name(&mut baladyia.name_en, &patch.name_en, "name_en");
+        }
+        Ok(overrides)
+    }
+}
+
+fn set<T: Clone>(
+    target: &mut T,
+    value: &Option<T>,
+    field: Override,
+    overrides: &mut Vec<Override>,
+) {
+    if let Some(value) = value {
+        *target = value.clone();
+        overrides.push(field);
+    }
+}
+
+/// Checks that the baladyiats of a daira belong to its wilaya and are not used elsewhere.
+fn validate_baladyiats(
+    wilayas: &[Wilaya],
+    daira_code: u16,
+    baladyiats: &[Baladyia],
+) -> Result<(), String> {
+    let mattricule = wilayas
+        .iter()
+        .find(|w| w.dairats.iter().any(|d| d.code == daira_code))
+        .map(|w| w.mattricule)
+        .ok_or_else(|| format!("unknown daira {}", daira_code))?;
+    for (i, baladyia) in baladyiats.iter().enumerate() {
+        if baladyia.code / 100 != mattricule {
+            return Err(format!(
+                "daira {}: baladyia {} is not in wilaya {}",
+                daira_code, baladyia.code, mattricule
+            ));
+        }
+        let elsewhere = wilayas
+            .iter()
+            .flat_map(|w| w.dairats.iter())
+            .filter(|d| d.code != daira_code)
+            .flat_map(|d| d.baladyiats.iter().flatten())
+            .any(|b| b.code == baladyia.code);
+        if elsewhere || baladyiats[..i].iter().any(|b| b.code == baladyia.code) {
+            return Err(format!(
+                "daira {}: duplicate baladyia {}",
+                daira_code, baladyia.code
+            ));
+        }
+    }
+    Ok(())
+}
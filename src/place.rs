@@ -0,0 +1,269 @@
+//! Places at any administrative level.
+
+use crate::{get_baladyia_by_code, get_daira_by_code, get_wilaya_by_code};
+use crate::{Baladyia, Daira, Wilaya, ALL_DAIRATS, ALL_WILAYAS};
+
+/// Administrative level of a place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Level {
+    /// A wilaya, identified by its mattricule.
+    Wilaya,
+    /// A daira, identified by its code.
+    Daira,
+    /// A baladyia, identified by its code.
+    Baladyia,
+}
+
+impl Level {
+    /// Get the level's name, as used in data files.
+    /// # Example
+    /// ```
+    /// use leblad::Level;
+    /// assert_eq!(Level::Baladyia.as_str(), "baladyia");
+    /// ```
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Level::Wilaya => "wilaya",
+            Level::Daira => "daira",
+            Level::Baladyia => "baladyia",
+        }
+    }
+
+    /// Parse a level from its name, case insensitive.
+    /// # Example
+    /// ```
+    /// use leblad::Level;
+    /// assert_eq!(Level::parse("Wilaya"), Some(Level::Wilaya));
+    /// assert_eq!(Level::parse("commune"), None);
+    /// ```
+    pub fn parse(level: &str) -> Option<Level> {
+        [Level::Wilaya, Level::Daira, Level::Baladyia]
+            .into_iter()
+            .find(|l| l.as_str().eq_ignore_ascii_case(level.trim()))
+    }
+}
+
+/// Language of a place's name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Language {
+    /// The main name, in French transliteration.
+    #[default]
+    Fr,
+    /// English.
+    En,
+    /// Arabic.
+    Ar,
+    /// Berber, only known for wilayas.
+    Ber,
+}
+
+impl Language {
+    /// Every language, in declaration order.
+    pub const ALL: [Language; 4] = [Language::Fr, Language::En, Language::Ar, Language::Ber];
+
+    /// Get the language's code.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Language::Fr => "fr",
+            Language::En => "en",
+            Language::Ar => "ar",
+            Language::Ber => "ber",
+        }
+    }
+
+    /// Parse a language from its code, case insensitive.
+    /// # Example
+    /// ```
+    /// use leblad::Language;
+    /// assert_eq!(Language::parse("AR"), Some(Language::Ar));
+    /// assert_eq!(Language::parse("de"), None);
+    /// ```
+    pub fn parse(language: &str) -> Option<Language> {
+        Language::ALL
+            .into_iter()
+            .find(|l| l.as_str().eq_ignore_ascii_case(language.trim()))
+    }
+}
+
+/// A wilaya, daira or baladyia from our database.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Place<'a> {
+    /// A wilaya.
+    Wilaya(&'a Wilaya),
+    /// A daira.
+    Daira(&'a Daira),
+    /// A baladyia.
+    Baladyia(&'a Baladyia),
+}
+
+impl<'a> Place<'a> {
+    /// Get a place by level and code.
+    /// # Example
+    /// ```
+    /// use leblad::{Level, Place};
+    /// let place = Place::get(Level::Wilaya, 1);
+    /// assert_eq!(place.unwrap().name(), "Adrar");
+    /// ```
+    pub const fn get(level: Level, code: u16) -> Option<Place<'a>> {
+        match level {
+            Level::Wilaya => match get_wilaya_by_code(code) {
+                Some(wilaya) => Some(Place::Wilaya(wilaya)),
+                None => None,
+            },
+            Level::Daira => match get_daira_by_code(code) {
+                Some(daira) => Some(Place::Daira(daira)),
+                None => None,
+            },
+            Level::Baladyia => match get_baladyia_by_code(code) {
+                Some(baladyia) => Some(Place::Baladyia(baladyia)),
+                None => None,
+            },
+        }
+    }
+
+    /// Get the place's level.
+    pub const fn level(&self) -> Level {
+        match self {
+            Place::Wilaya(_) => Level::Wilaya,
+            Place::Daira(_) => Level::Daira,
+            Place::Baladyia(_) => Level::Baladyia,
+        }
+    }
+
+    /// Get the place's code, which is the mattricule for wilayas.
+    pub const fn code(&self) -> u16 {
+        match self {
+            Place::Wilaya(wilaya) => wilaya.mattricule,
+            Place::Daira(daira) => daira.code,
+            Place::Baladyia(baladyia) => baladyia.code,
+        }
+    }
+
+    /// Get the place's name.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Place::Wilaya(wilaya) => wilaya.name,
+            Place::Daira(daira) => daira.name,
+            Place::Baladyia(baladyia) => baladyia.name,
+        }
+    }
+
+    /// Get the place's name in English.
+    pub const fn name_en(&self) -> &'static str {
+        match self {
+            Place::Wilaya(wilaya) => wilaya.name_en,
+            Place::Daira(daira) => daira.name_en,
+            Place::Baladyia(baladyia) => baladyia.name_en,
+        }
+    }
+
+    /// Get the place's name in Arabic.
+    pub const fn name_ar(&self) -> &'static str {
+        match self {
+            Place::Wilaya(wilaya) => wilaya.name_ar,
+            Place::Daira(daira) => daira.name_ar,
+            Place::Baladyia(baladyia) => baladyia.name_ar,
+        }
+    }
+
+    /// Get the place's name in a language, or its main name when unknown in that language.
+    /// # Example
+    /// ```
+    /// use leblad::{Language, Level, Place};
+    /// let daira = Place::get(Level::Daira, 101).unwrap();
+    /// assert_eq!(daira.name_in(Language::Ar), daira.name_ar());
+    /// assert_eq!(daira.name_in(Language::Ber), daira.name());
+    /// ```
+    pub const fn name_in(&self, language: Language) -> &'static str {
+        match (language, self) {
+            (Language::Fr, _) => self.name(),
+            (Language::En, _) => self.name_en(),
+            (Language::Ar, _) => self.name_ar(),
+            (Language::Ber, Place::Wilaya(wilaya)) => wilaya.name_ber,
+            (Language::Ber, _) => self.name(),
+        }
+    }
+
+    /// Get the place containing this one: the wilaya of a daira, or the daira of a baladyia.
+    /// # Example
+    /// ```
+    /// use leblad::{Level, Place};
+    /// let baladyia = Place::get(Level::Baladyia, 101).unwrap();
+    /// let daira = baladyia.parent().unwrap();
+    /// assert_eq!(daira.level(), Level::Daira);
+    /// assert_eq!(daira.parent().unwrap().name(), "Adrar");
+    /// ```
+    pub fn parent(&self) -> Option<Place<'static>> {
+        match self {
+            Place::Wilaya(_) => None,
+            Place::Daira(daira) => ALL_WILAYAS
+                .iter()
+                .find(|wilaya| wilaya.dairats.iter().any(|d| d.code == daira.code))
+                .map(Place::Wilaya),
+            Place::Baladyia(baladyia) => ALL_DAIRATS
+                .iter()
+                .find(|daira| {
+                    daira.baladyiats.is_some_and(|baladyiats| {
+                        baladyiats.iter().any(|b| b.code == baladyia.code)
+                    })
+                })
+                .map(Place::Daira),
+        }
+    }
+
+    /// Get the places directly contained in this one: the dairats of a wilaya, or the
+    /// baladyiats of a daira.
+    pub fn children(&self) -> Vec<Place<'a>> {
+        match self {
+            Place::Wilaya(wilaya) => wilaya.dairats.iter().map(Place::Daira).collect(),
+            Place::Daira(daira) => daira
+                .baladyiats
+                .into_iter()
+                .flatten()
+                .map(Place::Baladyia)
+                .collect(),
+            Place::Baladyia(_) => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_levels() {
+        assert_eq!(Level::parse("wilaya"), Some(Level::Wilaya));
+        assert_eq!(Level::parse(" DAIRA "), Some(Level::Daira));
+        assert_eq!(Level::parse("baladyia"), Some(Level::Baladyia));
+        assert_eq!(Level::parse("commune"), None);
+    }
+
+    #[test]
+    fn get_existing_places() {
+        let wilaya = Place::get(Level::Wilaya, 1).unwrap();
+        assert_eq!(wilaya, Place::Wilaya(&ALL_WILAYAS[0]));
+        let daira = Place::get(Level::Daira, 101).unwrap();
+        assert_eq!(daira, Place::Daira(&ALL_WILAYAS[0].dairats[0]));
+        assert_eq!(daira.level(), Level::Daira);
+        assert_eq!(daira.code(), 101);
+    }
+
+    #[test]
+    fn parents_and_children() {
+        let wilaya = Place::Wilaya(&ALL_WILAYAS[0]);
+        assert!(wilaya.parent().is_none());
+        for daira in wilaya.children() {
+            assert_eq!(daira.parent(), Some(wilaya));
+            for baladyia in daira.children() {
+                assert_eq!(baladyia.parent(), Some(daira));
+                assert!(baladyia.children().is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn get_non_existing_place() {
+        assert!(Place::get(Level::Baladyia, 1).is_none());
+    }
+}
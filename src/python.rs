@@ -0,0 +1,447 @@
+//! Python bindings, built as the `leblad` extension module with maturin.
+//!
+//! Places are returned as instances of the `Wilaya`, `Daira` and `Baladyia` classes, and
+//! missing places as `None`. [`records`] exports a level as a list of dictionaries, to load
+//! into pandas with `pandas.DataFrame(leblad.records("baladyia"))`.
+//!
+//! ```sh
+//! maturin develop
+//! pytest tests/python
+//! ```
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::{
+    get_baladyia_by_code, get_daira_by_code, get_wilaya_by_code, get_wilaya_by_iso_code,
+    get_wilaya_by_phone_code, get_wilaya_by_zip_code, search_level, Baladyia, Daira, Level, Place,
+    Wilaya, ALL_WILAYAS,
+};
+
+/// Default number of results of [`search`].
+const DEFAULT_LIMIT: usize = 10;
+
+/// A wilaya of the dataset.
+#[pyclass(name = "Wilaya", module = "leblad", frozen)]
+#[derive(Debug, Clone, Copy)]
+pub struct PyWilaya(&'static Wilaya);
+
+#[pymethods]
+impl PyWilaya {
+    /// Mattricule of the wilaya.
+    #[getter]
+    fn code(&self) -> u16 {
+        self.0.mattricule
+    }
+
+    /// Name of the wilaya.
+    #[getter]
+    fn name(&self) -> &'static str {
+        self.0.name
+    }
+
+    /// Arabic name of the wilaya.
+    #[getter]
+    fn name_ar(&self) -> &'static str {
+        self.0.name_ar
+    }
+
+    /// Berber name of the wilaya, in Tifinagh.
+    #[getter]
+    fn name_ber(&self) -> &'static str {
+        self.0.name_ber
+    }
+
+    /// English name of the wilaya.
+    #[getter]
+    fn name_en(&self) -> &'static str {
+        self.0.name_en
+    }
+
+    /// Phone codes of the wilaya.
+    #[getter]
+    fn phone_codes(&self) -> Vec<u16> {
+        self.0.phone_codes.to_vec()
+    }
+
+    /// Postal codes of the wilaya.
+    #[getter]
+    fn postal_codes(&self) -> Vec<u16> {
+        self.0.postal_codes.to_vec()
+    }
+
+    /// Mattricules of the adjacent wilayas.
+    #[getter]
+    fn adjacent_wilayas(&self) -> Vec<u16> {
+        self.0.adjacent_wilayas.to_vec()
+    }
+
+    /// Dairats of the wilaya.
+    #[getter]
+    fn dairats(&self) -> Vec<PyDaira> {
+        self.0.dairats.iter().map(PyDaira).collect()
+    }
+
+    /// Baladyias of the wilaya, from every daira.
+    #[getter]
+    fn baladyiats(&self) -> Vec<PyBaladyia> {
+        self.0
+            .dairats
+            .iter()
+            .flat_map(|daira| daira.baladyiats.into_iter().flatten())
+            .map(PyBaladyia)
+            .collect()
+    }
+
+    fn __eq__(&self, other: &PyWilaya) -> bool {
+        self.0.mattricule == other.0.mattricule
+    }
+
+    fn __hash__(&self) -> u64 {
+        self.0.mattricule.into()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Wilaya({}, {:?})", self.0.mattricule, self.0.name)
+    }
+}
+
+/// A daira of the dataset.
+#[pyclass(name = "Daira", module = "leblad", frozen)]
+#[derive(Debug, Clone, Copy)]
+pub struct PyDaira(&'static Daira);
+
+#[pymethods]
+impl PyDaira {
+    /// Code of the daira.
+    #[getter]
+    fn code(&self) -> u16 {
+        self.0.code
+    }
+
+    /// Name of the daira.
+    #[getter]
+    fn name(&self) -> &'static str {
+        self.0.name
+    }
+
+    /// Arabic name of the daira.
+    #[getter]
+    fn name_ar(&self) -> &'static str {
+        self.0.name_ar
+    }
+
+    /// English name of the daira.
+    #[getter]
+    fn name_en(&self) -> &'static str {
+        self.0.name_en
+    }
+
+    /// Wilaya of the daira.
+    #[getter]
+    fn wilaya(&self) -> Option<PyWilaya> {
+        get_wilaya_by_code(self.0.code / 100).map(PyWilaya)
+    }
+
+    /// Baladyias of the daira, or `None` when the dataset does not list them.
+    #[getter]
+    fn baladyiats(&self) -> Option<Vec<PyBaladyia>> {
+        let baladyiats = self.0.baladyiats?;
+        Some(baladyiats.iter().map(PyBaladyia).collect())
+    }
+
+    fn __eq__(&self, other: &PyDaira) -> bool {
+        self.0.code == other.0.code
+    }
+
+    fn __hash__(&self) -> u64 {
+        self.0.code.into()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Daira({}, {:?})", self.0.code, self.0.name)
+    }
+}
+
+/// A baladyia of the dataset.
+#[pyclass(name = "Baladyia", module = "leblad", frozen)]
+#[derive(Debug, Clone, Copy)]
+pub struct PyBaladyia(&'static Baladyia);
+
+#[pymethods]
+impl PyBaladyia {
+    /// Code of the baladyia.
+    #[getter]
+    fn code(&self) -> u16 {
+        self.0.code
+    }
+
+    /// Name of the baladyia.
+    #[getter]
+    fn name(&self) -> &'static str {
+        self.0.name
+    }
+
+    /// Arabic name of the baladyia.
+    #[getter]
+    fn name_ar(&self) -> &'static str {
+        self.0.name_ar
+    }
+
+    /// English name of the baladyia.
+    #[getter]
+    fn name_en(&self) -> &'static str {
+        self.0.name_en
+    }
+
+    /// Daira of the baladyia.
+    #[getter]
+    fn daira(&self) -> Option<PyDaira> {
+        match Place::Baladyia(self.0).parent() {
+            Some(Place::Daira(daira)) => Some(PyDaira(daira)),
+            _ => None,
+        }
+    }
+
+    /// Wilaya of the baladyia.
+    #[getter]
+    fn wilaya(&self) -> Option<PyWilaya> {
+        get_wilaya_by_code(self.0.code / 100).map(PyWilaya)
+    }
+
+    fn __eq__(&self, other: &PyBaladyia) -> bool {
+        self.0.code == other.0.code
+    }
+
+    fn __hash__(&self) -> u64 {
+        self.0.code.into()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Baladyia({}, {:?})", self.0.code, self.0.name)
+    }
+}
+
+/// A place of any level, converted to the class of its level.
+#[derive(Debug, Clone, Copy, IntoPyObject)]
+pub enum PyPlace {
+    /// A wilaya.
+    Wilaya(PyWilaya),
+    /// A daira.
+    Daira(PyDaira),
+    /// A baladyia.
+    Baladyia(PyBaladyia),
+}
+
+impl From<Place<'static>> for PyPlace {
+    fn from(place: Place<'static>) -> PyPlace {
+        match place {
+            Place::Wilaya(wilaya) => PyPlace::Wilaya(PyWilaya(wilaya)),
+            Place::Daira(daira) => PyPlace::Daira(PyDaira(daira)),
+            Place::Baladyia(baladyia) => PyPlace::Baladyia(PyBaladyia(baladyia)),
+        }
+    }
+}
+
+/// Get every wilaya.
+#[pyfunction]
+#[pyo3(name = "get_wilaya_list")]
+fn wilaya_list() -> Vec<PyWilaya> {
+    ALL_WILAYAS.iter().map(PyWilaya).collect()
+}
+
+/// Get a wilaya by mattricule.
+#[pyfunction]
+#[pyo3(name = "get_wilaya_by_code")]
+fn wilaya_by_code(mattricule: u16) -> Option<PyWilaya> {
+    get_wilaya_by_code(mattricule).map(PyWilaya)
+}
+
+/// Get a wilaya by postal code.
+#[pyfunction]
+#[pyo3(name = "get_wilaya_by_zip_code")]
+fn wilaya_by_zip_code(zip_code: u16) -> Option<PyWilaya> {
+    get_wilaya_by_zip_code(zip_code).map(PyWilaya)
+}
+
+/// Get a wilaya by phone code.
+#[pyfunction]
+#[pyo3(name = "get_wilaya_by_phone_code")]
+fn wilaya_by_phone_code(phone_code: u16) -> Option<PyWilaya> {
+    get_wilaya_by_phone_code(phone_code).map(PyWilaya)
+}
+
+/// Get a wilaya by ISO 3166-2 code, e.g. `DZ-16`.
+#[pyfunction]
+#[pyo3(name = "get_wilaya_by_iso_code")]
+fn wilaya_by_iso_code(iso_code: &str) -> Option<PyWilaya> {
+    get_wilaya_by_iso_code(iso_code).map(PyWilaya)
+}
+
+/// Get a daira by code.
+#[pyfunction]
+#[pyo3(name = "get_daira_by_code")]
+fn daira_by_code(code: u16) -> Option<PyDaira> {
+    get_daira_by_code(code).map(PyDaira)
+}
+
+/// Get a baladyia by code.
+#[pyfunction]
+#[pyo3(name = "get_baladyia_by_code")]
+fn baladyia_by_code(code: u16) -> Option<PyBaladyia> {
+    get_baladyia_by_code(code).map(PyBaladyia)
+}
+
+/// Get the places of a level with a code or a name in any language, ignoring case and
+/// accents.
+#[pyfunction]
+fn lookup(level: &str, query: &str) -> PyResult<Vec<PyPlace>> {
+    let level = parse_level(level)?;
+    Ok(crate::lookup(level, query)
+        .into_iter()
+        .map(PyPlace::from)
+        .collect())
+}
+
+/// Search places by name or code, optionally of one level only, best matches first.
+#[pyfunction]
+#[pyo3(signature = (query, level = None, limit = DEFAULT_LIMIT))]
+fn search(query: &str, level: Option<&str>, limit: usize) -> PyResult<Vec<PyPlace>> {
+    let places = match level {
+        Some(level) => search_level(parse_level(level)?, query),
+        None => crate::search(query),
+    };
+    Ok(places.into_iter().take(limit).map(PyPlace::from).collect())
+}
+
+/// Export the places of a level as flat records, one dictionary per place with the codes
+/// and names of its parents, e.g. for `pandas.DataFrame(records("daira"))`.
+///
+/// The keys are the columns of the flat CSV table: `wilaya_code`, `wilaya_name`, ...,
+/// `daira_code`, ..., `baladyia_code`, ..., down to the requested level.
+#[pyfunction]
+#[pyo3(signature = (level = "baladyia"))]
+fn records<'py>(py: Python<'py>, level: &str) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    rows(parse_level(level)?)
+        .into_iter()
+        .map(|row| {
+            let record = PyDict::new(py);
+            for (key, value) in row {
+                record.set_item(key, value)?;
+            }
+            Ok(record)
+        })
+        .collect()
+}
+
+/// A field of a record.
+#[derive(Debug, Clone, Copy, PartialEq, IntoPyObject)]
+enum Value {
+    Code(u16),
+    Name(&'static str),
+}
+
+type Row = Vec<(&'static str, Value)>;
+
+fn rows(level: Level) -> Vec<Row> {
+    let mut rows = vec![];
+    for wilaya in ALL_WILAYAS {
+        let wilaya_row = vec![
+            ("wilaya_code", Value::Code(wilaya.mattricule)),
+            ("wilaya_name", Value::Name(wilaya.name)),
+            ("wilaya_name_ar", Value::Name(wilaya.name_ar)),
+            ("wilaya_name_ber", Value::Name(wilaya.name_ber)),
+            ("wilaya_name_en", Value::Name(wilaya.name_en)),
+        ];
+        if level == Level::Wilaya {
+            rows.push(wilaya_row);
+            continue;
+        }
+        for daira in wilaya.dairats {
+            let mut daira_row = wilaya_row.clone();
+            daira_row.extend(daira_fields(daira));
+            match level {
+                Level::Daira => rows.push(daira_row),
+                _ => {
+                    for baladyia in daira.baladyiats.into_iter().flatten() {
+                        let mut baladyia_row = daira_row.clone();
+                        baladyia_row.extend(baladyia_fields(baladyia));
+                        rows.push(baladyia_row);
+                    }
+                }
+            }
+        }
+    }
+    rows
+}
+
+fn daira_fields(daira: &'static Daira) -> Row {
+    vec![
+        ("daira_code", Value::Code(daira.code)),
+        ("daira_name", Value::Name(daira.name)),
+        ("daira_name_ar", Value::Name(daira.name_ar)),
+        ("daira_name_en", Value::Name(daira.name_en)),
+    ]
+}
+
+fn baladyia_fields(baladyia: &'static Baladyia) -> Row {
+    vec![
+        ("baladyia_code", Value::Code(baladyia.code)),
+        ("baladyia_name", Value::Name(baladyia.name)),
+        ("baladyia_name_ar", Value::Name(baladyia.name_ar)),
+        ("baladyia_name_en", Value::Name(baladyia.name_en)),
+    ]
+}
+
+fn parse_level(level: &str) -> PyResult<Level> {
+    Level::parse(level).ok_or_else(|| PyValueError::new_err(format!("unknown level {:?}", level)))
+}
+
+/// The `leblad` Python module.
+#[pymodule]
+fn leblad(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyWilaya>()?;
+    module.add_class::<PyDaira>()?;
+    module.add_class::<PyBaladyia>()?;
+    module.add_function(wrap_pyfunction!(wilaya_list, module)?)?;
+    module.add_function(wrap_pyfunction!(wilaya_by_code, module)?)?;
+    module.add_function(wrap_pyfunction!(wilaya_by_zip_code, module)?)?;
+    module.add_function(wrap_pyfunction!(wilaya_by_phone_code, module)?)?;
+    module.add_function(wrap_pyfunction!(wilaya_by_iso_code, module)?)?;
+    module.add_function(wrap_pyfunction!(daira_by_code, module)?)?;
+    module.add_function(wrap_pyfunction!(baladyia_by_code, module)?)?;
+    module.add_function(wrap_pyfunction!(lookup, module)?)?;
+    module.add_function(wrap_pyfunction!(search, module)?)?;
+    module.add_function(wrap_pyfunction!(records, module)?)?;
+    module.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(row: &Row) -> Vec<&'static str> {
+        row.iter().map(|(key, _)| *key).collect()
+    }
+
+    #[test]
+    fn records_of_each_level() {
+        let wilayas = rows(Level::Wilaya);
+        assert_eq!(wilayas.len(), ALL_WILAYAS.len());
+        assert_eq!(wilayas[0][0], ("wilaya_code", Value::Code(1)));
+        assert_eq!(wilayas[0][1], ("wilaya_name", Value::Name("Adrar")));
+
+        let dairats = rows(Level::Daira);
+        assert_eq!(dairats.len(), crate::ALL_DAIRATS.len());
+        assert_eq!(dairats[0][5], ("daira_code", Value::Code(101)));
+
+        let baladyiats = rows(Level::Baladyia);
+        assert_eq!(baladyiats.len(), crate::ALL_BALADYIATS.len());
+        assert_eq!(baladyiats[0][10], ("baladyia_name", Value::Name("ADRAR")));
+        #[cfg(feature = "csv")]
+        assert_eq!(keys(&baladyiats[0]).join(","), crate::csv::FLAT_HEADER);
+        assert_eq!(keys(&baladyiats[0])[..9], keys(&dairats[0])[..]);
+    }
+}
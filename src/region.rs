@@ -0,0 +1,290 @@
+//! Geographic and planning regions of wilayas, and custom groupings.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::{get_wilaya_by_code, Wilaya, ALL_WILAYAS};
+
+/// A region of Algeria.
+///
+/// [`Region::Tell`], [`Region::HautsPlateaux`] and [`Region::Sud`] are the geographic regions,
+/// every wilaya belongs to exactly one of them. [`Region::GrandSud`] is a programme region
+/// grouping the far southern wilayas, which also belong to [`Region::Sud`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Region {
+    /// The coastal and Tell Atlas wilayas, also known as Nord.
+    Tell,
+    /// The wilayas of the high plateaus, between the Tell and Saharan Atlas.
+    HautsPlateaux,
+    /// The Saharan wilayas.
+    Sud,
+    /// The far southern wilayas.
+    GrandSud,
+}
+
+const HAUTS_PLATEAUX: &[u16] = &[4, 5, 12, 14, 17, 19, 20, 28, 32, 34, 38, 40, 45];
+const SUD: &[u16] = &[
+    1, 3, 7, 8, 11, 30, 33, 37, 39, 47, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58,
+];
+const GRAND_SUD: &[u16] = &[1, 8, 11, 33, 37, 49, 50, 52, 53, 54, 56];
+
+impl Region {
+    /// All regions.
+    pub const ALL: [Region; 4] = [
+        Region::Tell,
+        Region::HautsPlateaux,
+        Region::Sud,
+        Region::GrandSud,
+    ];
+
+    /// Get the region's name.
+    /// # Example
+    /// ```
+    /// use leblad::Region;
+    /// assert_eq!(Region::HautsPlateaux.as_str(), "hauts-plateaux");
+    /// ```
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Region::Tell => "tell",
+            Region::HautsPlateaux => "hauts-plateaux",
+            Region::Sud => "sud",
+            Region::GrandSud => "grand-sud",
+        }
+    }
+
+    /// Parse a region from its name, case insensitive. `nord` is accepted for [`Region::Tell`].
+    /// # Example
+    /// ```
+    /// use leblad::Region;
+    /// assert_eq!(Region::parse("Grand-Sud"), Some(Region::GrandSud));
+    /// assert_eq!(Region::parse("nord"), Some(Region::Tell));
+    /// ```
+    pub fn parse(region: &str) -> Option<Region> {
+        let region = region.trim();
+        if region.eq_ignore_ascii_case("nord") {
+            return Some(Region::Tell);
+        }
+        Region::ALL
+            .into_iter()
+            .find(|r| r.as_str().eq_ignore_ascii_case(region))
+    }
+
+    /// Check if a wilaya is in the region.
+    /// # Example
+    /// ```
+    /// use leblad::{wilaya, Region};
+    /// assert!(Region::Sud.contains(wilaya!(1)));
+    /// assert!(!Region::Sud.contains(wilaya!(16)));
+    /// ```
+    pub fn contains(&self, wilaya: &Wilaya) -> bool {
+        let code = wilaya.mattricule;
+        match self {
+            Region::Tell => !HAUTS_PLATEAUX.contains(&code) && !SUD.contains(&code),
+            Region::HautsPlateaux => HAUTS_PLATEAUX.contains(&code),
+            Region::Sud => SUD.contains(&code),
+            Region::GrandSud => GRAND_SUD.contains(&code),
+        }
+    }
+}
+
+impl Wilaya {
+    /// Get the geographic region of the wilaya.
+    /// # Example
+    /// ```
+    /// use leblad::{wilaya, Region};
+    /// assert_eq!(wilaya!(16).region(), Region::Tell);
+    /// assert_eq!(wilaya!(1).region(), Region::Sud);
+    /// ```
+    pub fn region(&self) -> Region {
+        [Region::HautsPlateaux, Region::Sud]
+            .into_iter()
+            .find(|region| region.contains(self))
+            .unwrap_or(Region::Tell)
+    }
+
+    /// Get all the regions of the wilaya, including programme regions.
+    /// # Example
+    /// ```
+    /// use leblad::{wilaya, Region};
+    /// assert_eq!(wilaya!(49).regions(), vec![Region::Sud, Region::GrandSud]);
+    /// ```
+    pub fn regions(&self) -> Vec<Region> {
+        Region::ALL
+            .into_iter()
+            .filter(|region| region.contains(self))
+            .collect()
+    }
+}
+
+/// Get the wilayas of a region.
+/// # Example
+/// ```
+/// use leblad::{get_wilayas_in_region, Region};
+/// let wilayas = get_wilayas_in_region(Region::GrandSud);
+/// assert!(wilayas.iter().any(|wilaya| wilaya.name == "Timimoun"));
+/// ```
+pub fn get_wilayas_in_region<'a>(region: Region) -> Vec<&'a Wilaya> {
+    ALL_WILAYAS
+        .iter()
+        .filter(|wilaya| region.contains(wilaya))
+        .collect()
+}
+
+/// Error returned when a grouping refers to a wilaya that does not exist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownWilayaError(pub u16);
+
+impl fmt::Display for UnknownWilayaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown wilaya {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownWilayaError {}
+
+/// Custom grouping of wilayas, such as shipping zones.
+///
+/// Groups are identified by name, and a wilaya may belong to several groups.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Grouping {
+    groups: BTreeMap<String, Vec<u16>>,
+}
+
+impl Grouping {
+    /// Create an empty grouping.
+    pub fn new() -> Grouping {
+        Grouping::default()
+    }
+
+    /// Create a grouping with one group per region.
+    /// # Example
+    /// ```
+    /// use leblad::{wilaya, Grouping};
+    /// let grouping = Grouping::from_regions();
+    /// assert_eq!(grouping.groups_of(wilaya!(1)), vec!["grand-sud", "sud"]);
+    /// ```
+    pub fn from_regions() -> Grouping {
+        let mut grouping = Grouping::new();
+        for region in Region::ALL {
+            let codes = get_wilayas_in_region(region)
+                .iter()
+                .map(|wilaya| wilaya.mattricule)
+                .collect();
+            grouping.groups.insert(region.as_str().to_string(), codes);
+        }
+        grouping
+    }
+
+    /// Add wilayas to a group, creating it if needed.
+    /// # Example
+    /// ```
+    /// use leblad::{wilaya, Grouping};
+    /// let mut grouping = Grouping::new();
+    /// grouping.add("zone-a", &[16]).unwrap();
+    /// grouping.add("zone-b", &[1, 49]).unwrap();
+    /// assert_eq!(grouping.groups_of(wilaya!(49)), vec!["zone-b"]);
+    /// assert!(grouping.add("zone-c", &[99]).is_err());
+    /// ```
+    pub fn add(&mut self, group: &str, mattricules: &[u16]) -> Result<(), UnknownWilayaError> {
+        if let Some(unknown) = mattricules
+            .iter()
+            .find(|code| get_wilaya_by_code(**code).is_none())
+        {
+            return Err(UnknownWilayaError(*unknown));
+        }
+        let codes = self.groups.entry(group.to_string()).or_default();
+        for code in mattricules {
+            if !codes.contains(code) {
+                codes.push(*code);
+            }
+        }
+        codes.sort_unstable();
+        Ok(())
+    }
+
+    /// Remove a group, returning whether it existed.
+    pub fn remove(&mut self, group: &str) -> bool {
+        self.groups.remove(group).is_some()
+    }
+
+    /// Get the names of all groups, sorted.
+    pub fn groups(&self) -> Vec<&str> {
+        self.groups.keys().map(String::as_str).collect()
+    }
+
+    /// Get the names of the groups a wilaya belongs to, sorted.
+    pub fn groups_of(&self, wilaya: &Wilaya) -> Vec<&str> {
+        self.groups
+            .iter()
+            .filter(|(_, codes)| codes.contains(&wilaya.mattricule))
+            .map(|(group, _)| group.as_str())
+            .collect()
+    }
+
+    /// Get the wilayas of a group, sorted by mattricule.
+    pub fn wilayas_in<'a>(&self, group: &str) -> Option<Vec<&'a Wilaya>> {
+        self.groups.get(group).map(|codes| {
+            codes
+                .iter()
+                .filter_map(|code| get_wilaya_by_code(*code))
+                .collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geographic_regions_partition_wilayas() {
+        let count = [Region::Tell, Region::HautsPlateaux, Region::Sud]
+            .into_iter()
+            .map(|region| get_wilayas_in_region(region).len())
+            .sum::<usize>();
+        assert_eq!(count, ALL_WILAYAS.len());
+        for wilaya in ALL_WILAYAS {
+            assert!(wilaya.region().contains(wilaya));
+        }
+    }
+
+    #[test]
+    fn grand_sud_is_in_sud() {
+        let grand_sud = get_wilayas_in_region(Region::GrandSud);
+        assert!(!grand_sud.is_empty());
+        assert!(grand_sud
+            .iter()
+            .all(|wilaya| wilaya.region() == Region::Sud));
+    }
+
+    #[test]
+    fn region_names_round_trip() {
+        for region in Region::ALL {
+            assert_eq!(Region::parse(region.as_str()), Some(region));
+        }
+        assert_eq!(Region::parse("centre"), None);
+    }
+
+    #[test]
+    fn custom_grouping() {
+        let mut grouping = Grouping::new();
+        grouping.add("express", &[16, 1]).unwrap();
+        grouping.add("express", &[1, 49]).unwrap();
+        grouping.add("standard", &[16]).unwrap();
+        let codes = grouping
+            .wilayas_in("express")
+            .unwrap()
+            .iter()
+            .map(|wilaya| wilaya.mattricule)
+            .collect::<Vec<u16>>();
+        assert_eq!(codes, vec![1, 16, 49]);
+        assert_eq!(
+            grouping.groups_of(get_wilaya_by_code(16).unwrap()),
+            vec!["express", "standard"]
+        );
+        assert_eq!(grouping.add("express", &[0]), Err(UnknownWilayaError(0)));
+        assert!(grouping.remove("standard"));
+        assert_eq!(grouping.groups(), vec!["express"]);
+        assert!(grouping.wilayas_in("standard").is_none());
+    }
+}
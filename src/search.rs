@@ -0,0 +1,189 @@
+//! Search of places by name or code.
+//!
+//! Names are compared in every language, ignoring case, Latin accents, Arabic diacritics and
+//! punctuation, so `bordj bou arreridj`, `BORDJ-BOU-ARRERIDJ` and `برج بوعريريج` all match.
+
+use crate::{Level, Place, ALL_BALADYIATS, ALL_DAIRATS, ALL_WILAYAS};
+
+/// Search places of every level by name or code.
+///
+/// Results are ranked: exact matches first, then names starting with the query, then names
+/// with a word starting with the query, then names containing it. Ties are sorted by level and
+/// code. A numeric query matches the places with that code.
+/// # Example
+/// ```
+/// use leblad::{search, Level};
+/// let results = search("adrar");
+/// assert_eq!(results[0].level(), Level::Wilaya);
+/// assert_eq!(results[0].name(), "Adrar");
+/// ```
+pub fn search<'a>(query: &str) -> Vec<Place<'a>> {
+    rank(query, places())
+}
+
+/// Search places of one level by name or code, see [`search`].
+/// # Example
+/// ```
+/// use leblad::{search_level, Level};
+/// let results = search_level(Level::Baladyia, "adr");
+/// assert!(results.iter().all(|place| place.level() == Level::Baladyia));
+/// assert_eq!(results[0].name(), "ADRAR");
+/// ```
+pub fn search_level<'a>(level: Level, query: &str) -> Vec<Place<'a>> {
+    rank(query, places().filter(move |place| place.level() == level))
+}
+
+/// Look up places of one level by exact code or name, in any language.
+/// Unlike [`search_level`], partial names do not match.
+/// # Example
+/// ```
+/// use leblad::{lookup, Level};
+/// assert_eq!(lookup(Level::Wilaya, "ADRAR")[0].code(), 1);
+/// assert_eq!(lookup(Level::Wilaya, "1")[0].name(), "Adrar");
+/// assert!(lookup(Level::Wilaya, "adr").is_empty());
+/// ```
+pub fn lookup<'a>(level: Level, query: &str) -> Vec<Place<'a>> {
+    let query = query.trim();
+    let code = query.parse::<u16>().ok();
+    let query = normalize(query);
+    places()
+        .filter(|place| place.level() == level)
+        .filter(|place| match code {
+            Some(code) => place.code() == code,
+            None => names(place).any(|name| normalize(name) == query),
+        })
+        .collect()
+}
+
+fn places<'a>() -> impl Iterator<Item = Place<'a>> {
+    ALL_WILAYAS
+        .iter()
+        .map(Place::Wilaya)
+        .chain(ALL_DAIRATS.iter().map(Place::Daira))
+        .chain(ALL_BALADYIATS.iter().map(Place::Baladyia))
+}
+
+fn rank<'a>(query: &str, places: impl Iterator<Item = Place<'a>>) -> Vec<Place<'a>> {
+    let query = query.trim();
+    let code = query.parse::<u16>().ok();
+    let query = normalize(query);
+    if query.is_empty() {
+        return vec![];
+    }
+    let mut results = places
+        .filter_map(|place| {
+            if code.is_some() {
+                return (code == Some(place.code())).then_some((0, place));
+            }
+            names(&place)
+                .filter_map(|name| score(&normalize(name), &query))
+                .min()
+                .map(|score| (score, place))
+        })
+        .collect::<Vec<(u8, Place)>>();
+    results.sort_by_key(|(score, place)| (*score, place.level(), place.code()));
+    results.into_iter().map(|(_, place)| place).collect()
+}
+
+fn names<'a>(place: &Place<'a>) -> impl Iterator<Item = &'static str> {
+    let name_ber = match place {
+        Place::Wilaya(wilaya) => Some(wilaya.name_ber),
+        _ => None,
+    };
+    [place.name(), place.name_en(), place.name_ar()]
+        .into_iter()
+        .chain(name_ber)
+}
+
+fn score(name: &str, query: &str) -> Option<u8> {
+    if name == query {
+        Some(0)
+    } else if name.starts_with(query) {
+        Some(1)
+    } else if name.split(' ').any(|word| word.starts_with(query)) {
+        Some(2)
+    } else if name.contains(query) {
+        Some(3)
+    } else {
+        None
+    }
+}
+
+/// Normalizes a name for comparisons: lowercase, without accents, Arabic diacritics nor
+/// punctuation, and with single spaces between words.
+fn normalize(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for c in name.chars().flat_map(char::to_lowercase) {
+        let c = match c {
+            'à' | 'â' | 'ä' | 'á' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'î' | 'ï' | 'í' => 'i',
+            'ô' | 'ö' | 'ó' => 'o',
+            'û' | 'ù' | 'ü' | 'ú' => 'u',
+            'ç' => 'c',
+            'أ' | 'إ' | 'آ' | 'ٱ' => 'ا',
+            'ى' => 'ي',
+            'ة' => 'ه',
+            // Harakat, shadda, sukun and tatweel.
+            '\u{064B}'..='\u{0652}' | '\u{0640}' => continue,
+            c if c.is_alphanumeric() => c,
+            _ => ' ',
+        };
+        if c != ' ' || !(normalized.is_empty() || normalized.ends_with(' ')) {
+            normalized.push(c);
+        }
+    }
+    normalized.truncate(normalized.trim_end().len());
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_names() {
+        assert_eq!(normalize("  Bordj-Bou-Arréridj "), "bordj bou arreridj");
+        assert_eq!(normalize("Aïn Témouchent"), "ain temouchent");
+        assert_eq!(normalize("EL  M'GHAIR"), "el m ghair");
+        assert_eq!(normalize("أَدْرَار"), "ادرار");
+        assert_eq!(normalize("--"), "");
+    }
+
+    #[test]
+    fn scores_by_position() {
+        assert_eq!(score("adrar", "adrar"), Some(0));
+        assert_eq!(score("adrar", "adr"), Some(1));
+        assert_eq!(score("ouled ahmed timmi", "ahm"), Some(2));
+        assert_eq!(score("adrar", "rar"), Some(3));
+        assert_eq!(score("adrar", "alger"), None);
+    }
+
+    #[test]
+    fn search_ranks_and_deduplicates() {
+        let results = search("ADRAR");
+        assert_eq!(results[0], Place::Wilaya(&ALL_WILAYAS[0]));
+        let wilayas = results
+            .iter()
+            .filter(|place| place.level() == Level::Wilaya)
+            .count();
+        assert_eq!(wilayas, 1);
+        assert!(search("").is_empty());
+        assert!(search("zzzz").is_empty());
+    }
+
+    #[test]
+    fn search_by_code() {
+        let results = search("101");
+        assert!(!results.is_empty());
+        assert!(results.iter().all(|place| place.code() == 101));
+        assert_eq!(search_level(Level::Wilaya, "1")[0].name(), "Adrar");
+    }
+
+    #[test]
+    fn search_in_arabic() {
+        let wilaya = &ALL_WILAYAS[0];
+        let results = search_level(Level::Wilaya, wilaya.name_ar);
+        assert_eq!(results[0], Place::Wilaya(wilaya));
+    }
+}
@@ -0,0 +1,610 @@
+//! SQL dumps of the dataset, for seeding databases.
+//!
+//! [`dump`] generates the tables and the INSERT statements for PostgreSQL, MySQL or SQLite.
+//! The schema has a table per level, linked by foreign keys, and tables for the phone codes,
+//! postal codes and adjacent wilayas of each wilaya:
+//!
+//! - `wilayas (code, name, name_ar, name_ber, name_en)`,
+//! - `dairats (code, wilaya_code, name, name_ar, name_en)`,
+//! - `baladyiats (code, daira_code, name, name_ar, name_en)`,
+//! - `wilaya_phone_codes (wilaya_code, phone_code)`,
+//! - `wilaya_postal_codes (wilaya_code, postal_code)`,
+//! - `wilaya_adjacency (wilaya_code, adjacent_wilaya_code)`.
+//!
+//! [`migration`] generates the statements upgrading a database seeded with a version of the
+//! dataset to another one, from two [`Snapshot`]s.
+
+use std::fmt::Write;
+
+use crate::diff::{diff, ChangeKind, Snapshot};
+use crate::{dataset_version, Level, Wilaya};
+
+/// Maximum number of rows of an INSERT statement.
+const ROWS_PER_INSERT: usize = 500;
+
+/// SQL dialect of the generated statements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dialect {
+    /// PostgreSQL.
+    Postgres,
+    /// MySQL, with the InnoDB engine.
+    MySql,
+    /// SQLite.
+    Sqlite,
+}
+
+impl Dialect {
+    /// All the dialects.
+    pub const ALL: [Dialect; 3] = [Dialect::Postgres, Dialect::MySql, Dialect::Sqlite];
+
+    /// Get the name of the dialect.
+    /// # Example
+    /// ```
+    /// use leblad::sql::Dialect;
+    /// assert_eq!(Dialect::MySql.as_str(), "mysql");
+    /// ```
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Dialect::Postgres => "postgres",
+            Dialect::MySql => "mysql",
+            Dialect::Sqlite => "sqlite",
+        }
+    }
+
+    /// Parse the name of a dialect, ignoring case.
+    /// # Example
+    /// ```
+    /// use leblad::sql::Dialect;
+    /// assert_eq!(Dialect::parse("SQLite"), Some(Dialect::Sqlite));
+    /// assert_eq!(Dialect::parse("oracle"), None);
+    /// ```
+    pub fn parse(dialect: &str) -> Option<Dialect> {
+        Dialect::ALL
+            .into_iter()
+            .find(|d| d.as_str().eq_ignore_ascii_case(dialect.trim()))
+    }
+
+    fn text_type(&self) -> &'static str {
+        match self {
+            Dialect::MySql => "VARCHAR(255)",
+            Dialect::Postgres | Dialect::Sqlite => "TEXT",
+        }
+    }
+
+    fn table_options(&self) -> &'static str {
+        match self {
+            Dialect::MySql => " ENGINE=InnoDB DEFAULT CHARSET=utf8mb4",
+            Dialect::Postgres | Dialect::Sqlite => "",
+        }
+    }
+
+    /// Quotes a string literal.
+    fn literal(&self, value: &str) -> String {
+        let value = value.replace('\'', "''");
+        match self {
+            // MySQL also treats backslashes as escapes by default.
+            Dialect::MySql => format!("'{}'", value.replace('\\', "\\\\")),
+            Dialect::Postgres | Dialect::Sqlite => format!("'{}'", value),
+        }
+    }
+}
+
+/// Generate the statements creating the tables.
+/// # Example
+/// ```
+/// use leblad::sql::{schema, Dialect};
+/// let ddl = schema(Dialect::Postgres);
+/// assert!(ddl.contains("FOREIGN KEY (wilaya_code) REFERENCES wilayas (code)"));
+/// ```
+pub fn schema(dialect: Dialect) -> String {
+    let text = dialect.text_type();
+    let name = |column: &str| format!("{} {} NOT NULL", column, text);
+    let code = |column: &str| format!("{} SMALLINT NOT NULL", column);
+    let foreign_key =
+        |column: &str, table: &str| format!("FOREIGN KEY ({}) REFERENCES {} (code)", column, table);
+    let tables = [
+        (
+            "wilayas",
+            vec![
+                "code SMALLINT NOT NULL PRIMARY KEY".to_string(),
+                name("name"),
+                name("name_ar"),
+                name("name_ber"),
+                name("name_en"),
+            ],
+        ),
+        (
+            "dairats",
+            vec![
+                "code SMALLINT NOT NULL PRIMARY KEY".to_string(),
+                code("wilaya_code"),
+                name("name"),
+                name("name_ar"),
+                name("name_en"),
+                foreign_key("wilaya_code", "wilayas"),
+            ],
+        ),
+        (
+            "baladyiats",
+            vec![
+                "code SMALLINT NOT NULL PRIMARY KEY".to_string(),
+                code("daira_code"),
+                name("name"),
+                name("name_ar"),
+                name("name_en"),
+                foreign_key("daira_code", "dairats"),
+            ],
+        ),
+        (
+            "wilaya_phone_codes",
+            vec![
+                code("wilaya_code"),
+                code("phone_code"),
+                "PRIMARY KEY (wilaya_code, phone_code)".to_string(),
+                foreign_key("wilaya_code", "wilayas"),
+            ],
+        ),
+        (
+            "wilaya_postal_codes",
+            vec![
+                code("wilaya_code"),
+                // Postal codes go up to 58000, beyond SMALLINT.
+                "postal_code INTEGER NOT NULL".to_string(),
+                "PRIMARY KEY (wilaya_code, postal_code)".to_string(),
+                foreign_key("wilaya_code", "wilayas"),
+            ],
+        ),
+        (
+            "wilaya_adjacency",
+            vec![
+                code("wilaya_code"),
+                code("adjacent_wilaya_code"),
+                "PRIMARY KEY (wilaya_code, adjacent_wilaya_code)".to_string(),
+                foreign_key("wilaya_code", "wilayas"),
+                foreign_key("adjacent_wilaya_code", "wilayas"),
+            ],
+        ),
+    ];
+    let mut sql = String::new();
+    for (table, columns) in tables {
+        let _ = writeln!(
+            sql,
+            "CREATE TABLE {} (\n    {}\n){};\n",
+            table,
+            columns.join(",\n    "),
+            dialect.table_options()
+        );
+    }
+    sql
+}
+
+/// Generate the INSERT statements of wilayas and of their dairats and baladyiats.
+pub fn inserts(dialect: Dialect, wilayas: &[Wilaya]) -> String {
+    let mut rows = Rows::new(dialect);
+    for wilaya in wilayas {
+        rows.wilaya(
+            wilaya.mattricule,
+            [wilaya.name, wilaya.name_ar, wilaya.name_ber, wilaya.name_en],
+        );
+        rows.codes(
+            wilaya.mattricule,
+            wilaya.phone_codes,
+            wilaya.postal_codes,
+            wilaya.adjacent_wilayas,
+        );
+        for daira in wilaya.dairats {
+            rows.daira(
+                daira.code,
+                wilaya.mattricule,
+                [daira.name, daira.name_ar, daira.name_en],
+            );
+            for baladyia in daira.baladyiats.into_iter().flatten() {
+                let names = [baladyia.name, baladyia.name_ar, baladyia.name_en];
+                rows.baladyia(baladyia.code, daira.code, names);
+            }
+        }
+    }
+    rows.to_sql()
+}
+
+/// Generate a dump of wilayas: the tables, then the INSERT statements in a transaction.
+/// # Example
+/// ```
+/// use leblad::sql::{dump, Dialect};
+/// use leblad::get_wilaya_list;
+/// let sql = dump(Dialect::Sqlite, get_wilaya_list());
+/// assert!(sql.starts_with("-- leblad dataset"));
+/// assert!(sql.contains("INSERT INTO wilayas (code, name, name_ar, name_ber, name_en) VALUES"));
+/// ```
+pub fn dump(dialect: Dialect, wilayas: &[Wilaya]) -> String {
+    let info = dataset_version();
+    let mut sql = format!(
+        "-- leblad dataset {} ({}), {} dialect\n\n",
+        info.revision,
+        info.content_hash,
+        dialect.as_str()
+    );
+    if dialect == Dialect::Sqlite {
+        sql.push_str("PRAGMA foreign_keys = ON;\n\n");
+    }
+    sql.push_str(&schema(dialect));
+    sql.push_str("BEGIN;\n\n");
+    sql.push_str(&inserts(dialect, wilayas));
+    sql.push_str("COMMIT;\n");
+    sql
+}
+
+/// Generate the statements upgrading a database seeded with the `old` snapshot to the `new`
+/// one, in a transaction.
+///
+/// New places are inserted first, then existing ones are updated and removed ones deleted,
+/// children before their parents, so that foreign keys hold after every statement.
+/// # Example
+/// ```
+/// use leblad::diff::Snapshot;
+/// use leblad::sql::{migration, Dialect};
+/// let sql = migration(Dialect::Postgres, &Snapshot::compiled().unwrap(), &Snapshot::default());
+/// assert!(sql.contains("DELETE FROM wilayas WHERE code = 1;"));
+/// ```
+pub fn migration(dialect: Dialect, old: &Snapshot, new: &Snapshot) -> String {
+    let changeset = diff(old, new);
+    let mut rows = Rows::new(dialect);
+    let mut added_wilayas = vec![];
+    let mut updates = String::new();
+    let mut deletes = vec![];
+    for change in changeset.changes() {
+        let (level, code) = (change.level, change.code);
+        let table = table(level);
+        match &change.kind {
+            ChangeKind::Added { .. } => {
+                let Some(record) = new.record(level, code) else {
+                    continue;
+                };
+                let names = [&record.name, &record.name_ar, &record.name_en];
+                match (level, record.parent) {
+                    (Level::Wilaya, _) => {
+                        let name_ber = record.name_ber.as_deref().unwrap_or_default();
+                        rows.wilaya(code, [names[0], names[1], name_ber, names[2]]);
+                        added_wilayas.push((code, record));
+                    }
+                    (Level::Daira, Some(parent)) => {
+                        rows.daira(code, parent, names.map(String::as_str))
+                    }
+                    (Level::Baladyia, Some(parent)) => {
+                        rows.baladyia(code, parent, names.map(String::as_str))
+                    }
+                    (_, None) => {}
+                }
+            }
+            ChangeKind::Renamed { field, to, .. } => {
+                let _ = writeln!(
+                    updates,
+                    "UPDATE {} SET {} = {} WHERE code = {};",
+                    table,
+                    field,
+                    dialect.literal(to),
+                    code
+                );
+            }
+            ChangeKind::Moved { to, .. } => {
+                let column = match level {
+                    Level::Baladyia => "daira_code",
+                    Level::Wilaya | Level::Daira => "wilaya_code",
+                };
+                let _ = writeln!(
+                    updates,
+                    "UPDATE {} SET {} = {} WHERE code = {};",
+                    table, column, to, code
+                );
+            }
+            ChangeKind::CodesChanged {
+                field,
+                added,
+                removed,
+            } => {
+                let (table, column) = code_table(field);
+                for removed in removed {
+                    let _ = writeln!(
+                        updates,
+                        "DELETE FROM {} WHERE wilaya_code = {} AND {} = {};",
+                        table, code, column, removed
+                    );
+                }
+                for added in added {
+                    let _ = writeln!(
+                        updates,
+                        "INSERT INTO {} (wilaya_code, {}) VALUES ({}, {});",
+                        table, column, code, added
+                    );
+                }
+            }
+            ChangeKind::Removed { .. } => deletes.push((level, code)),
+        }
+    }
+    // The codes of new wilayas may refer to each other, so they follow all the new wilayas.
+    for (code, record) in added_wilayas {
+        rows.codes(
+            code,
+            &record.phone_codes,
+            &record.postal_codes,
+            &record.adjacent_wilayas,
+        );
+    }
+    deletes.sort_by_key(|&(level, code)| (std::cmp::Reverse(level), code));
+
+    let mut sql = "BEGIN;\n\n".to_string();
+    sql.push_str(&rows.to_sql());
+    if !updates.is_empty() {
+        sql.push_str(&updates);
+        sql.push('\n');
+    }
+    for (level, code) in &deletes {
+        if *level == Level::Wilaya {
+            for table in [
+                "wilaya_phone_codes",
+                "wilaya_postal_codes",
+                "wilaya_adjacency",
+            ] {
+                let _ = writeln!(sql, "DELETE FROM {} WHERE wilaya_code = {};", table, code);
+            }
+            let _ = writeln!(
+                sql,
+                "DELETE FROM wilaya_adjacency WHERE adjacent_wilaya_code = {};",
+                code
+            );
+        }
+        let _ = writeln!(sql, "DELETE FROM {} WHERE code = {};", table(*level), code);
+    }
+    if !deletes.is_empty() {
+        sql.push('\n');
+    }
+    sql.push_str("COMMIT;\n");
+    sql
+}
+
+fn table(level: Level) -> &'static str {
+    match level {
+        Level::Wilaya => "wilayas",
+        Level::Daira => "dairats",
+        Level::Baladyia => "baladyiats",
+    }
+}
+
+/// Gets the table and column of a field of codes changed in a [`ChangeKind::CodesChanged`].
+fn code_table(field: &str) -> (&'static str, &'static str) {
+    match field {
+        "phone_codes" => ("wilaya_phone_codes", "phone_code"),
+        "postal_codes" => ("wilaya_postal_codes", "postal_code"),
+        _ => ("wilaya_adjacency", "adjacent_wilaya_code"),
+    }
+}
+
+/// Rows to insert in each table, as SQL values.
+struct Rows {
+    dialect: Dialect,
+    wilayas: Vec<String>,
+    dairats: Vec<String>,
+    baladyiats: Vec<String>,
+    phone_codes: Vec<String>,
+    postal_codes: Vec<String>,
+    adjacency: Vec<String>,
+}
+
+impl Rows {
+    fn new(dialect: Dialect) -> Rows {
+        Rows {
+            dialect,
+            wilayas: vec![],
+            dairats: vec![],
+            baladyiats: vec![],
+            phone_codes: vec![],
+            postal_codes: vec![],
+            adjacency: vec![],
+        }
+    }
+
+    fn values(&self, code: u16, parent: Option<u16>, names: &[&str]) -> String {
+        let mut values = vec![code.to_string()];
+        values.extend(parent.map(|parent| parent.to_string()));
+        values.extend(names.iter().map(|name| self.dialect.literal(name)));
+        format!("({})", values.join(", "))
+    }
+
+    /// Adds a wilaya, with its name, Arabic, Berber and English names.
+    fn wilaya(&mut self, mattricule: u16, names: [&str; 4]) {
+        let values = self.values(mattricule, None, &names);
+        self.wilayas.push(values);
+    }
+
+    fn codes(
+        &mut self,
+        mattricule: u16,
+        phone_codes: &[u16],
+        postal_codes: &[u16],
+        adjacent: &[u16],
+    ) {
+        let pairs = |codes: &[u16]| {
+            codes
+                .iter()
+                .map(|code| format!("({}, {})", mattricule, code))
+                .collect::<Vec<String>>()
+        };
+        self.phone_codes.extend(pairs(phone_codes));
+        self.postal_codes.extend(pairs(postal_codes));
+        self.adjacency.extend(pairs(adjacent));
+    }
+
+    /// Adds a daira, with its name, Arabic and English names.
+    fn daira(&mut self, code: u16, mattricule: u16, names: [&str; 3]) {
+        let values = self.values(code, Some(mattricule), &names);
+        self.dairats.push(values);
+    }
+
+    /// Adds a baladyia, with its name, Arabic and English names.
+    fn baladyia(&mut self, code: u16, daira_code: u16, names: [&str; 3]) {
+        let values = self.values(code, Some(daira_code), &names);
+        self.baladyiats.push(values);
+    }
+
+    /// Generates the INSERT statements, parents before their children.
+    fn to_sql(&self) -> String {
+        let tables = [
+            (
+                "wilayas (code, name, name_ar, name_ber, name_en)",
+                &self.wilayas,
+            ),
+            (
+                "dairats (code, wilaya_code, name, name_ar, name_en)",
+                &self.dairats,
+            ),
+            (
+                "baladyiats (code, daira_code, name, name_ar, name_en)",
+                &self.baladyiats,
+            ),
+            (
+                "wilaya_phone_codes (wilaya_code, phone_code)",
+                &self.phone_codes,
+            ),
+            (
+                "wilaya_postal_codes (wilaya_code, postal_code)",
+                &self.postal_codes,
+            ),
+            (
+                "wilaya_adjacency (wilaya_code, adjacent_wilaya_code)",
+                &self.adjacency,
+            ),
+        ];
+        let mut sql = String::new();
+        for (table, rows) in tables {
+            for chunk in rows.chunks(ROWS_PER_INSERT) {
+                let _ = writeln!(
+                    sql,
+                    "INSERT INTO {} VALUES\n    {};\n",
+                    table,
+                    chunk.join(",\n    ")
+                );
+            }
+        }
+        sql
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ALL_WILAYAS;
+
+    fn snapshot(json: &str) -> Snapshot {
+        Snapshot::from_json(json).unwrap()
+    }
+
+    #[test]
+    fn literals_are_escaped() {
+        assert_eq!(Dialect::Postgres.literal("l'Ouest"), "'l''Ouest'");
+        assert_eq!(Dialect::Sqlite.literal("a\\b"), "'a\\b'");
+        assert_eq!(Dialect::MySql.literal("l'a\\b"), "'l''a\\\\b'");
+    }
+
+    #[test]
+    fn schema_per_dialect() {
+        for dialect in Dialect::ALL {
+            let ddl = schema(dialect);
+            assert_eq!(ddl.matches("CREATE TABLE").count(), 6);
+            assert!(ddl.contains(
+                "CREATE TABLE baladyiats (\n    code SMALLINT NOT NULL PRIMARY KEY,\n    \
+                 daira_code SMALLINT NOT NULL,"
+            ));
+            assert!(ddl.contains("FOREIGN KEY (daira_code) REFERENCES dairats (code)\n)"));
+        }
+        assert!(schema(Dialect::MySql).contains("name VARCHAR(255) NOT NULL"));
+        assert!(schema(Dialect::MySql).contains(") ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;"));
+        assert!(schema(Dialect::Postgres).contains("name_ber TEXT NOT NULL"));
+        assert!(!schema(Dialect::Sqlite).contains("ENGINE"));
+    }
+
+    #[test]
+    fn dump_inserts_every_place() {
+        let sql = dump(Dialect::Sqlite, ALL_WILAYAS);
+        assert!(sql.contains("PRAGMA foreign_keys = ON;"));
+        assert!(sql.contains("\n    (1, 'Adrar', '"));
+        assert!(sql.contains("\n    (101, 1, 'ADRAR', '"));
+        assert!(sql.contains("\n    (101, 101, 'ADRAR', '"));
+        assert!(sql.contains(", 101, 'OULED AHMED TIMMI', '"));
+        assert!(sql.contains("(1, 1000)"));
+        assert!(sql.trim_end().ends_with("COMMIT;"));
+        let rows = sql.lines().filter(|line| line.starts_with("    (")).count();
+        let codes = ALL_WILAYAS
+            .iter()
+            .map(|w| 1 + w.phone_codes.len() + w.postal_codes.len() + w.adjacent_wilayas.len())
+            .sum::<usize>();
+        let places = crate::ALL_DAIRATS.len() + crate::ALL_BALADYIATS.len();
+        assert_eq!(rows, codes + places);
+        assert!(!dump(Dialect::Postgres, ALL_WILAYAS).contains("PRAGMA"));
+    }
+
+    #[test]
+    fn large_inserts_are_split() {
+        let mut rows = Rows::new(Dialect::Postgres);
+        for code in 0..ROWS_PER_INSERT as u16 + 1 {
+            rows.baladyia(code, 101, ["", "", ""]);
+        }
+        assert_eq!(rows.to_sql().matches("INSERT INTO baladyiats").count(), 2);
+    }
+
+    #[test]
+    fn migration_keeps_foreign_keys() {
+        let wilaya = |code: u16, name: &str, adjacent: &str, dairats: &str| {
+            format!(
+                r#"{{"mattricule": {code}, "name": "{name}", "name_ar": "", "name_ber": "", "name_en": "{name}",
+                "phoneCodes": [{code}], "postalCodes": [], "adjacentWilayas": [{adjacent}], "dairats": [{dairats}]}}"#
+            )
+        };
+        let daira = |code: u16, baladyiats: &str| {
+            format!(
+                r#"{{"code": {code}, "name": "D", "name_ar": "", "name_en": "D", "baladyiats": [{baladyiats}]}}"#
+            )
+        };
+        let baladyia = |code: u16, name: &str| {
+            format!(r#"{{"code": {code}, "name": "{name}", "name_ar": "", "name_en": "{name}"}}"#)
+        };
+        let old = snapshot(&format!(
+            "[{}, {}]",
+            wilaya(1, "Adrar", "2", &daira(101, &baladyia(101, "ADRAR"))),
+            wilaya(2, "Chlef", "1", &daira(201, &baladyia(201, "CHLEF"))),
+        ));
+        let new = snapshot(&format!(
+            "[{}, {}]",
+            wilaya(1, "Adrar", "49", &daira(101, &baladyia(101, "L'ADRAR"))),
+            wilaya(
+                49,
+                "Timimoun",
+                "1",
+                &daira(4901, &baladyia(4901, "TIMIMOUN"))
+            ),
+        ));
+        let sql = migration(Dialect::Postgres, &old, &new);
+        let expected = "BEGIN;\n\n\
+            INSERT INTO wilayas (code, name, name_ar, name_ber, name_en) VALUES\n    \
+            (49, 'Timimoun', '', '', 'Timimoun');\n\n\
+            INSERT INTO dairats (code, wilaya_code, name, name_ar, name_en) VALUES\n    \
+            (4901, 49, 'D', '', 'D');\n\n\
+            INSERT INTO baladyiats (code, daira_code, name, name_ar, name_en) VALUES\n    \
+            (4901, 4901, 'TIMIMOUN', '', 'TIMIMOUN');\n\n\
+            INSERT INTO wilaya_phone_codes (wilaya_code, phone_code) VALUES\n    (49, 49);\n\n\
+            INSERT INTO wilaya_adjacency (wilaya_code, adjacent_wilaya_code) VALUES\n    (49, 1);\n\n\
+            DELETE FROM wilaya_adjacency WHERE wilaya_code = 1 AND adjacent_wilaya_code = 2;\n\
+            INSERT INTO wilaya_adjacency (wilaya_code, adjacent_wilaya_code) VALUES (1, 49);\n\
+            UPDATE baladyiats SET name = 'L''ADRAR' WHERE code = 101;\n\
+            UPDATE baladyiats SET name_en = 'L''ADRAR' WHERE code = 101;\n\n\
+            DELETE FROM baladyiats WHERE code = 201;\n\
+            DELETE FROM dairats WHERE code = 201;\n\
+            DELETE FROM wilaya_phone_codes WHERE wilaya_code = 2;\n\
+            DELETE FROM wilaya_postal_codes WHERE wilaya_code = 2;\n\
+            DELETE FROM wilaya_adjacency WHERE wilaya_code = 2;\n\
+            DELETE FROM wilaya_adjacency WHERE adjacent_wilaya_code = 2;\n\
+            DELETE FROM wilayas WHERE code = 2;\n\n\
+            COMMIT;\n";
+        assert_eq!(sql, expected);
+        assert_eq!(migration(Dialect::MySql, &old, &old), "BEGIN;\n\nCOMMIT;\n");
+    }
+}
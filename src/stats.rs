@@ -0,0 +1,248 @@
+//! Population, area and density statistics.
+//!
+//! Places carry their own figures in their `stats` field, embedded at build time from
+//! `data/stats.csv`. Figures missing for a place are rolled up from the places it contains:
+//! baladyiats to their daira, and dairats to their wilaya.
+
+use crate::{Baladyia, Daira, Wilaya, ALL_BALADYIATS, ALL_WILAYAS};
+
+/// Statistics of a place.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Stats {
+    /// Population at the 2008 census (RGPH 2008).
+    pub population_2008: Option<u32>,
+    /// Latest population estimate.
+    pub population_estimate: Option<u32>,
+    /// Year of the latest population estimate.
+    pub estimate_year: Option<u16>,
+    /// Surface area, in square kilometers.
+    pub area_km2: Option<f64>,
+}
+
+impl Stats {
+    /// Get the latest known population, which is the estimate if any, or the 2008 census.
+    pub fn population(&self) -> Option<u32> {
+        self.population_estimate.or(self.population_2008)
+    }
+
+    /// Get the density of the latest known population, in inhabitants per square kilometer.
+    /// # Example
+    /// ```
+    /// use leblad::stats::Stats;
+    /// let stats = Stats {
+    ///     population_2008: Some(1_000),
+    ///     population_estimate: Some(1_500),
+    ///     estimate_year: Some(2020),
+    ///     area_km2: Some(10.0),
+    /// };
+    /// assert_eq!(stats.density(), Some(150.0));
+    /// assert_eq!(stats.density_2008(), Some(100.0));
+    /// ```
+    pub fn density(&self) -> Option<f64> {
+        density(self.population(), self.area_km2)
+    }
+
+    /// Get the density of the 2008 census, in inhabitants per square kilometer.
+    pub fn density_2008(&self) -> Option<f64> {
+        density(self.population_2008, self.area_km2)
+    }
+
+    /// Sum the statistics of several places.
+    /// A figure is only summed when it is known for every place, so partial data never
+    /// undercounts. Returns `None` if there are no places or one of them has no statistics.
+    /// # Example
+    /// ```
+    /// use leblad::stats::Stats;
+    /// let a = Stats { population_2008: Some(10), area_km2: Some(1.0), ..Default::default() };
+    /// let b = Stats { population_2008: Some(20), ..Default::default() };
+    /// let sum = Stats::sum([Some(a), Some(b)]).unwrap();
+    /// assert_eq!(sum.population_2008, Some(30));
+    /// assert_eq!(sum.area_km2, None);
+    /// ```
+    pub fn sum<I: IntoIterator<Item = Option<Stats>>>(stats: I) -> Option<Stats> {
+        let stats = stats.into_iter().collect::<Option<Vec<Stats>>>()?;
+        let first = stats.first()?;
+        let estimate_year = first
+            .estimate_year
+            .filter(|year| stats.iter().all(|s| s.estimate_year == Some(*year)));
+        Some(Stats {
+            population_2008: stats.iter().map(|s| s.population_2008).sum(),
+            population_estimate: estimate_year
+                .and_then(|_| stats.iter().map(|s| s.population_estimate).sum()),
+            estimate_year: estimate_year
+                .filter(|_| stats.iter().all(|s| s.population_estimate.is_some())),
+            area_km2: stats.iter().map(|s| s.area_km2).sum(),
+        })
+    }
+}
+
+fn density(population: Option<u32>, area_km2: Option<f64>) -> Option<f64> {
+    match (population, area_km2) {
+        (Some(population), Some(area_km2)) if area_km2 > 0.0 => {
+            Some(f64::from(population) / area_km2)
+        }
+        _ => None,
+    }
+}
+
+/// Get the statistics of a baladyia.
+pub fn baladyia_stats(baladyia: &Baladyia) -> Option<Stats> {
+    baladyia.stats
+}
+
+/// Get the statistics of a daira, summed from its baladyiats if it has none of its own.
+pub fn daira_stats(daira: &Daira) -> Option<Stats> {
+    daira
+        .stats
+        .or_else(|| Stats::sum(daira.baladyiats?.iter().map(baladyia_stats)))
+}
+
+/// Get the statistics of a wilaya, summed from its dairats if it has none of its own.
+/// # Example
+/// ```
+/// use leblad::stats::wilaya_stats;
+/// use leblad::wilaya;
+/// let stats = wilaya_stats(wilaya!(16)).unwrap();
+/// assert_eq!(stats.population_2008, Some(2_988_145));
+/// ```
+pub fn wilaya_stats(wilaya: &Wilaya) -> Option<Stats> {
+    wilaya
+        .stats
+        .or_else(|| Stats::sum(wilaya.dairats.iter().map(daira_stats)))
+}
+
+/// Get the `n` wilayas with the highest density, along with their density.
+/// Wilayas without a known density are left out.
+pub fn top_wilayas_by_density<'a>(n: usize) -> Vec<(&'a Wilaya, f64)> {
+    top_by(ALL_WILAYAS, |wilaya| wilaya_stats(wilaya)?.density(), n)
+}
+
+/// Get the `n` wilayas with the highest population, along with their population.
+/// Wilayas without a known population are left out.
+pub fn top_wilayas_by_population<'a>(n: usize) -> Vec<(&'a Wilaya, u32)> {
+    top_by(ALL_WILAYAS, |wilaya| wilaya_stats(wilaya)?.population(), n)
+}
+
+/// Get the `n` baladyiats with the highest density, along with their density.
+/// Baladyiats without a known density are left out.
+pub fn top_baladyiats_by_density<'a>(n: usize) -> Vec<(&'a Baladyia, f64)> {
+    top_by(
+        ALL_BALADYIATS,
+        |baladyia| baladyia_stats(baladyia)?.density(),
+        n,
+    )
+}
+
+/// Ranks items by decreasing key, keeping the `n` first ones.
+fn top_by<T, K: PartialOrd + Copy>(
+    items: &[T],
+    key: impl Fn(&T) -> Option<K>,
+    n: usize,
+) -> Vec<(&T, K)> {
+    let mut ranked = items
+        .iter()
+        .filter_map(|item| key(item).map(|k| (item, k)))
+        .collect::<Vec<(&T, K)>>();
+    ranked.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+    ranked.truncate(n);
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(population_2008: u32, estimate: Option<(u32, u16)>, area_km2: f64) -> Stats {
+        Stats {
+            population_2008: Some(population_2008),
+            population_estimate: estimate.map(|(population, _)| population),
+            estimate_year: estimate.map(|(_, year)| year),
+            area_km2: Some(area_km2),
+        }
+    }
+
+    #[test]
+    fn density_needs_a_positive_area() {
+        assert_eq!(stats(100, None, 4.0).density(), Some(25.0));
+        assert_eq!(stats(100, None, 0.0).density(), None);
+        assert_eq!(Stats::default().density(), None);
+    }
+
+    #[test]
+    fn sum_of_complete_stats() {
+        let res = Stats::sum([
+            Some(stats(100, Some((120, 2020)), 1.0)),
+            Some(stats(50, Some((60, 2020)), 2.0)),
+        ]);
+        assert_eq!(res, Some(stats(150, Some((180, 2020)), 3.0)));
+    }
+
+    #[test]
+    fn sum_of_estimates_from_different_years() {
+        let res = Stats::sum([
+            Some(stats(100, Some((120, 2020)), 1.0)),
+            Some(stats(50, Some((60, 2018)), 2.0)),
+        ])
+        .unwrap();
+        assert_eq!(res.population_estimate, None);
+        assert_eq!(res.estimate_year, None);
+        assert_eq!(res.population_2008, Some(150));
+    }
+
+    #[test]
+    fn sum_with_missing_stats() {
+        assert_eq!(Stats::sum([Some(stats(1, None, 1.0)), None]), None);
+        assert_eq!(Stats::sum([]), None);
+    }
+
+    #[test]
+    fn ranking_by_decreasing_key() {
+        let items = [1, 5, 3, 4];
+        let res = top_by(&items, |i| if *i == 4 { None } else { Some(*i * 10) }, 2);
+        assert_eq!(res, vec![(&5, 50), (&3, 30)]);
+    }
+
+    #[test]
+    fn stats_roll_up_to_wilaya() {
+        let baladyiats: &'static [Baladyia] = Box::leak(Box::new([
+            Baladyia {
+                stats: Some(stats(100, None, 1.0)),
+                ..Default::default()
+            },
+            Baladyia {
+                stats: Some(stats(50, None, 2.0)),
+                ..Default::default()
+            },
+        ]));
+        let dairats: &'static [Daira] = Box::leak(Box::new([
+            Daira {
+                baladyiats: Some(baladyiats),
+                ..Default::default()
+            },
+            Daira {
+                stats: Some(stats(10, None, 4.0)),
+                ..Default::default()
+            },
+        ]));
+        let mut wilaya = Wilaya {
+            dairats,
+            ..Default::default()
+        };
+        assert_eq!(daira_stats(&dairats[0]), Some(stats(150, None, 3.0)));
+        assert_eq!(wilaya_stats(&wilaya), Some(stats(160, None, 7.0)));
+
+        wilaya.stats = Some(stats(1, None, 1.0));
+        assert_eq!(wilaya_stats(&wilaya), Some(stats(1, None, 1.0)));
+    }
+
+    #[test]
+    fn builtin_wilaya_stats() {
+        let algiers = crate::get_wilaya_by_code(16).unwrap();
+        assert_eq!(
+            wilaya_stats(algiers).unwrap().population_2008,
+            Some(2_988_145)
+        );
+        let res = top_wilayas_by_population(1);
+        assert_eq!(res[0].0.mattricule, 16);
+    }
+}
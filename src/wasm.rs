@@ -0,0 +1,474 @@
+//! WebAssembly bindings, for validating addresses in the browser.
+//!
+//! Places are returned as plain JavaScript objects with camel case fields, e.g.
+//! `{ level: "wilaya", code: 1, name: "Adrar", nameAr: "أدرار", ... }`, and missing places as
+//! `undefined`. The whole dataset is embedded in the module, so lookups work offline.
+//!
+//! The `wasm-release` profile optimizes the module for size:
+//!
+//! ```sh
+//! cargo build --target wasm32-unknown-unknown --profile wasm-release --features wasm
+//! wasm-bindgen --target web --out-dir pkg \
+//!     target/wasm32-unknown-unknown/wasm-release/leblad.wasm
+//! ```
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    get_baladyia_by_code, get_daira_by_code, get_wilaya_by_code, get_wilaya_by_iso_code,
+    get_wilaya_by_phone_code, get_wilaya_by_zip_code, search_level, Baladyia, Daira, Language,
+    Level, Place, Wilaya, ALL_WILAYAS,
+};
+
+/// Default number of results of [`search`] and [`autocomplete`].
+const DEFAULT_LIMIT: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WilayaObject {
+    code: u16,
+    name: &'static str,
+    name_ar: &'static str,
+    name_ber: &'static str,
+    name_en: &'static str,
+    phone_codes: &'static [u16],
+    postal_codes: &'static [u16],
+    adjacent_wilayas: &'static [u16],
+}
+
+impl From<&'static Wilaya> for WilayaObject {
+    fn from(wilaya: &'static Wilaya) -> WilayaObject {
+        WilayaObject {
+            code: wilaya.mattricule,
+            name: wilaya.name,
+            name_ar: wilaya.name_ar,
+            name_ber: wilaya.name_ber,
+            name_en: wilaya.name_en,
+            phone_codes: wilaya.phone_codes,
+            postal_codes: wilaya.postal_codes,
+            adjacent_wilayas: wilaya.adjacent_wilayas,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DairaObject {
+    code: u16,
+    wilaya_code: u16,
+    name: &'static str,
+    name_ar: &'static str,
+    name_en: &'static str,
+}
+
+impl From<&'static Daira> for DairaObject {
+    fn from(daira: &'static Daira) -> DairaObject {
+        DairaObject {
+            code: daira.code,
+            wilaya_code: daira.code / 100,
+            name: daira.name,
+            name_ar: daira.name_ar,
+            name_en: daira.name_en,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BaladyiaObject {
+    code: u16,
+    daira_code: Option<u16>,
+    wilaya_code: u16,
+    name: &'static str,
+    name_ar: &'static str,
+    name_en: &'static str,
+}
+
+impl From<&'static Baladyia> for BaladyiaObject {
+    fn from(baladyia: &'static Baladyia) -> BaladyiaObject {
+        let daira = Place::Baladyia(baladyia).parent();
+        BaladyiaObject {
+            code: baladyia.code,
+            daira_code: daira.map(|daira| daira.code()),
+            wilaya_code: baladyia.code / 100,
+            name: baladyia.name,
+            name_ar: baladyia.name_ar,
+            name_en: baladyia.name_en,
+        }
+    }
+}
+
+/// A place, tagged with its level.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "level", rename_all = "lowercase")]
+enum PlaceObject {
+    Wilaya(WilayaObject),
+    Daira(DairaObject),
+    Baladyia(BaladyiaObject),
+}
+
+impl From<Place<'static>> for PlaceObject {
+    fn from(place: Place<'static>) -> PlaceObject {
+        match place {
+            Place::Wilaya(wilaya) => PlaceObject::Wilaya(wilaya.into()),
+            Place::Daira(daira) => PlaceObject::Daira(daira.into()),
+            Place::Baladyia(baladyia) => PlaceObject::Baladyia(baladyia.into()),
+        }
+    }
+}
+
+/// A suggestion for an address field.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Suggestion {
+    level: &'static str,
+    code: u16,
+    /// Name in the requested language.
+    label: &'static str,
+    /// Name of the wilaya of dairats and baladyiats, to tell homonyms apart.
+    wilaya_label: Option<&'static str>,
+}
+
+/// An invalid field of an address.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct FieldError {
+    /// `wilaya`, `baladyia` or `postalCode`.
+    field: &'static str,
+    message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct Validation {
+    valid: bool,
+    errors: Vec<FieldError>,
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsError> {
+    serde_wasm_bindgen::to_value(value).map_err(|e| JsError::new(&e.to_string()))
+}
+
+fn parse_level(level: Option<String>) -> Result<Option<Level>, JsError> {
+    level
+        .map(|level| Level::parse(&level).ok_or_else(|| JsError::new("unknown level")))
+        .transpose()
+}
+
+/// Get all wilayas.
+#[wasm_bindgen(js_name = getWilayaList)]
+pub fn wilaya_list() -> Result<JsValue, JsError> {
+    to_js(
+        &ALL_WILAYAS
+            .iter()
+            .map(WilayaObject::from)
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Get a wilaya by mattricule.
+#[wasm_bindgen(js_name = getWilayaByCode)]
+pub fn wilaya_by_code(mattricule: u16) -> Result<JsValue, JsError> {
+    to_js(&get_wilaya_by_code(mattricule).map(WilayaObject::from))
+}
+
+/// Get a wilaya by postal code.
+#[wasm_bindgen(js_name = getWilayaByZipCode)]
+pub fn wilaya_by_zip_code(zip_code: u16) -> Result<JsValue, JsError> {
+    to_js(&get_wilaya_by_zip_code(zip_code).map(WilayaObject::from))
+}
+
+/// Get a wilaya by phone code.
+#[wasm_bindgen(js_name = getWilayaByPhoneCode)]
+pub fn wilaya_by_phone_code(phone_code: u16) -> Result<JsValue, JsError> {
+    to_js(&get_wilaya_by_phone_code(phone_code).map(WilayaObject::from))
+}
+
+/// Get a wilaya by ISO 3166-2 code, e.g. `DZ-16`.
+#[wasm_bindgen(js_name = getWilayaByIsoCode)]
+pub fn wilaya_by_iso_code(iso_code: &str) -> Result<JsValue, JsError> {
+    to_js(&get_wilaya_by_iso_code(iso_code).map(WilayaObject::from))
+}
+
+/// Get a daira by code.
+#[wasm_bindgen(js_name = getDairaByCode)]
+pub fn daira_by_code(code: u16) -> Result<JsValue, JsError> {
+    to_js(&get_daira_by_code(code).map(DairaObject::from))
+}
+
+/// Get a baladyia by code.
+#[wasm_bindgen(js_name = getBaladyiaByCode)]
+pub fn baladyia_by_code(code: u16) -> Result<JsValue, JsError> {
+    to_js(&get_baladyia_by_code(code).map(BaladyiaObject::from))
+}
+
+/// Get the dairats of a wilaya, or `undefined` for an unknown wilaya.
+#[wasm_bindgen(js_name = getDairatsForWilaya)]
+pub fn dairats_for_wilaya(mattricule: u16) -> Result<JsValue, JsError> {
+    let dairats = get_wilaya_by_code(mattricule).map(|wilaya| {
+        wilaya
+            .dairats
+            .iter()
+            .map(DairaObject::from)
+            .collect::<Vec<_>>()
+    });
+    to_js(&dairats)
+}
+
+/// Get the baladyiats of a wilaya or daira, or `undefined` for an unknown place.
+#[wasm_bindgen(js_name = getBaladyiats)]
+pub fn baladyiats(level: &str, code: u16) -> Result<JsValue, JsError> {
+    let level = Level::parse(level).ok_or_else(|| JsError::new("unknown level"))?;
+    let baladyiats = Place::get(level, code).map(|place| {
+        baladyiats_in(place)
+            .into_iter()
+            .map(BaladyiaObject::from)
+            .collect::<Vec<_>>()
+    });
+    to_js(&baladyiats)
+}
+
+/// Search places by name or code, optionally of one level only, best matches first.
+#[wasm_bindgen]
+pub fn search(
+    query: &str,
+    level: Option<String>,
+    limit: Option<usize>,
+) -> Result<JsValue, JsError> {
+    let places = search_places(query, parse_level(level)?)
+        .into_iter()
+        .take(limit.unwrap_or(DEFAULT_LIMIT))
+        .map(PlaceObject::from)
+        .collect::<Vec<_>>();
+    to_js(&places)
+}
+
+/// Suggest places of a level for an address field as the user types, with labels in a
+/// language: `fr` (default), `en` or `ar`.
+///
+/// Dairats can be restricted to a wilaya, and baladyiats to a wilaya or a daira, by giving
+/// its code as `parent_code`.
+#[wasm_bindgen]
+pub fn autocomplete(
+    query: &str,
+    level: &str,
+    parent_code: Option<u16>,
+    language: Option<String>,
+    limit: Option<usize>,
+) -> Result<JsValue, JsError> {
+    let level = Level::parse(level).ok_or_else(|| JsError::new("unknown level"))?;
+    let language = match language {
+        Some(language) => {
+            Language::parse(&language).ok_or_else(|| JsError::new("unknown language"))?
+        }
+        None => Language::Fr,
+    };
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    to_js(&suggestions(query, level, parent_code, language, limit))
+}
+
+/// Check that a baladyia and a postal code belong to a wilaya.
+///
+/// Returns `{ valid, errors }`, where each error has the invalid `field` (`wilaya`,
+/// `baladyia` or `postalCode`) and a `message`.
+#[wasm_bindgen(js_name = validateAddress)]
+pub fn validate_address(
+    wilaya_code: u16,
+    baladyia_code: Option<u16>,
+    postal_code: Option<u16>,
+) -> Result<JsValue, JsError> {
+    to_js(&validate(wilaya_code, baladyia_code, postal_code))
+}
+
+fn search_places(query: &str, level: Option<Level>) -> Vec<Place<'static>> {
+    match level {
+        Some(level) => search_level(level, query),
+        None => crate::search(query),
+    }
+}
+
+fn baladyiats_in(place: Place<'static>) -> Vec<&'static Baladyia> {
+    match place {
+        Place::Wilaya(wilaya) => wilaya
+            .dairats
+            .iter()
+            .flat_map(|daira| daira.baladyiats.into_iter().flatten())
+            .collect(),
+        Place::Daira(daira) => daira.baladyiats.into_iter().flatten().collect(),
+        Place::Baladyia(baladyia) => vec![baladyia],
+    }
+}
+
+/// Checks if `place` is `ancestor` or one of its descendants.
+fn is_within(place: Place<'static>, ancestor: Place<'static>) -> bool {
+    let mut current = Some(place);
+    while let Some(place) = current {
+        if place.level() == ancestor.level() && place.code() == ancestor.code() {
+            return true;
+        }
+        current = place.parent();
+    }
+    false
+}
+
+fn suggestions(
+    query: &str,
+    level: Level,
+    parent_code: Option<u16>,
+    language: Language,
+    limit: usize,
+) -> Vec<Suggestion> {
+    // Dairats are within a wilaya, baladyias within a wilaya or daira depending on the code.
+    let parent = match (level, parent_code) {
+        (Level::Wilaya, _) | (_, None) => None,
+        (Level::Daira, Some(code)) => Place::get(Level::Wilaya, code),
+        (Level::Baladyia, Some(code)) if code >= 100 => Place::get(Level::Daira, code),
+        (Level::Baladyia, Some(code)) => Place::get(Level::Wilaya, code),
+    };
+    if level != Level::Wilaya && parent_code.is_some() && parent.is_none() {
+        return vec![];
+    }
+    search_level(level, query)
+        .into_iter()
+        .filter(|place| parent.is_none_or(|parent| is_within(*place, parent)))
+        .take(limit)
+        .map(|place| Suggestion {
+            level: place.level().as_str(),
+            code: place.code(),
+            label: place.name_in(language),
+            wilaya_label: match place {
+                Place::Wilaya(_) => None,
+                _ => get_wilaya_by_code(place.code() / 100)
+                    .map(|wilaya| Place::Wilaya(wilaya).name_in(language)),
+            },
+        })
+        .collect()
+}
+
+fn validate(wilaya_code: u16, baladyia_code: Option<u16>, postal_code: Option<u16>) -> Validation {
+    let mut errors = vec![];
+    let wilaya = get_wilaya_by_code(wilaya_code);
+    if wilaya.is_none() {
+        errors.push(FieldError {
+            field: "wilaya",
+            message: format!("unknown wilaya {}", wilaya_code),
+        });
+    }
+    if let Some(code) = baladyia_code {
+        match get_baladyia_by_code(code) {
+            None => errors.push(FieldError {
+                field: "baladyia",
+                message: format!("unknown baladyia {}", code),
+            }),
+            Some(_) if wilaya.is_some() && code / 100 != wilaya_code => errors.push(FieldError {
+                field: "baladyia",
+                message: format!("baladyia {} is not in wilaya {}", code, wilaya_code),
+            }),
+            Some(_) => {}
+        }
+    }
+    if let (Some(wilaya), Some(code)) = (wilaya, postal_code) {
+        if !wilaya.postal_codes.contains(&code) {
+            errors.push(FieldError {
+                field: "postalCode",
+                message: format!("postal code {} is not in wilaya {}", code, wilaya_code),
+            });
+        }
+    }
+    Validation {
+        valid: errors.is_empty(),
+        errors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn to_json<T: Serialize>(value: &T) -> serde_json::Value {
+        serde_json::to_value(value).unwrap()
+    }
+
+    #[test]
+    fn places_are_plain_objects() {
+        let wilaya = PlaceObject::from(Place::Wilaya(get_wilaya_by_code(1).unwrap()));
+        let json = to_json(&wilaya);
+        assert_eq!(json["level"], "wilaya");
+        assert_eq!(json["nameEn"], "Adrar");
+        assert_eq!(json["postalCodes"][0], 1000);
+        let baladyia = get_baladyia_by_code(101).unwrap();
+        let json = to_json(&BaladyiaObject::from(baladyia));
+        assert_eq!(
+            json,
+            json!({
+                "code": 101,
+                "dairaCode": 101,
+                "wilayaCode": 1,
+                "name": "ADRAR",
+                "nameAr": baladyia.name_ar,
+                "nameEn": baladyia.name_en,
+            })
+        );
+        let daira = PlaceObject::from(Place::Daira(get_daira_by_code(101).unwrap()));
+        assert_eq!(to_json(&daira)["wilayaCode"], 1);
+    }
+
+    #[test]
+    fn suggestions_within_a_parent() {
+        let all = suggestions("a", Level::Baladyia, None, Language::Fr, 10);
+        assert!(all.iter().any(|s| s.code == 101));
+        let within = suggestions("ouled ahmed", Level::Baladyia, Some(101), Language::Fr, 10);
+        assert!(within
+            .iter()
+            .any(|s| s.label == "OULED AHMED TIMMI" && s.wilaya_label == Some("Adrar")));
+        let query = "ouled ahmed timmi";
+        assert!(suggestions(query, Level::Baladyia, Some(16), Language::Fr, 10).is_empty());
+        assert!(suggestions("a", Level::Daira, Some(99), Language::Fr, 10).is_empty());
+        let wilayas = suggestions("ad", Level::Wilaya, Some(1), Language::En, 1);
+        assert_eq!(
+            to_json(&wilayas),
+            json!([
+                { "level": "wilaya", "code": 1, "label": "Adrar", "wilayaLabel": null }
+            ])
+        );
+    }
+
+    #[test]
+    fn addresses_are_validated() {
+        assert_eq!(
+            to_json(&validate(1, Some(101), Some(1000))),
+            json!({ "valid": true, "errors": [] })
+        );
+        let validation = validate(16, Some(101), Some(1000));
+        let fields = validation
+            .errors
+            .iter()
+            .map(|e| e.field)
+            .collect::<Vec<_>>();
+        assert_eq!(fields, ["baladyia", "postalCode"]);
+        assert_eq!(
+            validation.errors[0].message,
+            "baladyia 101 is not in wilaya 16"
+        );
+        let validation = validate(100, Some(1), None);
+        assert!(!validation.valid);
+        assert_eq!(validation.errors.len(), 2);
+    }
+
+    #[test]
+    fn baladyiats_of_places() {
+        let adrar = baladyiats_in(Place::get(Level::Wilaya, 1).unwrap());
+        let names = adrar.iter().map(|b| b.name).collect::<Vec<_>>();
+        assert_eq!(names[0], "ADRAR");
+        assert!(names.contains(&"OULED AHMED TIMMI"));
+        let daira = baladyiats_in(Place::get(Level::Daira, 101).unwrap());
+        assert!(daira.iter().all(|baladyia| adrar.contains(baladyia)));
+        let baladyia = get_baladyia_by_code(101).unwrap();
+        assert_eq!(baladyiats_in(Place::Baladyia(baladyia)), vec![baladyia]);
+        let unknown: &'static Daira = Box::leak(Box::default());
+        assert!(baladyiats_in(Place::Daira(unknown)).is_empty());
+        let results = search_places("timimoun", Some(Level::Baladyia));
+        assert!(results.iter().all(|place| place.level() == Level::Baladyia));
+    }
+}
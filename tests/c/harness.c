@@ -0,0 +1,101 @@
+/*
+ * Checks the C bindings against the shared library, run by `tests/ffi.rs`:
+ *
+ *     cargo build --features ffi
+ *     cc tests/c/harness.c -Iinclude -Ltarget/debug -lleblad -o harness
+ *     LD_LIBRARY_PATH=target/debug ./harness
+ */
+#include <stdio.h>
+#include <string.h>
+
+#include "leblad.h"
+
+static int failures = 0;
+
+#define CHECK(condition)                                                   \
+  do {                                                                     \
+    if (!(condition)) {                                                    \
+      fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__,     \
+              #condition);                                                 \
+      failures++;                                                          \
+    }                                                                      \
+  } while (0)
+
+static int equals(LebladStr s, const char *expected) {
+  return s.len == strlen(expected) && memcmp(s.ptr, expected, s.len) == 0;
+}
+
+static void lookups(void) {
+  const LebladWilaya *adrar = leblad_wilaya_by_code(1);
+  CHECK(adrar != NULL);
+  CHECK(leblad_wilaya_code(adrar) == 1);
+  CHECK(equals(leblad_wilaya_name(adrar), "Adrar"));
+  CHECK(leblad_wilaya_name_ar(adrar).len > 0);
+  CHECK(leblad_wilaya_by_zip_code(1000) == adrar);
+  CHECK(leblad_wilaya_by_name("adrar") == adrar);
+  CHECK(leblad_wilaya_by_code(100) == NULL);
+
+  LebladCodes phone_codes = leblad_wilaya_phone_codes(adrar);
+  CHECK(phone_codes.len > 0);
+  CHECK(leblad_wilaya_by_phone_code(phone_codes.ptr[0]) == adrar);
+
+  const LebladDaira *daira = leblad_daira_by_code(101);
+  CHECK(equals(leblad_daira_name(daira), "ADRAR"));
+  CHECK(leblad_daira_wilaya(daira) == adrar);
+
+  const LebladBaladyia *baladyia = leblad_baladyia_by_name("OULED AHMED TIMMI");
+  CHECK(baladyia != NULL);
+  CHECK(leblad_baladyia_daira(baladyia) == daira);
+  CHECK(leblad_baladyia_by_code(leblad_baladyia_code(baladyia)) == baladyia);
+}
+
+static void iteration(void) {
+  size_t count = leblad_wilaya_count();
+  CHECK(count > 0);
+  CHECK(leblad_wilaya_at(count) == NULL);
+  for (size_t i = 0; i < count; i++) {
+    const LebladWilaya *wilaya = leblad_wilaya_at(i);
+    CHECK(leblad_wilaya_by_code(leblad_wilaya_code(wilaya)) == wilaya);
+    for (size_t j = 0; j < leblad_wilaya_daira_count(wilaya); j++) {
+      const LebladDaira *daira = leblad_wilaya_daira_at(wilaya, j);
+      CHECK(leblad_daira_wilaya(daira) == wilaya);
+      for (size_t k = 0; k < leblad_daira_baladyia_count(daira); k++) {
+        const LebladBaladyia *baladyia = leblad_daira_baladyia_at(daira, k);
+        CHECK(leblad_baladyia_daira(baladyia) == daira);
+      }
+    }
+  }
+}
+
+static void search(void) {
+  LebladPlaces places = leblad_search("adrar", 3);
+  CHECK(places.len > 0 && places.len <= 3);
+  CHECK(places.ptr[0].level == LEBLAD_LEVEL_WILAYA);
+  CHECK(places.ptr[0].code == 1);
+  for (size_t i = 0; i < places.len; i++) {
+    const LebladPlace place = places.ptr[i];
+    const LebladWilaya *wilaya = leblad_wilaya_by_code(place.code);
+    const LebladDaira *daira = leblad_daira_by_code(place.code);
+    const LebladBaladyia *baladyia = leblad_baladyia_by_code(place.code);
+    CHECK((place.level == LEBLAD_LEVEL_WILAYA && wilaya != NULL) ||
+          (place.level == LEBLAD_LEVEL_DAIRA && daira != NULL) ||
+          (place.level == LEBLAD_LEVEL_BALADYIA && baladyia != NULL));
+  }
+  leblad_places_free(places);
+
+  places = leblad_search_level(LEBLAD_LEVEL_DAIRA, "nothing like this", 10);
+  CHECK(places.ptr == NULL && places.len == 0);
+  leblad_places_free(places);
+}
+
+int main(void) {
+  lookups();
+  iteration();
+  search();
+  if (failures > 0) {
+    fprintf(stderr, "%d checks failed\n", failures);
+    return 1;
+  }
+  printf("all checks passed\n");
+  return 0;
+}
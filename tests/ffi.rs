@@ -0,0 +1,53 @@
+//! Builds and runs the C harness of `tests/c` against the shared library.
+#![cfg(feature = "ffi")]
+
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn header_is_up_to_date() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/leblad.h"));
+    let committed = include_str!("../include/leblad.h");
+    assert!(
+        generated == committed,
+        "include/leblad.h is outdated, copy it from {}",
+        concat!(env!("OUT_DIR"), "/leblad.h")
+    );
+}
+
+#[test]
+fn c_harness() {
+    let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // Tests run from `target/<profile>/deps`, where cargo builds the shared library too.
+    let exe = std::env::current_exe().unwrap();
+    let lib_dir = exe.parent().unwrap();
+    let harness = lib_dir.join("leblad-harness");
+    let compiler = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+
+    let status = Command::new(compiler)
+        .arg(manifest.join("tests/c/harness.c"))
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(manifest.join("include"))
+        .arg("-L")
+        .arg(lib_dir)
+        .arg("-lleblad")
+        .arg("-o")
+        .arg(&harness)
+        .status()
+        .expect("a C compiler to build the harness");
+    assert!(status.success(), "the harness does not compile");
+
+    let output = Command::new(&harness)
+        .env("LD_LIBRARY_PATH", lib_dir)
+        .env("DYLD_LIBRARY_PATH", lib_dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,94 @@
+"""Tests of the Python bindings, run with `pytest` after `maturin develop`."""
+
+import pytest
+
+import leblad
+
+
+def test_wilaya_lookups():
+    adrar = leblad.get_wilaya_by_code(1)
+    assert isinstance(adrar, leblad.Wilaya)
+    assert adrar.code == 1
+    assert adrar.name == "Adrar"
+    assert repr(adrar) == 'Wilaya(1, "Adrar")'
+    assert 1000 in adrar.postal_codes
+    assert leblad.get_wilaya_by_zip_code(1000) == adrar
+    assert leblad.get_wilaya_by_phone_code(adrar.phone_codes[0]) == adrar
+    assert leblad.get_wilaya_by_iso_code("DZ-01") == adrar
+    assert leblad.get_wilaya_by_code(100) is None
+    assert leblad.get_wilaya_list()[0] == adrar
+    with pytest.raises(OverflowError):
+        leblad.get_wilaya_by_code(-1)
+
+
+def test_navigation():
+    adrar = leblad.get_wilaya_by_code(1)
+    daira = adrar.dairats[0]
+    assert isinstance(daira, leblad.Daira)
+    assert daira == leblad.get_daira_by_code(101)
+    assert daira.name == "ADRAR"
+    assert daira.wilaya == adrar
+    baladyia = daira.baladyiats[0]
+    assert isinstance(baladyia, leblad.Baladyia)
+    assert baladyia == leblad.get_baladyia_by_code(101)
+    assert baladyia.daira == daira
+    assert baladyia.wilaya == adrar
+    assert baladyia in adrar.baladyiats
+    assert len({adrar, leblad.get_wilaya_by_code(1)}) == 1
+    assert leblad.get_daira_by_code(1000) is None
+
+
+def test_lookup_and_search():
+    places = leblad.lookup("baladyia", "ouled ahmed timmi")
+    assert places
+    assert all(place.name == "OULED AHMED TIMMI" for place in places)
+    assert leblad.get_daira_by_code(101) in [place.daira for place in places]
+    assert leblad.lookup("wilaya", "1") == [leblad.get_wilaya_by_code(1)]
+
+    results = leblad.search("adrar")
+    assert 1 < len(results) <= 10
+    assert results[0] == leblad.get_wilaya_by_code(1)
+    assert len(leblad.search("adrar", limit=1)) == 1
+    dairats = leblad.search("adrar", level="daira")
+    assert all(isinstance(place, leblad.Daira) for place in dairats)
+    assert leblad.search("zzzz") == []
+    with pytest.raises(ValueError, match="unknown level"):
+        leblad.search("adrar", level="commune")
+
+
+def test_records():
+    wilayas = leblad.records("wilaya")
+    assert len(wilayas) == len(leblad.get_wilaya_list())
+    assert wilayas[0]["wilaya_code"] == 1
+    assert wilayas[0]["wilaya_name"] == "Adrar"
+    assert "daira_code" not in wilayas[0]
+
+    baladyiats = leblad.records()
+    first = baladyiats[0]
+    assert list(first) == [
+        "wilaya_code",
+        "wilaya_name",
+        "wilaya_name_ar",
+        "wilaya_name_ber",
+        "wilaya_name_en",
+        "daira_code",
+        "daira_name",
+        "daira_name_ar",
+        "daira_name_en",
+        "baladyia_code",
+        "baladyia_name",
+        "baladyia_name_ar",
+        "baladyia_name_en",
+    ]
+    assert (first["daira_code"], first["baladyia_name"]) == (101, "ADRAR")
+    with pytest.raises(ValueError):
+        leblad.records("region")
+
+
+def test_records_load_into_pandas():
+    pandas = pytest.importorskip("pandas")
+    frame = pandas.DataFrame(leblad.records("daira"))
+    assert len(frame) == len(leblad.records("daira"))
+    assert frame.groupby("wilaya_code").size()[1] == len(
+        leblad.get_wilaya_by_code(1).dairats
+    )